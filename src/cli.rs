@@ -1,9 +1,14 @@
 //! Clap argument definitions.
 
+use std::path::PathBuf;
+use std::time::Duration;
+
 use clap::{Parser, Subcommand};
 use clap_complete::Shell;
 
-use crate::core::Status;
+use crate::commands::export::{CsvColumn, DataFormat};
+use crate::core::duration::parse_duration;
+use crate::core::{Priority, Status};
 
 /// Git-backed issue tracker for multi-agent coordination.
 #[derive(Parser, Debug)]
@@ -39,6 +44,22 @@ pub enum Commands {
         /// Issue IDs this issue depends on (comma-separated)
         #[arg(short, long, value_delimiter = ',')]
         depends_on: Vec<u32>,
+
+        /// How urgent the issue is (overrides the template's default)
+        #[arg(short, long)]
+        priority: Option<Priority>,
+
+        /// Label to add (repeatable; overrides the template's default labels)
+        #[arg(short, long = "label")]
+        labels: Vec<String>,
+
+        /// Template from .itack/templates/ (opens the editor on it unless --body is given)
+        #[arg(short, long)]
+        template: Option<String>,
+
+        /// Open the editor on the new issue before committing it
+        #[arg(long, conflicts_with = "body")]
+        edit: bool,
     },
 
     /// Show issue details
@@ -65,16 +86,39 @@ pub enum Commands {
         message: Option<String>,
     },
 
+    /// Set issue fields without opening an editor
+    Set {
+        /// Issue ID
+        id: u32,
+
+        /// Fields as key=value: title, epic, status, assignee, branch, session, priority,
+        /// labels (comma-separated). An empty value clears the field
+        #[arg(required = true, value_name = "KEY=VALUE")]
+        fields: Vec<String>,
+
+        /// Start the issue even if issues it depends on are still open
+        #[arg(long)]
+        ignore_deps: bool,
+    },
+
     /// Mark issue as done
     Done {
         /// Issue ID
         id: u32,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
     },
 
     /// Mark issue as wont-fix
     WontFix {
         /// Issue ID
         id: u32,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
     },
 
     /// Claim an issue for an assignee
@@ -82,12 +126,28 @@ pub enum Commands {
         /// Issue ID
         id: u32,
 
-        /// Assignee name
-        assignee: String,
+        /// Assignee name (defaults to ITACK_AGENT, config default_assignee, or git user.name)
+        assignee: Option<String>,
 
         /// Session ID (e.g., Claude Code session working on this issue)
         #[arg(short, long)]
         session: Option<String>,
+
+        /// Wait for an existing claim to be released or expire instead of failing
+        #[arg(long)]
+        wait: bool,
+
+        /// Wait until every issue this one depends on is closed
+        #[arg(long)]
+        wait_deps: bool,
+
+        /// Claim even if issues this one depends on are still open
+        #[arg(long, conflicts_with = "wait_deps")]
+        ignore_deps: bool,
+
+        /// Give up waiting after this long (e.g. 30s, 10m, 1h)
+        #[arg(long, value_parser = parse_duration)]
+        timeout: Option<Duration>,
     },
 
     /// Release a claimed issue
@@ -96,6 +156,69 @@ pub enum Commands {
         id: u32,
     },
 
+    /// Claim an issue and create a worktree and branch to work on it
+    Start {
+        /// Issue ID
+        id: u32,
+
+        /// Assignee name (defaults to ITACK_AGENT, config default_assignee, or git user.name)
+        assignee: Option<String>,
+
+        /// Session ID (e.g., Claude Code session working on this issue)
+        #[arg(short, long)]
+        session: Option<String>,
+
+        /// Claim even if issues this one depends on are still open
+        #[arg(long)]
+        ignore_deps: bool,
+    },
+
+    /// Mark an issue started with `itack start` as done and drop its claim
+    Finish {
+        /// Issue ID
+        id: u32,
+
+        /// Remove the issue's worktree if its branch is merged into the mainline
+        #[arg(long)]
+        remove_worktree: bool,
+    },
+
+    /// Add a comment to an issue
+    Comment {
+        /// Issue ID
+        id: u32,
+
+        /// Comment text (markdown)
+        text: String,
+
+        /// Comment author (defaults to the current agent identity)
+        #[arg(long)]
+        author: Option<String>,
+    },
+
+    /// Show the next issue that is ready to work on (lowest ID first)
+    Next {
+        /// Only consider issues in this epic
+        #[arg(short, long)]
+        epic: Option<String>,
+
+        /// Claim the issue as well
+        #[arg(long)]
+        claim: bool,
+
+        /// Assignee for the claim (defaults to the current agent identity)
+        #[arg(long, requires = "claim")]
+        assignee: Option<String>,
+
+        /// Session ID for the claim
+        #[arg(short, long, requires = "claim")]
+        session: Option<String>,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
     /// Add dependencies to an issue
     Depend {
         /// Issue ID
@@ -123,6 +246,117 @@ pub enum Commands {
         session: String,
     },
 
+    /// Show the current agent identity
+    Whoami,
+
+    /// List known agents with their claims, sessions and branches
+    Agents {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Apply newline-delimited JSON operations from stdin in a single commit
+    Batch {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Close and link issues mentioned in commit messages (`closes #12`, `refs #7`)
+    ScanCommits {
+        /// Revision range (e.g. main..HEAD) or a single commit
+        range: String,
+    },
+
+    /// Link code commits to an issue
+    LinkCommit {
+        /// Issue ID
+        id: u32,
+
+        /// Commit to link (defaults to the commits on the issue's branch that mention #ID)
+        commit: Option<String>,
+    },
+
+    /// Manage git hooks that run itack
+    Hook {
+        #[command(subcommand)]
+        action: HookAction,
+    },
+
+    /// Run a Model Context Protocol (MCP) server over stdio
+    Mcp,
+
+    /// Export a read-only static HTML site of the tracker
+    ExportHtml {
+        /// Output directory
+        dir: PathBuf,
+    },
+
+    /// Export every issue for backup or transfer
+    Export {
+        /// Output format
+        #[arg(long, value_enum, default_value = "jsonl")]
+        format: DataFormat,
+
+        /// Include each issue's data branch commit history (JSONL only)
+        #[arg(long)]
+        history: bool,
+
+        /// CSV columns, in order (default: id,title,status,epic,assignee,depends_on)
+        #[arg(long, value_enum, value_delimiter = ',')]
+        columns: Vec<CsvColumn>,
+
+        /// Write to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Import issues from an export or another tracker
+    #[command(args_conflicts_with_subcommands = true)]
+    Import {
+        #[command(subcommand)]
+        source: Option<ImportSource>,
+
+        /// Format of FILE
+        #[arg(long, value_enum, requires = "file")]
+        format: Option<DataFormat>,
+
+        /// File written by `itack export` (- for stdin)
+        #[arg(requires = "format")]
+        file: Option<PathBuf>,
+
+        /// Give imported issues new IDs, remapping dependencies, instead of keeping theirs (JSONL only)
+        #[arg(long)]
+        renumber: bool,
+
+        /// Show what would change without committing (CSV only)
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Serve a local HTTP REST API with a server-sent events feed
+    Serve {
+        /// Address to listen on
+        #[arg(long, default_value = "127.0.0.1:7420")]
+        bind: String,
+    },
+
+    /// Stream issue events from the data branch as newline-delimited JSON
+    Watch {
+        /// Resume after this data branch commit (the `commit` of the last event seen)
+        #[arg(long)]
+        since: Option<String>,
+
+        /// How often to poll the data branch (e.g. 1s, 30s)
+        #[arg(long, value_parser = parse_duration, default_value = "1s")]
+        interval: Duration,
+
+        /// Print pending events and exit instead of watching
+        #[arg(long)]
+        once: bool,
+    },
+
     /// List issues
     List {
         /// Filter by status
@@ -137,6 +371,18 @@ pub enum Commands {
         #[arg(short, long)]
         assignee: Option<String>,
 
+        /// Only show open issues whose dependencies are all closed
+        #[arg(long, conflicts_with = "blocked")]
+        ready: bool,
+
+        /// Only show issues waiting on unfinished dependencies
+        #[arg(long)]
+        blocked: bool,
+
+        /// Also list issues moved to .itack/archive/ by `itack archive`
+        #[arg(long)]
+        include_archived: bool,
+
         /// Output as JSON
         #[arg(long)]
         json: bool,
@@ -149,6 +395,36 @@ pub enum Commands {
         json: bool,
     },
 
+    /// Delete an issue and remove it from other issues' dependencies
+    Delete {
+        /// Issue ID
+        id: u32,
+    },
+
+    /// Move an issue to another itack project, leaving a wont-fix tombstone behind
+    Move {
+        /// Issue ID
+        id: u32,
+
+        /// Path to the target project's repository
+        #[arg(long)]
+        to: PathBuf,
+    },
+
+    /// Move issues closed more than N days ago into .itack/archive/
+    Archive {
+        /// Archive issues whose file hasn't changed for this many days
+        #[arg(long, default_value_t = 30)]
+        days: u32,
+    },
+
+    /// Find in-progress issues whose branch is merged into the mainline and offer to close them
+    Reconcile {
+        /// Mark them done and release their claims without asking
+        #[arg(short, long)]
+        yes: bool,
+    },
+
     /// Check database health and issue synchronization
     Doctor,
 
@@ -161,6 +437,10 @@ pub enum Commands {
         #[arg(short, long)]
         all_branches: bool,
 
+        /// Also search issues moved to .itack/archive/ by `itack archive`
+        #[arg(long, conflicts_with = "all_branches")]
+        include_archived: bool,
+
         /// Output as JSON
         #[arg(long)]
         json: bool,
@@ -172,3 +452,28 @@ pub enum Commands {
         shell: Shell,
     },
 }
+
+/// Actions for `itack hook`.
+#[derive(Subcommand, Debug)]
+pub enum HookAction {
+    /// Install a post-commit hook that runs `itack scan-commits` on each new commit
+    Install {
+        /// Also install a post-merge hook that scans merged commits
+        #[arg(long)]
+        post_merge: bool,
+
+        /// Overwrite existing hooks not installed by itack
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+/// Trackers that `itack import` can read from.
+#[derive(Subcommand, Debug)]
+pub enum ImportSource {
+    /// Import a GitHub issues JSON export (`gh issue list --json ...` or an API dump)
+    Github {
+        /// JSON file containing an array of issues
+        file: PathBuf,
+    },
+}
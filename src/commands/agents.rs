@@ -0,0 +1,31 @@
+//! itack agents command.
+
+use crate::core::Project;
+use crate::error::Result;
+use crate::output::{self, OutputFormat};
+
+/// Arguments for the agents command.
+pub struct AgentsArgs {
+    pub format: OutputFormat,
+}
+
+/// List known agents, active ones (holding claims) first.
+pub fn run(args: AgentsArgs) -> Result<()> {
+    let project = Project::discover()?;
+    let db = project.open_db()?;
+
+    let mut agents = db.list_agents()?;
+    // Stable sort keeps the most recently seen first within each group
+    agents.sort_by_key(|agent| !agent.is_active());
+
+    match args.format {
+        OutputFormat::Table => {
+            output::print_agents_table(&agents);
+        }
+        OutputFormat::Json => {
+            output::print_agents_json(&agents)?;
+        }
+    }
+
+    Ok(())
+}
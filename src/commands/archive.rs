@@ -0,0 +1,97 @@
+//! itack archive command: move old closed issues out of `.itack/`.
+
+use std::path::Path;
+
+use chrono::{Duration, Utc};
+
+use crate::core::git::dir_history;
+use crate::core::{Project, TreeEdit};
+use crate::error::Result;
+use crate::storage::db::{ARCHIVE_DIR, IssueInfo, load_all_issues_from_data_branch};
+
+/// Arguments for the archive command.
+#[derive(Debug)]
+pub struct ArchiveArgs {
+    /// Archive issues closed at least this many days ago.
+    pub days: u32,
+}
+
+/// Archive old closed issues and print which.
+pub fn run(args: ArchiveArgs) -> Result<()> {
+    let project = Project::discover()?;
+    let archived = execute(&project, &args)?;
+
+    if archived.is_empty() {
+        println!("No issues closed more than {} days ago", args.days);
+        return Ok(());
+    }
+    for info in &archived {
+        println!("Archived issue #{}: {}", info.issue.id, info.title);
+    }
+
+    Ok(())
+}
+
+/// Move done and wont-fix issues whose file hasn't changed for `days` days into
+/// `.itack/archive/` in one commit. Archived issues are left out of `list` and
+/// dependency checks but can still be shown, edited and listed with
+/// `--include-archived`; reopening one moves it back. Returns the archived issues.
+pub fn execute(project: &Project, args: &ArchiveArgs) -> Result<Vec<IssueInfo>> {
+    let data_branch = project
+        .config
+        .data_branch
+        .as_deref()
+        .unwrap_or("data/itack");
+
+    let issues = load_all_issues_from_data_branch(&project.repo_root, data_branch)?;
+    let history = dir_history(&project.repo_root, data_branch, Path::new(".itack"))?;
+    let cutoff = Utc::now() - Duration::days(i64::from(args.days));
+
+    let mut archived: Vec<IssueInfo> = issues
+        .into_iter()
+        .filter(|info| info.issue.status.is_closed())
+        .filter(|info| {
+            let name = info.relative_path.file_name().unwrap_or_default();
+            history
+                .get(&*name.to_string_lossy())
+                .and_then(|commits| commits.first())
+                .is_some_and(|last_change| last_change.time <= cutoff)
+        })
+        .collect();
+    if archived.is_empty() {
+        return Ok(archived);
+    }
+    archived.sort_by_key(|info| info.issue.id);
+
+    let mut edit = TreeEdit::new();
+    for info in &mut archived {
+        let name = info
+            .relative_path
+            .file_name()
+            .unwrap_or_default()
+            .to_owned();
+        let archived_path = Path::new(ARCHIVE_DIR).join(name);
+        edit.rename(&info.relative_path, &archived_path);
+        info.relative_path = archived_path;
+    }
+    let message = format!("Archive {} closed issues", archived.len());
+    edit.commit(&project.repo_root, data_branch, &message)?;
+
+    Ok(archived)
+}
+
+/// Stage moving a reopened issue back out of `.itack/archive/`, so `list` and
+/// dependency checks see it again, and point `info` at its new path. Does
+/// nothing for closed issues or issues that aren't archived.
+pub fn unarchive_reopened(edit: &mut TreeEdit, info: &mut IssueInfo) {
+    if info.issue.status.is_closed() || !info.relative_path.starts_with(ARCHIVE_DIR) {
+        return;
+    }
+    let name = info
+        .relative_path
+        .file_name()
+        .unwrap_or_default()
+        .to_owned();
+    edit.remove(&info.relative_path);
+    info.relative_path = Path::new(".itack").join(name);
+}
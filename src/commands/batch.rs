@@ -0,0 +1,591 @@
+//! itack batch command: apply many mutations in a single data branch commit.
+//!
+//! Operations are read from stdin as newline-delimited JSON, e.g.
+//!
+//! ```text
+//! {"op": "create", "title": "Parser", "epic": "v1"}
+//! {"op": "create", "title": "Docs", "depends_on": ["$1"]}
+//! {"op": "claim", "id": "$1", "assignee": "agent-1"}
+//! ```
+//!
+//! `$N` refers to the issue made by the Nth `create` of the batch. Every
+//! operation is applied to an in-memory copy of the issues first, so a failing
+//! operation (or a rejecting pre-hook) leaves the data branch and claims untouched.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::io::BufRead;
+
+use chrono::Utc;
+use serde::Deserialize;
+
+use crate::commands::claim::apply_claim;
+use crate::commands::done::cascade_unblocked;
+use crate::core::deps::open_dependencies;
+use crate::core::hooks::{self, HookEvent};
+use crate::core::{Issue, Project, Status, TreeEdit};
+use crate::error::{ItackError, Result};
+use crate::output::{self, OutputFormat};
+use crate::storage::Database;
+use crate::storage::db::{IssueInfo, load_all_issues_from_data_branch};
+use crate::storage::markdown::{append_comment, format_issue};
+
+/// Arguments for the batch command.
+pub struct BatchArgs {
+    pub format: OutputFormat,
+}
+
+/// A reference to an issue: an ID (`3`, `"#3"`) or a placeholder (`"$1"`).
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+enum IssueRef {
+    Id(u32),
+    Text(String),
+}
+
+impl IssueRef {
+    fn resolve(&self, created: &[u32]) -> Result<u32> {
+        match self {
+            IssueRef::Id(id) => Ok(*id),
+            IssueRef::Text(text) => match text.strip_prefix('$') {
+                Some(n) => n
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|n| created.get(n.checked_sub(1)?))
+                    .copied()
+                    .ok_or_else(|| {
+                        ItackError::Other(format!("{} does not refer to an earlier create", text))
+                    }),
+                None => text
+                    .trim_start_matches('#')
+                    .parse()
+                    .map_err(|_| ItackError::Other(format!("Invalid issue reference '{}'", text))),
+            },
+        }
+    }
+}
+
+/// One batch operation.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+enum Operation {
+    Create {
+        title: String,
+        epic: Option<String>,
+        body: Option<String>,
+        #[serde(default)]
+        depends_on: Vec<IssueRef>,
+    },
+    Edit {
+        id: IssueRef,
+        title: Option<String>,
+        body: Option<String>,
+        /// An empty string clears the epic.
+        epic: Option<String>,
+    },
+    Comment {
+        id: IssueRef,
+        text: String,
+        author: Option<String>,
+    },
+    Claim {
+        id: IssueRef,
+        assignee: Option<String>,
+        session: Option<String>,
+        #[serde(default)]
+        ignore_deps: bool,
+    },
+    Release {
+        id: IssueRef,
+    },
+    Done {
+        id: IssueRef,
+    },
+    #[serde(alias = "wont-fix")]
+    WontFix {
+        id: IssueRef,
+    },
+    Depend {
+        id: IssueRef,
+        deps: Vec<IssueRef>,
+    },
+    Undepend {
+        id: IssueRef,
+        deps: Vec<IssueRef>,
+    },
+}
+
+/// Result of a batch.
+pub struct BatchResult {
+    /// Number of operations applied.
+    pub operations: usize,
+    /// Issues made by `create` operations; `$N` is `created[N - 1]`.
+    pub created: Vec<IssueInfo>,
+    /// Every issue the batch changed, in ID order.
+    pub changed: Vec<IssueInfo>,
+}
+
+/// Apply operations from stdin in one commit.
+pub fn run(args: BatchArgs) -> Result<()> {
+    let project = Project::discover()?;
+
+    let mut operations = Vec::new();
+    for (i, line) in std::io::stdin().lock().lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let operation = serde_json::from_str(&line)
+            .map_err(|e| ItackError::Other(format!("Line {}: {}", i + 1, e)))?;
+        operations.push((i + 1, operation));
+    }
+
+    let result = execute(&project, operations)?;
+
+    match args.format {
+        OutputFormat::Table => {
+            for (i, info) in result.created.iter().enumerate() {
+                println!("${} -> #{}: {}", i + 1, info.issue.id, info.title);
+            }
+            println!(
+                "Applied {} operations to {} issues in one commit",
+                result.operations,
+                result.changed.len()
+            );
+        }
+        OutputFormat::Json => {
+            output::print_batch_json(&result.created, &result.changed)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Apply `(line number, operation)` pairs to the data branch in one commit.
+fn execute(project: &Project, operations: Vec<(usize, Operation)>) -> Result<BatchResult> {
+    if operations.is_empty() {
+        return Err(ItackError::Other("No operations given".to_string()));
+    }
+
+    let data_branch = project
+        .config
+        .data_branch
+        .as_deref()
+        .unwrap_or("data/itack");
+
+    // A batch can create the first issues of a project; unreadable issues are an error
+    let issues = match load_all_issues_from_data_branch(&project.repo_root, data_branch) {
+        Ok(issues) => issues,
+        Err(ItackError::DataBranchNotFound(_) | ItackError::DataBranchEmpty(_)) => Vec::new(),
+        Err(e) => return Err(e),
+    }
+    .into_iter()
+    .map(|info| (info.issue.id, info))
+    .collect();
+
+    let mut db = project.open_db()?;
+    let creates = operations
+        .iter()
+        .filter(|(_, op)| matches!(op, Operation::Create { .. }))
+        .count() as u32;
+    let first_id = if creates > 0 {
+        db.allocate_issue_ids(creates)?
+    } else {
+        0
+    };
+    // A batch that is rejected before its commit doesn't use up any IDs
+    let give_back_ids = |db: &Database| {
+        if creates > 0 {
+            let _ = db.unallocate_issue_ids(first_id, creates);
+        }
+    };
+    let mut batch = Batch {
+        project,
+        issues,
+        next_id: first_id,
+        created: Vec::new(),
+        changed: BTreeSet::new(),
+        initial_assignees: HashMap::new(),
+        released: BTreeSet::new(),
+        closed: Vec::new(),
+        post_hooks: Vec::new(),
+    };
+
+    let count = operations.len();
+    for (line, operation) in operations {
+        if let Err(e) = batch.apply(operation) {
+            give_back_ids(&db);
+            return Err(ItackError::Other(format!("Line {}: {}", line, e)));
+        }
+    }
+
+    let changed: Vec<IssueInfo> = batch
+        .changed
+        .iter()
+        .map(|id| batch.issues[id].clone())
+        .collect();
+
+    // Claims are taken before committing so the claim policy can still veto the batch
+    let undo = match batch.sync_claims(&mut db) {
+        Ok(undo) => undo,
+        Err(e) => {
+            give_back_ids(&db);
+            return Err(e);
+        }
+    };
+
+    let mut edit = TreeEdit::new();
+    for info in &changed {
+        let content = match format_issue(&info.issue, &info.title, &info.body) {
+            Ok(content) => content,
+            Err(e) => {
+                undo_claims(&mut db, undo);
+                give_back_ids(&db);
+                return Err(e);
+            }
+        };
+        edit.write(&info.relative_path, content);
+    }
+    let message = format!("Batch: {} operations on {} issues", count, changed.len());
+    if let Err(e) = edit.commit(&project.repo_root, data_branch, &message) {
+        undo_claims(&mut db, undo);
+        give_back_ids(&db);
+        return Err(e);
+    }
+
+    // Closed issues keep their assignee as a record, but not the claim lock
+    for id in &batch.closed {
+        match db.release(*id) {
+            Ok(()) | Err(ItackError::NotClaimed(_)) => {}
+            Err(e) => return Err(e),
+        }
+    }
+
+    for info in &changed {
+        let initial = batch
+            .initial_assignees
+            .get(&info.issue.id)
+            .cloned()
+            .flatten();
+        if initial != info.issue.assignee {
+            if let Some(assignee) = &initial {
+                db.touch_agent(assignee, None, None)?;
+            }
+            if let Some(assignee) = &info.issue.assignee {
+                db.touch_agent(
+                    assignee,
+                    info.issue.session.as_deref(),
+                    info.issue.branch.as_deref(),
+                )?;
+            }
+        }
+    }
+
+    for (event, info, env) in &batch.post_hooks {
+        hooks::run(project, *event, info, env)?;
+    }
+    for id in &batch.closed {
+        cascade_unblocked(project, data_branch, *id)?;
+    }
+
+    let created = batch
+        .created
+        .iter()
+        .map(|id| batch.issues[id].clone())
+        .collect();
+    Ok(BatchResult {
+        operations: count,
+        created,
+        changed,
+    })
+}
+
+/// Claims changed before the commit, to be undone if it fails.
+pub enum ClaimUndo {
+    Claimed(u32),
+    Released(u32, String, Option<String>),
+}
+
+pub fn undo_claims(db: &mut Database, undo: Vec<ClaimUndo>) {
+    for step in undo.into_iter().rev() {
+        let _ = match step {
+            ClaimUndo::Claimed(id) => db.release(id),
+            ClaimUndo::Released(id, assignee, epic) => {
+                db.record_claim(id, &assignee, epic.as_deref())
+            }
+        };
+    }
+}
+
+/// Extra environment variables for a hook.
+type HookEnv = Vec<(&'static str, String)>;
+
+/// In-memory state of a batch being applied.
+struct Batch<'a> {
+    project: &'a Project,
+    issues: BTreeMap<u32, IssueInfo>,
+    /// ID for the next `create`.
+    next_id: u32,
+    /// IDs made by `create`, in order.
+    created: Vec<u32>,
+    changed: BTreeSet<u32>,
+    /// Assignee of each changed issue before the batch.
+    initial_assignees: HashMap<u32, Option<String>>,
+    /// Issues released by the batch.
+    released: BTreeSet<u32>,
+    /// Issues marked done or wont-fix by the batch.
+    closed: Vec<u32>,
+    post_hooks: Vec<(HookEvent, IssueInfo, HookEnv)>,
+}
+
+impl Batch<'_> {
+    fn resolve(&self, id: &IssueRef) -> Result<u32> {
+        let id = id.resolve(&self.created)?;
+        if self.issues.contains_key(&id) {
+            Ok(id)
+        } else {
+            Err(ItackError::IssueNotFound(id))
+        }
+    }
+
+    fn resolve_all(&self, ids: &[IssueRef]) -> Result<Vec<u32>> {
+        ids.iter().map(|id| self.resolve(id)).collect()
+    }
+
+    /// Get an issue to change, remembering its assignee before the batch.
+    fn issue_mut(&mut self, id: u32) -> &mut IssueInfo {
+        let info = self.issues.get_mut(&id).expect("issue was resolved");
+        self.initial_assignees
+            .entry(id)
+            .or_insert_with(|| info.issue.assignee.clone());
+        self.changed.insert(id);
+        info
+    }
+
+    /// Run the pre-hook for a change now, and queue its post-hook.
+    fn hooks(&mut self, pre: HookEvent, post: HookEvent, id: u32, env: HookEnv) -> Result<()> {
+        let info = self.issues[&id].clone();
+        hooks::run(self.project, pre, &info, &env)?;
+        self.post_hooks.push((post, info, env));
+        Ok(())
+    }
+
+    fn apply(&mut self, operation: Operation) -> Result<()> {
+        match operation {
+            Operation::Create {
+                title,
+                epic,
+                body,
+                depends_on,
+            } => {
+                let id = self.next_id;
+                let mut issue = Issue::with_epic(id, epic);
+                issue.depends_on = self.resolve_all(&depends_on)?;
+                issue.depends_on.sort();
+                issue.depends_on.dedup();
+                let relative_path = Project::issue_relative_path(id, &issue.created);
+
+                self.next_id += 1;
+                self.created.push(id);
+                self.issues.insert(
+                    id,
+                    IssueInfo {
+                        issue,
+                        title,
+                        body: body.unwrap_or_default(),
+                        relative_path,
+                    },
+                );
+                self.issue_mut(id);
+                self.hooks(HookEvent::PreCreate, HookEvent::PostCreate, id, Vec::new())
+            }
+
+            Operation::Edit {
+                id,
+                title,
+                body,
+                epic,
+            } => {
+                let id = self.resolve(&id)?;
+                let info = self.issue_mut(id);
+                if let Some(title) = title {
+                    info.title = title;
+                }
+                if let Some(body) = body {
+                    info.body = body;
+                }
+                if let Some(epic) = epic {
+                    info.issue.epic = Some(epic).filter(|epic| !epic.is_empty());
+                }
+                self.hooks(HookEvent::PreEdit, HookEvent::PostEdit, id, Vec::new())
+            }
+
+            Operation::Comment { id, text, author } => {
+                let id = self.resolve(&id)?;
+                let author = self.project.resolve_assignee(author)?;
+                let info = self.issue_mut(id);
+                info.body = append_comment(&info.body, &author, Utc::now(), &text);
+                self.hooks(HookEvent::PreEdit, HookEvent::PostEdit, id, Vec::new())
+            }
+
+            Operation::Claim {
+                id,
+                assignee,
+                session,
+                ignore_deps,
+            } => {
+                let id = self.resolve(&id)?;
+                let assignee = self.project.resolve_assignee(assignee)?;
+
+                let all: Vec<IssueInfo> = self.issues.values().cloned().collect();
+                let info = &self.issues[&id];
+                // Claims held before the batch are checked against the database later
+                if let Some(holder) = &info.issue.assignee
+                    && self
+                        .initial_assignees
+                        .get(&id)
+                        .is_some_and(|initial| *initial != info.issue.assignee)
+                {
+                    return Err(ItackError::AlreadyClaimed(id, holder.clone()));
+                }
+                if !ignore_deps {
+                    let open = open_dependencies(&info.issue, &all);
+                    if !open.is_empty() {
+                        return Err(ItackError::Blocked { id, blockers: open });
+                    }
+                }
+
+                let project = self.project;
+                let info = self.issue_mut(id);
+                apply_claim(project, info, &assignee, session.as_deref());
+                self.hooks(HookEvent::PreClaim, HookEvent::PostClaim, id, Vec::new())
+            }
+
+            Operation::Release { id } => {
+                let id = self.resolve(&id)?;
+                let info = self.issue_mut(id);
+                let Some(old_assignee) = info.issue.assignee.take() else {
+                    return Err(ItackError::NotClaimed(id));
+                };
+                info.issue.branch = None;
+                info.issue.session = None;
+                self.released.insert(id);
+                let env = vec![("ITACK_OLD_ASSIGNEE", old_assignee)];
+                self.hooks(HookEvent::PreRelease, HookEvent::PostRelease, id, env)
+            }
+
+            Operation::Done { id } => {
+                let id = self.resolve(&id)?;
+                let old_status = self.close(id, Status::Done)?;
+                let env = vec![("ITACK_OLD_STATUS", old_status.to_string())];
+                self.hooks(HookEvent::PreDone, HookEvent::PostDone, id, env)
+            }
+
+            Operation::WontFix { id } => {
+                let id = self.resolve(&id)?;
+                let old_status = self.close(id, Status::WontFix)?;
+                let env = vec![("ITACK_OLD_STATUS", old_status.to_string())];
+                self.hooks(HookEvent::PreWontFix, HookEvent::PostWontFix, id, env)
+            }
+
+            Operation::Depend { id, deps } => {
+                let id = self.resolve(&id)?;
+                let deps = self.resolve_all(&deps)?;
+                let info = self.issue_mut(id);
+                for dep in deps {
+                    if dep != id && !info.issue.depends_on.contains(&dep) {
+                        info.issue.depends_on.push(dep);
+                    }
+                }
+                info.issue.depends_on.sort();
+                self.hooks(HookEvent::PreDepend, HookEvent::PostDepend, id, Vec::new())
+            }
+
+            Operation::Undepend { id, deps } => {
+                let id = self.resolve(&id)?;
+                let deps = self.resolve_all(&deps)?;
+                let info = self.issue_mut(id);
+                info.issue.depends_on.retain(|dep| !deps.contains(dep));
+                self.hooks(HookEvent::PreDepend, HookEvent::PostDepend, id, Vec::new())
+            }
+        }
+    }
+
+    /// Set a closing status, failing if the issue already has it.
+    /// Returns the previous status.
+    fn close(&mut self, id: u32, status: Status) -> Result<Status> {
+        let info = self.issue_mut(id);
+        if info.issue.status == status {
+            return Err(match status {
+                Status::WontFix => ItackError::AlreadyWontFix(id),
+                _ => ItackError::AlreadyDone(id),
+            });
+        }
+        let old_status = info.issue.status;
+        info.issue.status = status;
+        if !old_status.is_closed() {
+            self.closed.push(id);
+        }
+        Ok(old_status)
+    }
+
+    /// Bring database claims in line with the batch's assignees. On failure
+    /// every claim change already made is undone.
+    fn sync_claims(&self, db: &mut Database) -> Result<Vec<ClaimUndo>> {
+        let mut undo = Vec::new();
+        for id in &self.changed {
+            let info = &self.issues[id];
+            let initial = self.initial_assignees.get(id).cloned().flatten();
+            if initial == info.issue.assignee {
+                continue;
+            }
+
+            let result = (|| {
+                if let Some(old) = initial.filter(|_| self.released.contains(id)) {
+                    match db.release(*id) {
+                        Ok(()) => undo.push(ClaimUndo::Released(*id, old, info.issue.epic.clone())),
+                        Err(ItackError::NotClaimed(_)) => {}
+                        Err(e) => return Err(e),
+                    }
+                }
+                if let Some(assignee) = &info.issue.assignee {
+                    db.claim(*id, assignee, info.issue.epic.as_deref())?;
+                    undo.push(ClaimUndo::Claimed(*id));
+                }
+                Ok(())
+            })();
+
+            if let Err(e) = result {
+                undo_claims(db, undo);
+                return Err(e);
+            }
+        }
+        Ok(undo)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_operations() {
+        let op: Operation =
+            serde_json::from_str(r##"{"op": "depend", "id": "$2", "deps": [1, "#3"]}"##).unwrap();
+        let Operation::Depend { id, deps } = op else {
+            panic!("expected depend");
+        };
+        assert_eq!(id.resolve(&[10, 11]).unwrap(), 11);
+        assert_eq!(deps[0].resolve(&[]).unwrap(), 1);
+        assert_eq!(deps[1].resolve(&[]).unwrap(), 3);
+
+        assert!(serde_json::from_str::<Operation>(r#"{"op": "wont-fix", "id": 1}"#).is_ok());
+        assert!(serde_json::from_str::<Operation>(r#"{"op": "explode", "id": 1}"#).is_err());
+    }
+
+    #[test]
+    fn test_placeholders_must_refer_to_earlier_creates() {
+        assert!(IssueRef::Text("$1".to_string()).resolve(&[]).is_err());
+        assert!(IssueRef::Text("$0".to_string()).resolve(&[5]).is_err());
+        assert!(IssueRef::Text("one".to_string()).resolve(&[5]).is_err());
+    }
+}
@@ -3,7 +3,7 @@
 use crate::core::{Project, Status};
 use crate::error::Result;
 use crate::output::{self, OutputFormat};
-use crate::storage::db::load_all_issues_from_data_branch;
+use crate::storage::db::{IssueInfo, load_all_issues_from_data_branch};
 
 /// Arguments for the board command.
 pub struct BoardArgs {
@@ -23,7 +23,22 @@ pub struct BoardSummary {
 /// Show project board overview.
 pub fn run(args: BoardArgs) -> Result<()> {
     let project = Project::discover()?;
+    let (summary, issues) = execute(&project)?;
 
+    match args.format {
+        OutputFormat::Table => {
+            output::print_board(&summary, &issues);
+        }
+        OutputFormat::Json => {
+            output::print_board_json(&summary, &issues)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Load all issues and count them by status.
+pub fn execute(project: &Project) -> Result<(BoardSummary, Vec<IssueInfo>)> {
     // Verify database exists (will error with helpful message if not)
     let _db = project.open_db()?;
 
@@ -55,14 +70,5 @@ pub fn run(args: BoardArgs) -> Result<()> {
         total_count: issues.len(),
     };
 
-    match args.format {
-        OutputFormat::Table => {
-            output::print_board(&summary, &issues);
-        }
-        OutputFormat::Json => {
-            output::print_board_json(&summary, &issues)?;
-        }
-    }
-
-    Ok(())
+    Ok((summary, issues))
 }
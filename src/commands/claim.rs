@@ -1,21 +1,66 @@
 //! itack claim command.
 
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+use crate::core::deps::open_dependencies;
+use crate::core::hooks::{self, HookEvent};
 use crate::core::{Project, Status, commit_to_branch};
-use crate::error::Result;
-use crate::storage::db::load_issue_from_data_branch;
+use crate::error::{ItackError, Result};
+use crate::storage::Database;
+use crate::storage::db::{
+    IssueInfo, load_all_issues_from_data_branch, load_issue_from_data_branch,
+};
 use crate::storage::markdown::format_issue;
 
+/// How often to re-check while waiting for a claim or dependencies.
+const WAIT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
 /// Arguments for the claim command.
+#[derive(Debug, Default, Deserialize, JsonSchema)]
 pub struct ClaimArgs {
+    /// Issue ID.
     pub id: u32,
-    pub assignee: String,
+    /// Assignee name; defaults to the current agent identity.
+    pub assignee: Option<String>,
+    /// Session ID working on the issue.
     pub session: Option<String>,
+    #[serde(skip)]
+    pub wait: bool,
+    #[serde(skip)]
+    pub wait_deps: bool,
+    /// Claim even if issues this one depends on are still open.
+    #[serde(default)]
+    pub ignore_deps: bool,
+    #[serde(skip)]
+    pub timeout: Option<Duration>,
+    /// Branch to record on the issue instead of the current one.
+    #[serde(skip)]
+    pub branch: Option<String>,
 }
 
 /// Claim an issue with SQLite-backed locking.
 pub fn run(args: ClaimArgs) -> Result<()> {
     let project = Project::discover()?;
+    let issue_info = execute(&project, args)?;
+
+    println!(
+        "Claimed issue #{} for {}",
+        issue_info.issue.id,
+        issue_info.issue.assignee.as_deref().unwrap_or_default()
+    );
+
+    Ok(())
+}
+
+/// Claim an issue and commit the assignment to the data branch.
+pub fn execute(project: &Project, args: ClaimArgs) -> Result<IssueInfo> {
     let mut db = project.open_db()?;
+    let assignee = project.resolve_assignee(args.assignee)?;
 
     let data_branch = project
         .config
@@ -23,23 +68,50 @@ pub fn run(args: ClaimArgs) -> Result<()> {
         .as_deref()
         .unwrap_or("data/itack");
 
-    // Load the issue from data branch (source of truth)
+    // Make sure the issue exists before waiting on anything
     let mut issue_info = load_issue_from_data_branch(&project.repo_root, data_branch, args.id)?;
+    let epic = issue_info.issue.epic.clone();
+
+    let deadline = args
+        .timeout
+        .and_then(|timeout| Instant::now().checked_add(timeout));
+
+    if args.wait_deps {
+        wait_for_dependencies(&project.repo_root, data_branch, args.id, deadline)?;
+    } else if !args.ignore_deps {
+        let open = unfinished_dependencies(&project.repo_root, data_branch, args.id)?;
+        if !open.is_empty() {
+            return Err(ItackError::Blocked {
+                id: args.id,
+                blockers: open,
+            });
+        }
+    }
+
+    // Let the pre-claim hook veto the claim before anything is locked
+    apply_claim(project, &mut issue_info, &assignee, args.session.as_deref());
+    if args.branch.is_some() {
+        issue_info.issue.branch = args.branch.clone();
+    }
+    hooks::run(project, HookEvent::PreClaim, &issue_info, &[])?;
 
     // Try to claim in database (atomic operation)
-    db.claim(args.id, &args.assignee)?;
-
-    // Update issue fields
-    issue_info.issue.assignee = Some(args.assignee.clone());
-    issue_info.issue.branch = project.current_branch();
-    issue_info.issue.session = args.session.clone();
-    if issue_info.issue.status == Status::Open {
-        issue_info.issue.status = Status::InProgress;
+    if args.wait {
+        wait_for_claim(&mut db, args.id, &assignee, epic.as_deref(), deadline)?;
+    } else {
+        db.claim(args.id, &assignee, epic.as_deref())?;
+    }
+
+    // Reload the issue, since it may have changed while waiting
+    let mut issue_info = load_issue_from_data_branch(&project.repo_root, data_branch, args.id)?;
+    apply_claim(project, &mut issue_info, &assignee, args.session.as_deref());
+    if args.branch.is_some() {
+        issue_info.issue.branch = args.branch;
     }
 
     // Format issue content in memory and commit directly to data branch
     let content = format_issue(&issue_info.issue, &issue_info.title, &issue_info.body)?;
-    let message = format!("Claim issue #{} for {}", args.id, args.assignee);
+    let message = format!("Claim issue #{} for {}", args.id, assignee);
     commit_to_branch(
         &project.repo_root,
         data_branch,
@@ -48,7 +120,83 @@ pub fn run(args: ClaimArgs) -> Result<()> {
         &message,
     )?;
 
-    println!("Claimed issue #{} for {}", args.id, args.assignee);
+    db.touch_agent(
+        &assignee,
+        issue_info.issue.session.as_deref(),
+        issue_info.issue.branch.as_deref(),
+    )?;
 
-    Ok(())
+    hooks::run(project, HookEvent::PostClaim, &issue_info, &[])?;
+
+    Ok(issue_info)
+}
+
+/// Update the issue fields for a claim by `assignee`.
+pub fn apply_claim(project: &Project, info: &mut IssueInfo, assignee: &str, session: Option<&str>) {
+    info.issue.assignee = Some(assignee.to_string());
+    info.issue.branch = project.current_branch();
+    info.issue.session = session.map(|s| s.to_string());
+    if info.issue.status == Status::Open {
+        info.issue.status = Status::InProgress;
+    }
+}
+
+/// Retry the claim until the existing claim is released or expires.
+fn wait_for_claim(
+    db: &mut Database,
+    id: u32,
+    assignee: &str,
+    epic: Option<&str>,
+    deadline: Option<Instant>,
+) -> Result<()> {
+    loop {
+        match db.claim(id, assignee, epic) {
+            Err(ItackError::AlreadyClaimed(_, holder)) => {
+                if deadline.is_some_and(|d| Instant::now() >= d) {
+                    return Err(ItackError::Timeout(format!(
+                        "issue {} to be released by {}",
+                        id, holder
+                    )));
+                }
+                thread::sleep(WAIT_POLL_INTERVAL);
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Block until every dependency of the issue is closed on the data branch.
+fn wait_for_dependencies(
+    repo_root: &Path,
+    data_branch: &str,
+    id: u32,
+    deadline: Option<Instant>,
+) -> Result<()> {
+    loop {
+        let open = unfinished_dependencies(repo_root, data_branch, id)?;
+        if open.is_empty() {
+            return Ok(());
+        }
+
+        if deadline.is_some_and(|d| Instant::now() >= d) {
+            let dep_list: Vec<String> = open.iter().map(|d| format!("#{d}")).collect();
+            return Err(ItackError::Timeout(format!(
+                "dependencies of issue {} to close: {}",
+                id,
+                dep_list.join(", ")
+            )));
+        }
+        thread::sleep(WAIT_POLL_INTERVAL);
+    }
+}
+
+/// Get the dependencies of the issue that are still open on the data branch.
+fn unfinished_dependencies(repo_root: &Path, data_branch: &str, id: u32) -> Result<Vec<u32>> {
+    let issues = load_all_issues_from_data_branch(repo_root, data_branch)?;
+    let issue = issues
+        .iter()
+        .find(|info| info.issue.id == id)
+        .ok_or(ItackError::IssueNotFound(id))?;
+
+    Ok(open_dependencies(&issue.issue, &issues))
 }
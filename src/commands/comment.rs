@@ -0,0 +1,63 @@
+//! itack comment command.
+
+use chrono::Utc;
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+use crate::core::hooks::{self, HookEvent};
+use crate::core::{Project, commit_to_branch};
+use crate::error::Result;
+use crate::storage::db::{IssueInfo, load_issue_from_data_branch};
+use crate::storage::markdown::{append_comment, format_issue};
+
+/// Arguments for the comment command.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CommentArgs {
+    /// Issue ID.
+    pub id: u32,
+    /// Comment text (markdown).
+    pub text: String,
+    /// Comment author; defaults to the current agent identity.
+    pub author: Option<String>,
+}
+
+/// Add a comment to an issue.
+pub fn run(args: CommentArgs) -> Result<()> {
+    let project = Project::discover()?;
+    let issue_info = execute(&project, args)?;
+
+    println!("Added comment to issue #{}", issue_info.issue.id);
+
+    Ok(())
+}
+
+/// Append a comment to the issue body and commit it to the data branch.
+pub fn execute(project: &Project, args: CommentArgs) -> Result<IssueInfo> {
+    let author = project.resolve_assignee(args.author)?;
+
+    let data_branch = project
+        .config
+        .data_branch
+        .as_deref()
+        .unwrap_or("data/itack");
+
+    let mut issue_info = load_issue_from_data_branch(&project.repo_root, data_branch, args.id)?;
+    issue_info.body = append_comment(&issue_info.body, &author, Utc::now(), &args.text);
+
+    // A comment is an edit of the issue body
+    hooks::run(project, HookEvent::PreEdit, &issue_info, &[])?;
+
+    let content = format_issue(&issue_info.issue, &issue_info.title, &issue_info.body)?;
+    let message = format!("Comment on issue #{} by {}", args.id, author);
+    commit_to_branch(
+        &project.repo_root,
+        data_branch,
+        &issue_info.relative_path,
+        content.as_bytes(),
+        &message,
+    )?;
+
+    hooks::run(project, HookEvent::PostEdit, &issue_info, &[])?;
+
+    Ok(issue_info)
+}
@@ -1,40 +1,144 @@
 //! itack create command.
 
-use crate::core::{Issue, Project, commit_to_branch};
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+use crate::commands::edit::{OnInvalid, edit_until_valid};
+use crate::core::hooks::{self, HookEvent};
+use crate::core::template::Template;
+use crate::core::{Issue, Priority, Project, commit_to_branch};
 use crate::error::Result;
-use crate::storage::markdown::format_issue;
+use crate::storage::db::IssueInfo;
+use crate::storage::markdown::{format_issue, parse_issue};
 
 /// Arguments for the create command.
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct CreateArgs {
+    /// Issue title.
     pub title: String,
+    /// Epic/category for grouping.
     pub epic: Option<String>,
+    /// Issue body/description.
     pub body: Option<String>,
+    /// Custom commit message.
     pub message: Option<String>,
+    /// IDs of issues this one depends on.
+    #[serde(default)]
     pub depends_on: Vec<u32>,
+    /// Free-form labels.
+    #[serde(default)]
+    pub labels: Vec<String>,
+    /// How urgent the issue is.
+    pub priority: Option<Priority>,
+    /// Template in `.itack/templates/` providing the body skeleton and defaults
+    /// for epic, labels and priority.
+    pub template: Option<String>,
+    /// Open the editor on the new issue before committing it.
+    #[serde(skip)]
+    pub edit: bool,
 }
 
 /// Create a new issue.
-pub fn run(args: CreateArgs) -> Result<()> {
+pub fn run(mut args: CreateArgs) -> Result<()> {
     let project = Project::discover()?;
-    let db = project.open_db()?;
 
-    // Get next issue ID atomically
-    let id = db.next_issue_id()?;
+    if args.body.is_none()
+        && (args.edit || args.template.is_some() || project.config.edit_on_create)
+    {
+        args = edit_new_issue(&project, args)?;
+    }
+
+    let issue_info = execute(&project, args)?;
+
+    println!(
+        "Created issue #{}: {}",
+        issue_info.issue.id, issue_info.title
+    );
+
+    Ok(())
+}
+
+/// Let the user edit the issue `args` describe, as a whole issue file, and
+/// return arguments for what they saved. The ID and creation time shown are
+/// provisional; status and assignee are not taken from the file.
+fn edit_new_issue(project: &Project, args: CreateArgs) -> Result<CreateArgs> {
+    let next_id = project.open_db()?.peek_next_issue_id()?;
+    let message = args.message.clone();
+    let draft = build_issue(project, args, next_id)?;
+    let skeleton = format_issue(&draft.issue, &draft.title, &draft.body)?;
+
+    let (issue, title, body) =
+        edit_until_valid(project, &skeleton, OnInvalid::Reopen, parse_issue)?;
+    Ok(CreateArgs {
+        title,
+        epic: issue.epic,
+        body: Some(body),
+        message,
+        depends_on: issue.depends_on,
+        labels: issue.labels,
+        priority: issue.priority,
+        template: None,
+        edit: false,
+    })
+}
+
+/// Build the issue `args` describe with the given ID. With a template, its
+/// defaults fill in what the arguments leave unset, and its body is used when
+/// no body is given.
+fn build_issue(project: &Project, args: CreateArgs, id: u32) -> Result<IssueInfo> {
+    let template = args
+        .template
+        .as_deref()
+        .map(|name| Template::load(&project.itack_dir, name))
+        .transpose()?;
 
     // Create the issue (title is stored in markdown, not in Issue struct)
     let mut issue = Issue::with_epic(id, args.epic);
     issue.depends_on = args.depends_on;
+    issue.labels = args.labels;
+    issue.priority = args.priority;
+    let mut body = args.body;
+    if let Some(template) = template {
+        if issue.epic.is_none() {
+            issue.epic = template.defaults.epic;
+        }
+        if issue.labels.is_empty() {
+            issue.labels = template.defaults.labels;
+        }
+        if issue.priority.is_none() {
+            issue.priority = template.defaults.priority;
+        }
+        body = body.or(Some(template.body));
+    }
 
     // Get the relative path for the git tree
     let relative_path = Project::issue_relative_path(id, &issue.created);
 
+    Ok(IssueInfo {
+        issue,
+        title: args.title,
+        body: body.unwrap_or_default(),
+        relative_path,
+    })
+}
+
+/// Create a new issue and commit it to the data branch.
+pub fn execute(project: &Project, mut args: CreateArgs) -> Result<IssueInfo> {
+    let db = project.open_db()?;
+
+    // Get next issue ID atomically
+    let id = db.next_issue_id()?;
+
+    let message = args.message.take();
+    let issue_info = build_issue(project, args, id)?;
+
+    hooks::run(project, HookEvent::PreCreate, &issue_info, &[])?;
+
     // Format issue content in memory
-    let body = args.body.as_deref().unwrap_or("");
-    let content = format_issue(&issue, &args.title, body)?;
+    let content = format_issue(&issue_info.issue, &issue_info.title, &issue_info.body)?;
 
-    let commit_message = args
-        .message
-        .unwrap_or_else(|| format!("Create issue #{}: {}", id, args.title));
+    let commit_message =
+        message.unwrap_or_else(|| format!("Create issue #{}: {}", id, issue_info.title));
 
     let data_branch = project
         .config
@@ -46,12 +150,12 @@ pub fn run(args: CreateArgs) -> Result<()> {
     commit_to_branch(
         &project.repo_root,
         data_branch,
-        &relative_path,
+        &issue_info.relative_path,
         content.as_bytes(),
         &commit_message,
     )?;
 
-    println!("Created issue #{}: {}", id, args.title);
+    hooks::run(project, HookEvent::PostCreate, &issue_info, &[])?;
 
-    Ok(())
+    Ok(issue_info)
 }
@@ -0,0 +1,78 @@
+//! itack delete command.
+
+use crate::core::{Project, TreeEdit};
+use crate::error::{ItackError, Result};
+use crate::output;
+use crate::storage::db::{
+    IssueInfo, load_all_issues_from_data_branch, load_archived_issues_from_data_branch,
+    load_issue_from_data_branch,
+};
+use crate::storage::markdown::format_issue;
+
+/// Arguments for the delete command.
+#[derive(Debug)]
+pub struct DeleteArgs {
+    /// Issue ID.
+    pub id: u32,
+}
+
+/// Delete an issue and print what changed.
+pub fn run(args: DeleteArgs) -> Result<()> {
+    let project = Project::discover()?;
+    let (deleted, dependents) = execute(&project, &args)?;
+
+    println!("Deleted issue #{}: {}", deleted.issue.id, deleted.title);
+    if !dependents.is_empty() {
+        println!(
+            "Removed it from the dependencies of {}",
+            output::format_issue_refs(&dependents)
+        );
+    }
+
+    Ok(())
+}
+
+/// Remove the issue file and drop the issue from other issues' dependencies in
+/// one commit, then clear its claim. The issue stays in the data branch history.
+/// Returns the deleted issue and the IDs of the issues that depended on it.
+pub fn execute(project: &Project, args: &DeleteArgs) -> Result<(IssueInfo, Vec<u32>)> {
+    let data_branch = project
+        .config
+        .data_branch
+        .as_deref()
+        .unwrap_or("data/itack");
+
+    let deleted = load_issue_from_data_branch(&project.repo_root, data_branch, args.id)?;
+
+    let mut edit = TreeEdit::new();
+    edit.remove(&deleted.relative_path);
+
+    let mut issues = load_all_issues_from_data_branch(&project.repo_root, data_branch)?;
+    issues.extend(load_archived_issues_from_data_branch(
+        &project.repo_root,
+        data_branch,
+    )?);
+    let mut dependents = Vec::new();
+    for mut info in issues {
+        if !info.issue.depends_on.contains(&args.id) {
+            continue;
+        }
+        info.issue.depends_on.retain(|dep| *dep != args.id);
+        let content = format_issue(&info.issue, &info.title, &info.body)?;
+        edit.write(&info.relative_path, content);
+        dependents.push(info.issue.id);
+    }
+    dependents.sort();
+
+    let message = format!("Delete issue #{}: {}", args.id, deleted.title);
+    edit.commit(&project.repo_root, data_branch, &message)?;
+
+    let mut db = project.open_db()?;
+    match db.release(args.id) {
+        Ok(()) | Err(ItackError::NotClaimed(_)) => {}
+        Err(e) => return Err(e),
+    }
+    db.forget_imports(args.id)?;
+
+    Ok((deleted, dependents))
+}
@@ -1,5 +1,6 @@
 //! itack depend command.
 
+use crate::core::hooks::{self, HookEvent};
 use crate::core::{Project, commit_to_branch};
 use crate::error::Result;
 use crate::storage::db::load_issue_from_data_branch;
@@ -31,6 +32,8 @@ pub fn run(args: DependArgs) -> Result<()> {
     }
     issue_info.issue.depends_on.sort();
 
+    hooks::run(&project, HookEvent::PreDepend, &issue_info, &[])?;
+
     let content = format_issue(&issue_info.issue, &issue_info.title, &issue_info.body)?;
     let dep_list: Vec<String> = args.deps.iter().map(|d| format!("#{d}")).collect();
     let message = format!(
@@ -46,6 +49,8 @@ pub fn run(args: DependArgs) -> Result<()> {
         &message,
     )?;
 
+    hooks::run(&project, HookEvent::PostDepend, &issue_info, &[])?;
+
     println!(
         "Issue #{} now depends on: {:?}",
         args.id, issue_info.issue.depends_on
@@ -3,14 +3,18 @@
 use std::collections::{HashMap, HashSet};
 use std::fs;
 
-use crate::core::{Project, Status, commit_to_branch, remove_file_from_branch};
+use crate::commands::reconcile;
+use crate::core::git::branch_tip;
+use crate::core::{Project, Status, TreeEdit};
 use crate::error::Result;
 use crate::storage::Database;
-use crate::storage::db::{IssueInfo, load_all_issues_from_data_branch};
+use crate::storage::db::{
+    IssueInfo, load_all_issues_from_data_branch, load_archived_issues_from_data_branch,
+};
 use crate::storage::markdown;
 
 /// Expected schema version (must match SCHEMA_VERSION in db.rs).
-const EXPECTED_SCHEMA_VERSION: i32 = 1;
+const EXPECTED_SCHEMA_VERSION: i32 = 4;
 
 /// Run diagnostics on the itack database and issue files.
 pub fn run() -> Result<()> {
@@ -135,6 +139,48 @@ pub fn run() -> Result<()> {
         }
     }
 
+    // Check 5: In-progress issues whose branch is already merged
+    let mainline = reconcile::mainline(&project);
+    println!("\nChecking for in-progress issues with merged branches...");
+    match branch_tip(&project.repo_root, mainline) {
+        Ok(None) => println!("  - Skipped: mainline branch '{}' not found", mainline),
+        Ok(Some(_)) => match load_all_issues_from_data_branch(&project.repo_root, data_branch)
+            .and_then(|issues| reconcile::merged_in_progress(&project, &issues))
+        {
+            Ok(merged) if merged.is_empty() => {
+                println!(
+                    "  ✓ No in-progress issues have branches merged into '{}'",
+                    mainline
+                )
+            }
+            Ok(merged) => {
+                has_issues = true;
+                println!(
+                    "  ✗ Found {} in-progress issue(s) with branches merged into '{}':",
+                    merged.len(),
+                    mainline
+                );
+                for info in &merged {
+                    println!(
+                        "    - #{}: {} ({})",
+                        info.issue.id,
+                        info.title,
+                        info.issue.branch.as_deref().unwrap_or_default()
+                    );
+                }
+                println!("    Run 'itack reconcile' to mark them done.");
+            }
+            Err(e) => {
+                println!("  ✗ Could not check for merged branches: {}", e);
+                has_issues = true;
+            }
+        },
+        Err(e) => {
+            println!("  ✗ Could not check for merged branches: {}", e);
+            has_issues = true;
+        }
+    }
+
     // Summary
     println!();
     if has_issues {
@@ -208,8 +254,12 @@ fn check_issue_sync(project: &Project, data_branch: &str) -> Result<SyncCheckRes
         Some(data_branch),
     )?;
 
-    // Get all issues from data branch
-    let issues = load_all_issues_from_data_branch(&project.repo_root, data_branch)?;
+    // Get all issues from data branch, archived ones included since they keep their IDs
+    let mut issues = load_all_issues_from_data_branch(&project.repo_root, data_branch)?;
+    issues.extend(load_archived_issues_from_data_branch(
+        &project.repo_root,
+        data_branch,
+    )?);
 
     let issue_ids: HashSet<u32> = issues.iter().map(|i| i.issue.id).collect();
     let max_issue_id = issues.iter().map(|i| i.issue.id).max().unwrap_or(0);
@@ -273,7 +323,7 @@ fn check_duplicate_ids(project: &Project, data_branch: &str) -> Result<Vec<Issue
     Ok(duplicates)
 }
 
-/// Fix duplicate IDs by renumbering each duplicate to a new ID.
+/// Fix duplicate IDs by renumbering each duplicate to a new ID, in a single commit.
 /// Returns a list of (old_id, new_id, title) for each renumbered issue.
 fn fix_duplicate_ids(
     project: &Project,
@@ -282,39 +332,31 @@ fn fix_duplicate_ids(
 ) -> Result<Vec<(u32, u32, String)>> {
     let db = project.open_db()?;
     let mut renames = Vec::new();
+    let mut edit = TreeEdit::new();
 
     for dup in duplicates {
         let new_id = db.next_issue_id()?;
 
-        // Create new issue with the new ID
+        // Move the duplicate to a file for its new ID
         let mut new_issue = dup.issue.clone();
         new_issue.id = new_id;
 
         let new_path = Project::issue_relative_path(new_id, &new_issue.created);
         let content = markdown::format_issue(&new_issue, &dup.title, &dup.body)?;
-
-        // Write the new file
-        commit_to_branch(
-            &project.repo_root,
-            data_branch,
-            &new_path,
-            content.as_bytes(),
-            &format!(
-                "Renumber duplicate #{} → #{}: {}",
-                dup.issue.id, new_id, dup.title
-            ),
-        )?;
-
-        // Remove the old file
-        remove_file_from_branch(
-            &project.repo_root,
-            data_branch,
-            &dup.relative_path,
-            &format!("Remove duplicate file {}", dup.relative_path.display()),
-        )?;
+        edit.remove(&dup.relative_path).write(new_path, content);
 
         renames.push((dup.issue.id, new_id, dup.title.clone()));
     }
 
+    let summary: Vec<String> = renames
+        .iter()
+        .map(|(old_id, new_id, _)| format!("#{} → #{}", old_id, new_id))
+        .collect();
+    edit.commit(
+        &project.repo_root,
+        data_branch,
+        &format!("Renumber duplicate issues: {}", summary.join(", ")),
+    )?;
+
     Ok(renames)
 }
@@ -1,18 +1,53 @@
 //! itack done command.
 
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+use crate::core::deps::newly_unblocked;
+use crate::core::hooks::{self, HookEvent};
 use crate::core::{Project, Status, commit_to_branch};
 use crate::error::{ItackError, Result};
-use crate::storage::db::load_issue_from_data_branch;
+use crate::output::{self, OutputFormat};
+use crate::storage::db::{
+    IssueInfo, load_all_issues_from_data_branch, load_issue_from_data_branch,
+};
 use crate::storage::markdown::format_issue;
 
 /// Arguments for the done command.
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct DoneArgs {
+    /// Issue ID.
     pub id: u32,
+    #[serde(skip)]
+    pub format: OutputFormat,
 }
 
 /// Mark an issue as done.
 pub fn run(args: DoneArgs) -> Result<()> {
     let project = Project::discover()?;
+    let (old_status, unblocked) = execute(&project, &args)?;
+
+    match args.format {
+        OutputFormat::Table => {
+            println!(
+                "Updated issue #{} status: {} -> {}",
+                args.id,
+                old_status,
+                Status::Done
+            );
+            output::print_unblocked(&unblocked);
+        }
+        OutputFormat::Json => {
+            output::print_status_change_json(args.id, old_status, Status::Done, &unblocked)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Mark an issue as done and commit the change to the data branch.
+/// Returns the previous status and the issues that became ready.
+pub fn execute(project: &Project, args: &DoneArgs) -> Result<(Status, Vec<IssueInfo>)> {
     let data_branch = project
         .config
         .data_branch
@@ -29,6 +64,9 @@ pub fn run(args: DoneArgs) -> Result<()> {
     let old_status = issue_info.issue.status;
     issue_info.issue.status = Status::Done;
 
+    let hook_env = [("ITACK_OLD_STATUS", old_status.to_string())];
+    hooks::run(project, HookEvent::PreDone, &issue_info, &hook_env)?;
+
     // Format issue content in memory and commit directly to data branch
     let content = format_issue(&issue_info.issue, &issue_info.title, &issue_info.body)?;
     let message = format!("Mark issue #{} as done", args.id);
@@ -40,12 +78,41 @@ pub fn run(args: DoneArgs) -> Result<()> {
         &message,
     )?;
 
-    println!(
-        "Updated issue #{} status: {} -> {}",
-        args.id,
-        old_status,
-        Status::Done
-    );
+    // The claim lock only lasts while the work is open; the assignee stays as a record
+    let mut db = project.open_db()?;
+    match db.release(args.id) {
+        Ok(()) | Err(ItackError::NotClaimed(_)) => {}
+        Err(e) => return Err(e),
+    }
 
-    Ok(())
+    hooks::run(project, HookEvent::PostDone, &issue_info, &hook_env)?;
+
+    let unblocked = if old_status.is_closed() {
+        Vec::new()
+    } else {
+        cascade_unblocked(project, data_branch, args.id)?
+    };
+
+    Ok((old_status, unblocked))
+}
+
+/// Find the issues that became ready now that `closed_id` is closed,
+/// and run the `post-unblock` hook for each of them.
+pub fn cascade_unblocked(
+    project: &Project,
+    data_branch: &str,
+    closed_id: u32,
+) -> Result<Vec<IssueInfo>> {
+    let issues = load_all_issues_from_data_branch(&project.repo_root, data_branch)?;
+    let unblocked: Vec<IssueInfo> = newly_unblocked(closed_id, &issues)
+        .into_iter()
+        .cloned()
+        .collect();
+
+    let extra_env = [("ITACK_UNBLOCKED_BY", closed_id.to_string())];
+    for info in &unblocked {
+        hooks::run(project, HookEvent::PostUnblock, info, &extra_env)?;
+    }
+
+    Ok(unblocked)
 }
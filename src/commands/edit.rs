@@ -1,10 +1,13 @@
 //! itack edit command.
 
-use std::io::Write;
+use std::io::{self, BufRead, IsTerminal, Write};
 use std::process::Command;
 
-use crate::core::{Project, commit_to_branch, find_issue_in_branch, read_file_from_branch};
+use crate::commands::archive::unarchive_reopened;
+use crate::core::hooks::{self, HookEvent};
+use crate::core::{Issue, Project, TreeEdit, find_issue_in_branch, read_file_from_branch};
 use crate::error::{ItackError, Result};
+use crate::storage::db::IssueInfo;
 use crate::storage::markdown::{format_issue, parse_issue};
 
 /// Arguments for the edit command.
@@ -39,50 +42,155 @@ pub fn run(args: EditArgs) -> Result<()> {
     let current_content = String::from_utf8(current_content_bytes)
         .map_err(|e| ItackError::InvalidMarkdown(format!("Invalid UTF-8 in issue file: {}", e)))?;
 
+    let (current, current_title, _old_body) = parse_issue(&current_content)?;
+
     // If body is provided, update directly without editor
-    let new_content = if let Some(new_body) = args.body {
-        let (issue, title, _old_body) = parse_issue(&current_content)?;
-        format_issue(&issue, &title, &new_body)?
+    let (new_content, (issue, title, body)) = if let Some(new_body) = args.body {
+        let new_content = format_issue(&current, &current_title, &new_body)?;
+        (new_content, (current, current_title, new_body))
     } else {
-        // Editor-based workflow using a temp file
-        let editor = project.config.get_editor();
-
-        let mut temp_file = tempfile::Builder::new()
-            .suffix(".md")
-            .tempfile()
-            .map_err(|e| ItackError::Other(format!("Failed to create temp file: {}", e)))?;
-
-        temp_file
-            .write_all(current_content.as_bytes())
-            .map_err(|e| ItackError::Other(format!("Failed to write temp file: {}", e)))?;
-
-        let temp_path = temp_file.path().to_path_buf();
-
-        // Open editor
-        let status = Command::new(&editor)
-            .arg(&temp_path)
-            .status()
-            .map_err(|e| ItackError::EditorFailed(format!("Failed to launch {}: {}", editor, e)))?;
-
-        if !status.success() {
-            return Err(ItackError::EditorFailed(format!(
-                "Editor exited with status: {}",
-                status
-            )));
-        }
+        edit_until_valid(&project, &current_content, OnInvalid::Ask, |edited| {
+            Ok((edited.to_string(), check_edit(&current, edited)?))
+        })?
+    };
 
-        // Read the edited content
-        std::fs::read_to_string(&temp_path)?
+    if new_content.trim_end() == current_content.trim_end() {
+        println!("No changes to issue #{}", args.id);
+        return Ok(());
+    }
+
+    let mut issue_info = IssueInfo {
+        issue,
+        title,
+        body,
+        relative_path,
     };
+    hooks::run(&project, HookEvent::PreEdit, &issue_info, &[])?;
 
     // Commit to data branch only
-    commit_to_branch(
-        &project.repo_root,
-        data_branch,
-        &relative_path,
-        new_content.as_bytes(),
-        &commit_message,
-    )?;
+    let mut edit = TreeEdit::new();
+    unarchive_reopened(&mut edit, &mut issue_info);
+    edit.write(&issue_info.relative_path, new_content);
+    edit.commit(&project.repo_root, data_branch, &commit_message)?;
+
+    hooks::run(&project, HookEvent::PostEdit, &issue_info, &[])?;
 
     Ok(())
 }
+
+/// Parse an edited issue file, checking that it keeps the issue's ID and only
+/// changes its status in ways [`Status::can_edit_to`](crate::core::Status::can_edit_to) allows.
+fn check_edit(current: &Issue, edited: &str) -> Result<(Issue, String, String)> {
+    let (issue, title, body) = parse_issue(edited)?;
+    if issue.id != current.id {
+        return Err(ItackError::Other(format!(
+            "The issue ID can't be changed (was {}, now {})",
+            current.id, issue.id
+        )));
+    }
+    if !current.status.can_edit_to(issue.status) {
+        return Err(ItackError::Other(format!(
+            "Status can't be changed from {} to {} by editing; use 'itack claim', 'itack release', 'itack done' or 'itack wont-fix'",
+            current.status, issue.status
+        )));
+    }
+    Ok((issue, title, body))
+}
+
+/// What [`edit_until_valid`] does when the saved file is rejected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OnInvalid {
+    /// Re-open the editor straight away.
+    Reopen,
+    /// Show the error and ask whether to re-edit or abort. Without a terminal
+    /// to ask on, the error is returned.
+    Ask,
+}
+
+/// Start of the comment explaining why an edit was rejected.
+const ERROR_COMMENT: &str = "<!-- itack:";
+
+/// Open `content` in the editor until `validate` accepts what was saved. When it
+/// doesn't, the editor re-opens on the saved text with the error as a comment at
+/// the top, after asking first if `on_invalid` says so. Saving an empty file
+/// aborts.
+pub fn edit_until_valid<T>(
+    project: &Project,
+    content: &str,
+    on_invalid: OnInvalid,
+    mut validate: impl FnMut(&str) -> Result<T>,
+) -> Result<T> {
+    let mut content = content.to_string();
+    loop {
+        let edited = open_in_editor(project, &content)?;
+        let edited: String = edited
+            .lines()
+            .filter(|line| !line.starts_with(ERROR_COMMENT))
+            .map(|line| format!("{}\n", line))
+            .collect();
+        if edited.trim().is_empty() {
+            return Err(ItackError::EditorFailed(
+                "Aborted: the file was saved empty".to_string(),
+            ));
+        }
+
+        match validate(&edited) {
+            Ok(value) => return Ok(value),
+            Err(e) => {
+                if on_invalid == OnInvalid::Ask && !ask_to_reedit(&e)? {
+                    return Err(e);
+                }
+                content = format!(
+                    "{} {}. Fix it and save, or save an empty file to abort. -->\n{}",
+                    ERROR_COMMENT, e, edited
+                )
+            }
+        }
+    }
+}
+
+/// Show why an edit was rejected and ask whether to re-open the editor.
+/// Returns false when the user aborts or there is no terminal to ask on.
+fn ask_to_reedit(error: &ItackError) -> Result<bool> {
+    if !io::stdin().is_terminal() {
+        return Ok(false);
+    }
+    eprintln!("Invalid edit: {}", error);
+    eprint!("Re-open the editor to fix it? [Y/n] ");
+    io::stderr().flush()?;
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer)?;
+    Ok(!matches!(answer.trim(), "n" | "N" | "no"))
+}
+
+/// Open `content` in the configured editor via a temp file and return what was saved.
+pub fn open_in_editor(project: &Project, content: &str) -> Result<String> {
+    let editor = project.config.get_editor();
+
+    let mut temp_file = tempfile::Builder::new()
+        .suffix(".md")
+        .tempfile()
+        .map_err(|e| ItackError::Other(format!("Failed to create temp file: {}", e)))?;
+
+    temp_file
+        .write_all(content.as_bytes())
+        .map_err(|e| ItackError::Other(format!("Failed to write temp file: {}", e)))?;
+
+    let temp_path = temp_file.path().to_path_buf();
+
+    // Open editor
+    let status = Command::new(&editor)
+        .arg(&temp_path)
+        .status()
+        .map_err(|e| ItackError::EditorFailed(format!("Failed to launch {}: {}", editor, e)))?;
+
+    if !status.success() {
+        return Err(ItackError::EditorFailed(format!(
+            "Editor exited with status: {}",
+            status
+        )));
+    }
+
+    // Read the edited content
+    Ok(std::fs::read_to_string(&temp_path)?)
+}
@@ -0,0 +1,249 @@
+//! itack export command: dump every issue for backup or transfer.
+
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+use git2::Oid;
+use serde::{Deserialize, Serialize};
+
+use crate::core::git::{CommitSummary, dir_history};
+use crate::core::{Issue, Project};
+use crate::error::{ItackError, Result};
+use crate::storage::db::{
+    ARCHIVE_DIR, load_all_issues_from_data_branch, load_archived_issues_from_data_branch,
+};
+
+/// File formats for `export` and `import`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum DataFormat {
+    /// One JSON object per issue per line.
+    Jsonl,
+    /// Comma-separated values with a header row.
+    Csv,
+}
+
+/// Columns available in CSV exports and imports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+#[clap(rename_all = "snake_case")]
+pub enum CsvColumn {
+    Id,
+    Title,
+    Status,
+    Epic,
+    Assignee,
+    DependsOn,
+    Labels,
+    Session,
+    Branch,
+    Created,
+    Body,
+}
+
+impl CsvColumn {
+    /// Columns exported when none are given.
+    pub const DEFAULT: &[CsvColumn] = &[
+        CsvColumn::Id,
+        CsvColumn::Title,
+        CsvColumn::Status,
+        CsvColumn::Epic,
+        CsvColumn::Assignee,
+        CsvColumn::DependsOn,
+    ];
+
+    /// Header name of the column.
+    pub fn name(self) -> &'static str {
+        match self {
+            CsvColumn::Id => "id",
+            CsvColumn::Title => "title",
+            CsvColumn::Status => "status",
+            CsvColumn::Epic => "epic",
+            CsvColumn::Assignee => "assignee",
+            CsvColumn::DependsOn => "depends_on",
+            CsvColumn::Labels => "labels",
+            CsvColumn::Session => "session",
+            CsvColumn::Branch => "branch",
+            CsvColumn::Created => "created",
+            CsvColumn::Body => "body",
+        }
+    }
+
+    /// Cell value of the column for an exported issue. Lists are comma-separated.
+    fn value(self, record: &ExportRecord) -> String {
+        let issue = &record.front_matter;
+        match self {
+            CsvColumn::Id => issue.id.to_string(),
+            CsvColumn::Title => record.title.clone(),
+            CsvColumn::Status => issue.status.to_string(),
+            CsvColumn::Epic => issue.epic.clone().unwrap_or_default(),
+            CsvColumn::Assignee => issue.assignee.clone().unwrap_or_default(),
+            CsvColumn::DependsOn => issue
+                .depends_on
+                .iter()
+                .map(|id| id.to_string())
+                .collect::<Vec<_>>()
+                .join(","),
+            CsvColumn::Labels => issue.labels.join(","),
+            CsvColumn::Session => issue.session.clone().unwrap_or_default(),
+            CsvColumn::Branch => issue.branch.clone().unwrap_or_default(),
+            CsvColumn::Created => issue.created.to_rfc3339(),
+            CsvColumn::Body => record.body.clone(),
+        }
+    }
+}
+
+/// Arguments for the export command.
+#[derive(Debug)]
+pub struct ExportArgs {
+    pub format: DataFormat,
+    /// Include each issue's data branch commit history (JSONL only).
+    pub history: bool,
+    /// CSV columns, in order. Empty means `CsvColumn::DEFAULT`.
+    pub columns: Vec<CsvColumn>,
+    /// Write here instead of stdout.
+    pub output: Option<PathBuf>,
+}
+
+/// One exported issue: a line of a JSONL export.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportRecord {
+    /// ID of the project the issue was exported from.
+    pub project: String,
+    pub front_matter: Issue,
+    pub title: String,
+    pub body: String,
+    /// Commits that touched the issue file, newest first.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub history: Option<Vec<HistoryEntry>>,
+}
+
+/// A commit in an issue's history.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub commit: String,
+    pub time: DateTime<Utc>,
+    pub author: String,
+    pub summary: String,
+}
+
+/// Export every issue.
+pub fn run(args: ExportArgs) -> Result<()> {
+    if args.history && args.format == DataFormat::Csv {
+        return Err(ItackError::Other(
+            "History is only available in JSONL exports".to_string(),
+        ));
+    }
+
+    let project = Project::discover()?;
+    let records = execute(&project, &args)?;
+
+    let mut out: Box<dyn Write> = match &args.output {
+        Some(path) => Box::new(std::fs::File::create(path)?),
+        None => Box::new(std::io::stdout().lock()),
+    };
+    match args.format {
+        DataFormat::Jsonl => {
+            for record in &records {
+                writeln!(out, "{}", serde_json::to_string(record)?)?;
+            }
+        }
+        DataFormat::Csv => write_csv(&mut out, &records, &args.columns)?,
+    }
+    out.flush()?;
+
+    if let Some(path) = &args.output {
+        println!("Exported {} issues to {}", records.len(), path.display());
+    }
+
+    Ok(())
+}
+
+/// Write records as CSV with a header row.
+fn write_csv(out: impl Write, records: &[ExportRecord], columns: &[CsvColumn]) -> Result<()> {
+    let columns = if columns.is_empty() {
+        CsvColumn::DEFAULT
+    } else {
+        columns
+    };
+
+    let mut writer = csv::Writer::from_writer(out);
+    writer.write_record(columns.iter().map(|column| column.name()))?;
+    for record in records {
+        writer.write_record(columns.iter().map(|column| column.value(record)))?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Collect every issue on the data branch as export records, in ID order.
+/// Archived issues are included.
+pub fn execute(project: &Project, args: &ExportArgs) -> Result<Vec<ExportRecord>> {
+    let data_branch = project
+        .config
+        .data_branch
+        .as_deref()
+        .unwrap_or("data/itack");
+
+    let mut issues = load_all_issues_from_data_branch(&project.repo_root, data_branch)?;
+    issues.extend(load_archived_issues_from_data_branch(
+        &project.repo_root,
+        data_branch,
+    )?);
+    issues.sort_by_key(|info| info.issue.id);
+    let mut history = if args.history {
+        issue_history(&project.repo_root, data_branch)?
+    } else {
+        Default::default()
+    };
+
+    Ok(issues
+        .into_iter()
+        .map(|info| {
+            let commits = args.history.then(|| {
+                info.relative_path
+                    .file_name()
+                    .and_then(|name| history.remove(&*name.to_string_lossy()))
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|commit| HistoryEntry {
+                        commit: commit.id.to_string(),
+                        time: commit.time,
+                        author: commit.author,
+                        summary: commit.summary,
+                    })
+                    .collect()
+            });
+            ExportRecord {
+                project: project.metadata.project_id.clone(),
+                front_matter: info.issue,
+                title: info.title,
+                body: info.body,
+                history: commits,
+            }
+        })
+        .collect())
+}
+
+/// Get the history of each issue file on the data branch, newest first, keyed
+/// by file name. Archived files keep the history from before they were archived.
+pub fn issue_history(
+    repo_root: &Path,
+    data_branch: &str,
+) -> Result<HashMap<String, Vec<CommitSummary>>> {
+    let mut history = dir_history(repo_root, data_branch, Path::new(".itack"))?;
+    for (name, commits) in dir_history(repo_root, data_branch, Path::new(ARCHIVE_DIR))? {
+        let entry = history.entry(name).or_default();
+        // Archiving shows up in both directories
+        let seen: HashSet<Oid> = entry.iter().map(|commit| commit.id).collect();
+        entry.extend(
+            commits
+                .into_iter()
+                .filter(|commit| !seen.contains(&commit.id)),
+        );
+        entry.sort_by_key(|commit| Reverse(commit.time));
+    }
+    Ok(history)
+}
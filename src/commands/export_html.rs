@@ -0,0 +1,489 @@
+//! itack export-html command: a read-only static site.
+//!
+//! Layout of the exported directory:
+//! - `index.html`: board with one column per status
+//! - `epics/<slug>.html`: issues in an epic
+//! - `issues/<id>.html`: issue with rendered body, dependencies and history
+//! - `search-index.json`: id, title, status, epic, assignee, url and body of every issue
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+
+use pulldown_cmark::{CowStr, Event, Options, Parser, Tag};
+use serde::Serialize;
+
+use crate::commands::export::issue_history;
+use crate::core::git::CommitSummary;
+use crate::core::{Project, Status};
+use crate::error::Result;
+use crate::storage::db::{
+    IssueInfo, load_all_issues_from_data_branch, load_archived_issues_from_data_branch,
+};
+
+/// Board columns, in display order.
+const COLUMNS: [Status; 4] = [
+    Status::Open,
+    Status::InProgress,
+    Status::Done,
+    Status::WontFix,
+];
+
+const STYLE: &str = r#"body { font-family: system-ui, sans-serif; margin: 0 auto; max-width: 72rem; padding: 1rem 2rem; color: #222; }
+a { color: #0b5cad; text-decoration: none; }
+a:hover { text-decoration: underline; }
+header { display: flex; gap: 1rem; align-items: baseline; border-bottom: 1px solid #ddd; margin-bottom: 1rem; }
+nav a { margin-right: .75rem; }
+.board { display: grid; grid-template-columns: repeat(4, 1fr); gap: 1rem; }
+.column h2 { font-size: 1rem; text-transform: uppercase; color: #555; }
+.card { border: 1px solid #ddd; border-radius: 6px; padding: .5rem .75rem; margin-bottom: .5rem; background: #fafafa; }
+.meta { color: #666; font-size: .85rem; }
+.status { display: inline-block; border-radius: 4px; padding: 0 .4rem; font-size: .8rem; background: #eee; }
+.status-open { background: #dbeafe; }
+.status-in-progress { background: #fef3c7; }
+.status-done { background: #dcfce7; }
+.status-wont-fix { background: #e5e7eb; }
+table { border-collapse: collapse; width: 100%; }
+th, td { text-align: left; padding: .3rem .5rem; border-bottom: 1px solid #eee; vertical-align: top; }
+dl { display: grid; grid-template-columns: max-content auto; gap: .25rem 1rem; }
+dt { color: #666; }
+dd { margin: 0; }
+pre { background: #f5f5f5; padding: .75rem; overflow-x: auto; }
+code { font-family: ui-monospace, monospace; }
+#results { list-style: none; padding: 0; }
+"#;
+
+/// Filters the board using `search-index.json` (needs the site to be served over HTTP).
+const SEARCH_SCRIPT: &str = r##"<script>
+let index = null;
+const input = document.getElementById("search");
+const results = document.getElementById("results");
+input.addEventListener("input", async () => {
+  if (index === null) index = await (await fetch("search-index.json")).json();
+  const query = input.value.trim().toLowerCase();
+  results.innerHTML = "";
+  if (!query) return;
+  for (const issue of index) {
+    if (!(issue.title + " " + issue.body).toLowerCase().includes(query)) continue;
+    const item = document.createElement("li");
+    const link = document.createElement("a");
+    link.href = issue.url;
+    link.textContent = "#" + issue.id + " " + issue.title + " (" + issue.status + ")";
+    item.appendChild(link);
+    results.appendChild(item);
+  }
+});
+</script>"##;
+
+/// Arguments for the export-html command.
+pub struct ExportHtmlArgs {
+    pub dir: PathBuf,
+}
+
+/// An entry in `search-index.json`.
+#[derive(Serialize)]
+struct SearchEntry<'a> {
+    id: u32,
+    title: &'a str,
+    status: String,
+    epic: Option<&'a str>,
+    assignee: Option<&'a str>,
+    url: String,
+    body: &'a str,
+}
+
+/// Render the data branch as a static HTML site.
+pub fn run(args: ExportHtmlArgs) -> Result<()> {
+    let project = Project::discover()?;
+    let data_branch = project
+        .config
+        .data_branch
+        .as_deref()
+        .unwrap_or("data/itack");
+
+    let mut issues = load_all_issues_from_data_branch(&project.repo_root, data_branch)?;
+    issues.extend(load_archived_issues_from_data_branch(
+        &project.repo_root,
+        data_branch,
+    )?);
+    let history = issue_history(&project.repo_root, data_branch)?;
+    let site_name = &project.metadata.project_id;
+
+    let mut epics: BTreeMap<&str, Vec<&IssueInfo>> = BTreeMap::new();
+    for info in &issues {
+        if let Some(epic) = &info.issue.epic {
+            epics.entry(epic).or_default().push(info);
+        }
+    }
+    let epic_slugs = epic_slugs(epics.keys().copied());
+
+    fs::create_dir_all(args.dir.join("issues"))?;
+    fs::create_dir_all(args.dir.join("epics"))?;
+    fs::write(args.dir.join("style.css"), STYLE)?;
+
+    fs::write(
+        args.dir.join("index.html"),
+        render_board(site_name, &issues, &epic_slugs),
+    )?;
+
+    for (epic, epic_issues) in &epics {
+        fs::write(
+            args.dir
+                .join("epics")
+                .join(format!("{}.html", epic_slugs[epic])),
+            render_epic(site_name, epic, epic_issues, &epic_slugs),
+        )?;
+    }
+
+    for info in &issues {
+        let file_name = info
+            .relative_path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let commits = history.get(&file_name).map(Vec::as_slice).unwrap_or(&[]);
+        fs::write(
+            args.dir
+                .join("issues")
+                .join(format!("{}.html", info.issue.id)),
+            render_issue(site_name, info, &issues, commits, &epic_slugs),
+        )?;
+    }
+
+    let index: Vec<SearchEntry> = issues
+        .iter()
+        .map(|info| SearchEntry {
+            id: info.issue.id,
+            title: &info.title,
+            status: info.issue.status.to_string(),
+            epic: info.issue.epic.as_deref(),
+            assignee: info.issue.assignee.as_deref(),
+            url: format!("issues/{}.html", info.issue.id),
+            body: &info.body,
+        })
+        .collect();
+    fs::write(
+        args.dir.join("search-index.json"),
+        serde_json::to_string(&index)?,
+    )?;
+
+    println!("Exported {} issues to {}", issues.len(), args.dir.display());
+
+    Ok(())
+}
+
+/// Wrap page content in the shared layout. `root` is the relative path to the site root.
+fn page(site_name: &str, title: &str, root: &str, epics: &EpicSlugs, content: &str) -> String {
+    let nav: String = epics
+        .iter()
+        .map(|(epic, slug)| {
+            format!(
+                r#"<a href="{root}epics/{}.html">{}</a>"#,
+                slug,
+                escape(epic)
+            )
+        })
+        .collect();
+
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>{} - {}</title>
+<link rel="stylesheet" href="{root}style.css">
+</head>
+<body>
+<header><h1><a href="{root}index.html">{}</a></h1><nav>{}</nav></header>
+{}
+</body>
+</html>
+"#,
+        escape(title),
+        escape(site_name),
+        escape(site_name),
+        nav,
+        content
+    )
+}
+
+fn render_board(site_name: &str, issues: &[IssueInfo], epics: &EpicSlugs) -> String {
+    let mut content = String::from(
+        r#"<p><input id="search" type="search" placeholder="Search issues"></p>
+<ul id="results"></ul>
+<div class="board">
+"#,
+    );
+
+    for status in COLUMNS {
+        let cards: Vec<&IssueInfo> = issues
+            .iter()
+            .filter(|info| info.issue.status == status)
+            .collect();
+        content.push_str(&format!(
+            "<section class=\"column\">\n<h2>{} ({})</h2>\n",
+            status,
+            cards.len()
+        ));
+        for info in cards {
+            let mut meta = Vec::new();
+            if let Some(epic) = &info.issue.epic {
+                meta.push(format!(
+                    r#"<a href="epics/{}.html">{}</a>"#,
+                    epics[epic.as_str()],
+                    escape(epic)
+                ));
+            }
+            if let Some(assignee) = &info.issue.assignee {
+                meta.push(escape(assignee));
+            }
+            content.push_str(&format!(
+                "<div class=\"card\"><a href=\"issues/{id}.html\">#{id} {}</a><div class=\"meta\">{}</div></div>\n",
+                escape(&info.title),
+                meta.join(" &middot; "),
+                id = info.issue.id,
+            ));
+        }
+        content.push_str("</section>\n");
+    }
+    content.push_str("</div>\n");
+    content.push_str(SEARCH_SCRIPT);
+
+    page(site_name, "Board", "", epics, &content)
+}
+
+fn render_epic(site_name: &str, epic: &str, issues: &[&IssueInfo], epics: &EpicSlugs) -> String {
+    let done = issues
+        .iter()
+        .filter(|info| info.issue.status.is_closed())
+        .count();
+    let mut content = format!(
+        "<h2>Epic: {}</h2>\n<p>{} of {} issues closed</p>\n<table>\n<tr><th>ID</th><th>Title</th><th>Status</th><th>Assignee</th></tr>\n",
+        escape(epic),
+        done,
+        issues.len()
+    );
+    for info in issues {
+        content.push_str(&format!(
+            "<tr><td>#{id}</td><td><a href=\"../issues/{id}.html\">{}</a></td><td>{}</td><td>{}</td></tr>\n",
+            escape(&info.title),
+            status_badge(info.issue.status),
+            escape(info.issue.assignee.as_deref().unwrap_or("-")),
+            id = info.issue.id,
+        ));
+    }
+    content.push_str("</table>\n");
+
+    page(site_name, &format!("Epic {}", epic), "../", epics, &content)
+}
+
+fn render_issue(
+    site_name: &str,
+    info: &IssueInfo,
+    issues: &[IssueInfo],
+    commits: &[CommitSummary],
+    epics: &EpicSlugs,
+) -> String {
+    let by_id: HashMap<u32, &IssueInfo> = issues.iter().map(|i| (i.issue.id, i)).collect();
+    let issue_link = |id: u32| match by_id.get(&id) {
+        Some(dep) => format!(
+            "<a href=\"{id}.html\">#{id} {}</a> {}",
+            escape(&dep.title),
+            status_badge(dep.issue.status)
+        ),
+        None => format!("#{} (missing)", id),
+    };
+
+    let depends_on: Vec<String> = info
+        .issue
+        .depends_on
+        .iter()
+        .map(|id| issue_link(*id))
+        .collect();
+    let blocks: Vec<String> = issues
+        .iter()
+        .filter(|other| other.issue.depends_on.contains(&info.issue.id))
+        .map(|other| issue_link(other.issue.id))
+        .collect();
+    let or_dash = |items: Vec<String>| {
+        if items.is_empty() {
+            "-".to_string()
+        } else {
+            items.join("<br>")
+        }
+    };
+
+    let epic = match &info.issue.epic {
+        Some(epic) => format!(
+            "<a href=\"../epics/{}.html\">{}</a>",
+            epics[epic.as_str()],
+            escape(epic)
+        ),
+        None => "-".to_string(),
+    };
+
+    let mut content = format!(
+        "<h2>#{} {}</h2>\n<dl>\n<dt>Status</dt><dd>{}</dd>\n<dt>Epic</dt><dd>{}</dd>\n<dt>Assignee</dt><dd>{}</dd>\n<dt>Created</dt><dd>{}</dd>\n<dt>Depends on</dt><dd>{}</dd>\n<dt>Blocks</dt><dd>{}</dd>\n</dl>\n",
+        info.issue.id,
+        escape(&info.title),
+        status_badge(info.issue.status),
+        epic,
+        escape(info.issue.assignee.as_deref().unwrap_or("-")),
+        info.issue.created.format("%Y-%m-%d %H:%M UTC"),
+        or_dash(depends_on),
+        or_dash(blocks),
+    );
+
+    if !info.body.trim().is_empty() {
+        content.push_str("<h3>Description</h3>\n");
+        content.push_str(&render_markdown(&info.body));
+    }
+
+    content.push_str("<h3>History</h3>\n<table>\n<tr><th>Date</th><th>Author</th><th>Change</th><th>Commit</th></tr>\n");
+    for commit in commits {
+        let id = commit.id.to_string();
+        content.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td><code>{}</code></td></tr>\n",
+            commit.time.format("%Y-%m-%d %H:%M UTC"),
+            escape(&commit.author),
+            escape(&commit.summary),
+            &id[..id.len().min(8)],
+        ));
+    }
+    content.push_str("</table>\n");
+
+    page(
+        site_name,
+        &format!("#{} {}", info.issue.id, info.title),
+        "../",
+        epics,
+        &content,
+    )
+}
+
+fn status_badge(status: Status) -> String {
+    format!("<span class=\"status status-{status}\">{status}</span>")
+}
+
+/// Render an issue body as HTML.
+/// Bodies are untrusted, so raw HTML is shown as text and script links are dropped.
+fn render_markdown(text: &str) -> String {
+    let options =
+        Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS;
+    let parser = Parser::new_ext(text, options).map(|event| match event {
+        Event::Html(html) | Event::InlineHtml(html) => Event::Text(html),
+        Event::Start(Tag::Link {
+            link_type,
+            dest_url,
+            title,
+            id,
+        }) if is_script_url(&dest_url) => Event::Start(Tag::Link {
+            link_type,
+            dest_url: CowStr::Borrowed("#"),
+            title,
+            id,
+        }),
+        event => event,
+    });
+
+    let mut html = String::new();
+    pulldown_cmark::html::push_html(&mut html, parser);
+    html
+}
+
+fn is_script_url(url: &str) -> bool {
+    let url = url.trim_start().to_ascii_lowercase();
+    url.starts_with("javascript:") || url.starts_with("vbscript:") || url.starts_with("data:")
+}
+
+/// File name (without `.html`) of each epic's page, by epic name.
+type EpicSlugs<'a> = BTreeMap<&'a str, String>;
+
+/// Give each epic a file name. Names that slug to the same value ("MVP" and
+/// "mvp") get a numeric suffix, in name order, so no page overwrites another.
+fn epic_slugs<'a>(epics: impl Iterator<Item = &'a str>) -> EpicSlugs<'a> {
+    let mut used = HashSet::new();
+    epics
+        .map(|epic| {
+            let base = slug(epic);
+            let mut candidate = base.clone();
+            let mut n = 2;
+            while !used.insert(candidate.clone()) {
+                candidate = format!("{}-{}", base, n);
+                n += 1;
+            }
+            (epic, candidate)
+        })
+        .collect()
+}
+
+/// Turn an epic name into a file name.
+fn slug(name: &str) -> String {
+    let slug: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    let slug = slug.trim_matches('-').to_string();
+    if slug.is_empty() {
+        "epic".to_string()
+    } else {
+        slug
+    }
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slug() {
+        assert_eq!(slug("MVP"), "mvp");
+        assert_eq!(slug("Phase 2: API"), "phase-2--api");
+        assert_eq!(slug("!!!"), "epic");
+    }
+
+    #[test]
+    fn test_epic_slugs_are_unique() {
+        let slugs = epic_slugs(["MVP", "Phase 1", "Phase-1", "mvp", "mvp-2"].into_iter());
+        let mut files: Vec<&str> = slugs.values().map(String::as_str).collect();
+        files.sort();
+        files.dedup();
+        assert_eq!(files.len(), 5);
+        assert_eq!(slugs["MVP"], "mvp");
+        assert_eq!(slugs["mvp"], "mvp-2");
+        assert_eq!(slugs["mvp-2"], "mvp-2-2");
+        assert_eq!(slugs["Phase 1"], "phase-1");
+        assert_eq!(slugs["Phase-1"], "phase-1-2");
+    }
+
+    #[test]
+    fn test_render_markdown_is_safe() {
+        let html = render_markdown("**bold** <script>alert(1)</script> [x](javascript:alert(1))");
+        assert!(html.contains("<strong>bold</strong>"));
+        assert!(html.contains("&lt;script&gt;"));
+        assert!(!html.contains("<script>"));
+        assert!(!html.contains("javascript:"));
+    }
+}
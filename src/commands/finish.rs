@@ -0,0 +1,152 @@
+//! itack finish command: complete an issue started with `itack start`.
+
+use std::path::PathBuf;
+
+use crate::commands::done::{self, DoneArgs};
+use crate::commands::reconcile;
+use crate::core::git::{find_worktree, is_branch_merged, remove_worktree};
+use crate::core::{Project, Status};
+use crate::error::{ItackError, Result};
+use crate::output::{self, OutputFormat};
+use crate::storage::db::{IssueInfo, load_issue_from_data_branch};
+
+/// Arguments for the finish command.
+#[derive(Debug)]
+pub struct FinishArgs {
+    /// Issue ID.
+    pub id: u32,
+    /// Remove the issue's worktree if its branch is merged into the mainline.
+    pub remove_worktree: bool,
+}
+
+/// What happened to the issue's worktree.
+#[derive(Debug)]
+pub enum WorktreeCleanup {
+    /// Removal wasn't requested.
+    Kept,
+    Removed(PathBuf),
+    /// The issue has no recorded branch.
+    NoBranch,
+    /// No worktree has the issue's branch checked out.
+    NotFound(String),
+    /// The branch has commits the mainline doesn't.
+    NotMerged {
+        branch: String,
+        mainline: String,
+    },
+    /// The worktree is the one finish was run from.
+    InUse(PathBuf),
+}
+
+/// Result of finishing an issue.
+pub struct FinishOutcome {
+    /// Previous status, or None if the issue was already done.
+    pub old_status: Option<Status>,
+    pub unblocked: Vec<IssueInfo>,
+    pub worktree: WorktreeCleanup,
+}
+
+/// Finish an issue and print what changed.
+pub fn run(args: FinishArgs) -> Result<()> {
+    let project = Project::discover()?;
+    let outcome = execute(&project, &args)?;
+
+    match outcome.old_status {
+        Some(old_status) => {
+            println!(
+                "Updated issue #{} status: {} -> {}",
+                args.id,
+                old_status,
+                Status::Done
+            );
+            output::print_unblocked(&outcome.unblocked);
+        }
+        None => println!("Issue #{} is already done", args.id),
+    }
+
+    match outcome.worktree {
+        WorktreeCleanup::Kept => {}
+        WorktreeCleanup::Removed(path) => println!("Removed worktree {}", path.display()),
+        WorktreeCleanup::NoBranch => {
+            println!("Issue #{} has no branch; no worktree to remove", args.id)
+        }
+        WorktreeCleanup::NotFound(branch) => {
+            println!("No worktree has branch {} checked out", branch)
+        }
+        WorktreeCleanup::NotMerged { branch, mainline } => println!(
+            "Kept worktree: branch {} is not merged into {}",
+            branch, mainline
+        ),
+        WorktreeCleanup::InUse(path) => println!(
+            "Kept worktree {}: run finish from another worktree to remove it",
+            path.display()
+        ),
+    }
+
+    Ok(())
+}
+
+/// Mark the issue done and drop its claim lock, then remove its worktree if
+/// asked and the branch is merged. The assignee and branch stay on the issue
+/// as a record of who did the work and where.
+pub fn execute(project: &Project, args: &FinishArgs) -> Result<FinishOutcome> {
+    let data_branch = project
+        .config
+        .data_branch
+        .as_deref()
+        .unwrap_or("data/itack");
+
+    let issue_info = load_issue_from_data_branch(&project.repo_root, data_branch, args.id)?;
+
+    let (old_status, unblocked) = if issue_info.issue.status == Status::Done {
+        (None, Vec::new())
+    } else {
+        let done_args = DoneArgs {
+            id: args.id,
+            format: OutputFormat::Table,
+        };
+        let (old_status, unblocked) = done::execute(project, &done_args)?;
+        (Some(old_status), unblocked)
+    };
+
+    let mut db = project.open_db()?;
+    match db.release(args.id) {
+        Ok(()) | Err(ItackError::NotClaimed(_)) => {}
+        Err(e) => return Err(e),
+    }
+
+    let worktree = if args.remove_worktree {
+        clean_up_worktree(project, issue_info.issue.branch.as_deref())?
+    } else {
+        WorktreeCleanup::Kept
+    };
+
+    Ok(FinishOutcome {
+        old_status,
+        unblocked,
+        worktree,
+    })
+}
+
+fn clean_up_worktree(project: &Project, branch: Option<&str>) -> Result<WorktreeCleanup> {
+    let Some(branch) = branch else {
+        return Ok(WorktreeCleanup::NoBranch);
+    };
+    let mainline = reconcile::mainline(project);
+
+    if !is_branch_merged(&project.repo_root, branch, mainline)? {
+        return Ok(WorktreeCleanup::NotMerged {
+            branch: branch.to_string(),
+            mainline: mainline.to_string(),
+        });
+    }
+    let Some(path) = find_worktree(&project.repo_root, branch)? else {
+        return Ok(WorktreeCleanup::NotFound(branch.to_string()));
+    };
+    if path.canonicalize()? == project.repo_root.canonicalize()? {
+        return Ok(WorktreeCleanup::InUse(path));
+    }
+
+    remove_worktree(&project.repo_root, &path)?;
+    Ok(WorktreeCleanup::Removed(path))
+}
@@ -0,0 +1,87 @@
+//! itack hook install command: git hooks that scan new commits for issue references.
+
+use std::fs;
+use std::path::PathBuf;
+
+use crate::core::Project;
+use crate::core::git::hooks_dir;
+use crate::error::{ItackError, Result};
+
+/// Marker line identifying hooks written by itack, so they can be updated in place.
+const MARKER: &str = "# Installed by itack";
+
+/// Arguments for the hook install command.
+#[derive(Debug)]
+pub struct HookInstallArgs {
+    /// Also install a post-merge hook, so merged and pulled commits are scanned.
+    pub post_merge: bool,
+    /// Overwrite existing hooks that were not installed by itack.
+    pub force: bool,
+}
+
+/// Install the git hooks and print where they went.
+pub fn run(args: HookInstallArgs) -> Result<()> {
+    let project = Project::discover()?;
+    for path in execute(&project, &args)? {
+        println!("Installed {}", path.display());
+    }
+    Ok(())
+}
+
+/// Write the hook scripts. Returns the paths written.
+pub fn execute(project: &Project, args: &HookInstallArgs) -> Result<Vec<PathBuf>> {
+    let dir = hooks_dir(&project.repo_root)?;
+    fs::create_dir_all(&dir)?;
+
+    let mut hooks = vec![("post-commit", "HEAD")];
+    if args.post_merge {
+        hooks.push(("post-merge", "ORIG_HEAD..HEAD"));
+    }
+
+    // Check every hook before writing any, so a conflict leaves nothing half-installed
+    for (name, _) in &hooks {
+        let path = dir.join(name);
+        if !args.force
+            && let Ok(existing) = fs::read_to_string(&path)
+            && !existing.contains(MARKER)
+        {
+            return Err(ItackError::Other(format!(
+                "{} already exists; use --force to overwrite it",
+                path.display()
+            )));
+        }
+    }
+
+    let mut written = Vec::new();
+    for (name, range) in hooks {
+        let path = dir.join(name);
+        fs::write(&path, script(range))?;
+        make_executable(&path)?;
+        written.push(path);
+    }
+
+    Ok(written)
+}
+
+/// A hook script that scans `range` for issue references.
+fn script(range: &str) -> String {
+    format!(
+        "#!/bin/sh\n\
+         {}: close and link issues mentioned in commit messages.\n\
+         command -v itack >/dev/null 2>&1 || exit 0\n\
+         itack scan-commits {}\n",
+        MARKER, range
+    )
+}
+
+#[cfg(unix)]
+fn make_executable(path: &std::path::Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755))?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn make_executable(_path: &std::path::Path) -> Result<()> {
+    Ok(())
+}
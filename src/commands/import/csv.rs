@@ -0,0 +1,601 @@
+//! itack import --format csv: create or update issues from a spreadsheet.
+//!
+//! The first row names the columns, using the same names as `itack export
+//! --format csv`. Rows without an `id` create issues. Rows with one update that
+//! issue's title, status, epic, assignee, dependencies and labels; other columns
+//! are ignored for updates. An empty `epic`, `assignee`, `depends_on` or
+//! `labels` cell clears the field, while an empty `title` or `status` leaves it
+//! unchanged.
+
+use std::collections::{HashMap, HashSet};
+use std::io::Read;
+use std::path::PathBuf;
+
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+
+use crate::commands::archive::unarchive_reopened;
+use crate::commands::batch::{ClaimUndo, undo_claims};
+use crate::commands::done::cascade_unblocked;
+use crate::commands::export::CsvColumn;
+use crate::core::hooks::{self, HookEvent};
+use crate::core::{Issue, Project, Status, TreeEdit};
+use crate::error::{ItackError, Result};
+use crate::output;
+use crate::storage::Database;
+use crate::storage::db::{
+    IssueInfo, load_all_issues_from_data_branch, load_archived_issues_from_data_branch,
+};
+use crate::storage::markdown::format_issue;
+
+/// Arguments for importing a CSV file.
+#[derive(Debug)]
+pub struct CsvImportArgs {
+    /// CSV file, or `-` for stdin.
+    pub file: PathBuf,
+    /// Show the changes without committing them.
+    pub dry_run: bool,
+}
+
+/// Changes made (or, on a dry run, planned) by a CSV import.
+#[derive(Default)]
+pub struct CsvImportSummary {
+    /// New issues. On a dry run their IDs are the ones they would get.
+    pub created: Vec<IssueInfo>,
+    /// Changed issues, as (before, after).
+    pub updated: Vec<(IssueInfo, IssueInfo)>,
+    /// Rows that matched their issue exactly.
+    pub unchanged: usize,
+    /// Issues that became ready because the import closed their dependencies.
+    pub unblocked: Vec<IssueInfo>,
+}
+
+/// Import a CSV file, printing a diff of the changes.
+pub fn run(args: CsvImportArgs) -> Result<()> {
+    let project = Project::discover()?;
+    let summary = execute(&project, &args)?;
+
+    for info in &summary.created {
+        println!("+ #{} {}", info.issue.id, info.title);
+        for (field, _, after) in field_changes(None, info) {
+            println!("    {}: {}", field, after);
+        }
+    }
+    for (before, after) in &summary.updated {
+        println!("~ #{} {}", after.issue.id, before.title);
+        for (field, old, new) in field_changes(Some(before), after) {
+            println!("    {}: {} -> {}", field, old, new);
+        }
+    }
+
+    let counts = format!(
+        "{} created, {} updated, {} unchanged",
+        summary.created.len(),
+        summary.updated.len(),
+        summary.unchanged
+    );
+    if args.dry_run {
+        println!("Dry run, nothing committed: {}", counts);
+    } else {
+        println!("Imported CSV: {}", counts);
+        output::print_unblocked(&summary.unblocked);
+    }
+
+    Ok(())
+}
+
+/// Apply a CSV file to the tracker in a single data branch commit. Claims,
+/// hooks and unblocked dependents follow the changes as they would with
+/// `create`, `set`, `done` and `wont-fix`.
+pub fn execute(project: &Project, args: &CsvImportArgs) -> Result<CsvImportSummary> {
+    let mut content = String::new();
+    if args.file.as_os_str() == "-" {
+        std::io::stdin().read_to_string(&mut content)?;
+    } else {
+        content = std::fs::read_to_string(&args.file)?;
+    }
+    let rows = parse_rows(&content)?;
+
+    let data_branch = project
+        .config
+        .data_branch
+        .as_deref()
+        .unwrap_or("data/itack");
+    // A CSV can create the first issues of a project; unreadable issues are an error
+    let mut existing = match load_all_issues_from_data_branch(&project.repo_root, data_branch) {
+        Ok(existing) => existing,
+        Err(ItackError::DataBranchNotFound(_) | ItackError::DataBranchEmpty(_)) => Vec::new(),
+        Err(e) => return Err(e),
+    };
+    // Archived issues can still be updated, and depended on
+    match load_archived_issues_from_data_branch(&project.repo_root, data_branch) {
+        Ok(archived) => existing.extend(archived),
+        Err(ItackError::DataBranchNotFound(_)) => {}
+        Err(e) => return Err(e),
+    }
+    let existing: HashMap<u32, IssueInfo> = existing
+        .into_iter()
+        .map(|info| (info.issue.id, info))
+        .collect();
+
+    // Validate every row before allocating IDs or changing anything
+    let mut summary = CsvImportSummary::default();
+    let mut seen = HashSet::new();
+    for row in rows {
+        let fail = |message: String| ItackError::Other(format!("Row {}: {}", row.line, message));
+
+        for dep in row.depends_on.iter().flatten() {
+            if !existing.contains_key(dep) {
+                return Err(fail(format!("unknown dependency #{}", dep)));
+            }
+        }
+
+        let Some(id) = row.id else {
+            let title = row
+                .title
+                .clone()
+                .ok_or_else(|| fail("a title is needed to create an issue".to_string()))?;
+
+            // The ID and path are filled in once IDs are allocated
+            let mut issue = Issue::new(0);
+            if let Some(created) = row.created {
+                issue.created = created;
+            }
+            row.apply(&mut issue);
+            summary.created.push(IssueInfo {
+                issue,
+                title,
+                body: row.body.unwrap_or_default(),
+                relative_path: PathBuf::new(),
+            });
+            continue;
+        };
+
+        if !seen.insert(id) {
+            return Err(fail(format!("issue #{} appears more than once", id)));
+        }
+        let before = existing
+            .get(&id)
+            .ok_or_else(|| fail(format!("issue #{} not found", id)))?;
+        if row
+            .depends_on
+            .as_ref()
+            .is_some_and(|deps| deps.contains(&id))
+        {
+            return Err(fail(format!("issue #{} can't depend on itself", id)));
+        }
+
+        let mut after = before.clone();
+        if let Some(title) = &row.title {
+            after.title = title.clone();
+        }
+        row.apply(&mut after.issue);
+
+        if field_changes(Some(before), &after).is_empty() {
+            summary.unchanged += 1;
+        } else {
+            summary.updated.push((before.clone(), after));
+        }
+    }
+
+    let mut db = project.open_db()?;
+    let creates = summary.created.len() as u32;
+    let first_id = if creates == 0 {
+        0
+    } else if args.dry_run {
+        db.peek_next_issue_id()?
+    } else {
+        db.allocate_issue_ids(creates)?
+    };
+    for (offset, info) in summary.created.iter_mut().enumerate() {
+        info.issue.id = first_id + offset as u32;
+        info.relative_path = Project::issue_relative_path(info.issue.id, &info.issue.created);
+    }
+
+    if args.dry_run || (summary.created.is_empty() && summary.updated.is_empty()) {
+        return Ok(summary);
+    }
+
+    // An import that is rejected before its commit doesn't use up any IDs
+    let give_back_ids = |db: &Database| {
+        if creates > 0 {
+            let _ = db.unallocate_issue_ids(first_id, creates);
+        }
+    };
+
+    // Reopened archived issues move back out of the archive
+    let mut edit = TreeEdit::new();
+    for (_, after) in &mut summary.updated {
+        unarchive_reopened(&mut edit, after);
+    }
+
+    let changes: Vec<(Option<&IssueInfo>, &IssueInfo)> = summary
+        .created
+        .iter()
+        .map(|info| (None, info))
+        .chain(
+            summary
+                .updated
+                .iter()
+                .map(|(before, after)| (Some(before), after)),
+        )
+        .collect();
+
+    let mut post_hooks = Vec::new();
+    for &(before, after) in &changes {
+        let result = match before {
+            None => hooks::run(project, HookEvent::PreCreate, after, &[])
+                .map(|()| post_hooks.push((HookEvent::PostCreate, after, Vec::new()))),
+            Some(before) => (|| {
+                hooks::run(project, HookEvent::PreEdit, after, &[])?;
+                post_hooks.push((HookEvent::PostEdit, after, Vec::new()));
+                if let Some((pre, post)) = close_events(before.issue.status, after.issue.status) {
+                    let env = vec![("ITACK_OLD_STATUS", before.issue.status.to_string())];
+                    hooks::run(project, pre, after, &env)?;
+                    post_hooks.push((post, after, env));
+                }
+                Ok(())
+            })(),
+        };
+        if let Err(e) = result {
+            give_back_ids(&db);
+            return Err(e);
+        }
+    }
+
+    // Claims are taken before committing so the claim policy can still veto the import
+    let undo = match sync_claims(&mut db, &changes) {
+        Ok(undo) => undo,
+        Err(e) => {
+            give_back_ids(&db);
+            return Err(e);
+        }
+    };
+
+    for &(_, info) in &changes {
+        let content = match format_issue(&info.issue, &info.title, &info.body) {
+            Ok(content) => content,
+            Err(e) => {
+                undo_claims(&mut db, undo);
+                give_back_ids(&db);
+                return Err(e);
+            }
+        };
+        edit.write(&info.relative_path, content);
+    }
+    let message = format!(
+        "Import CSV: {} created, {} updated",
+        summary.created.len(),
+        summary.updated.len()
+    );
+    if let Err(e) = edit.commit(&project.repo_root, data_branch, &message) {
+        undo_claims(&mut db, undo);
+        give_back_ids(&db);
+        return Err(e);
+    }
+
+    for (event, info, env) in &post_hooks {
+        hooks::run(project, *event, info, env)?;
+    }
+    for (before, after) in &summary.updated {
+        if after.issue.status.is_closed() && !before.issue.status.is_closed() {
+            summary
+                .unblocked
+                .extend(cascade_unblocked(project, data_branch, after.issue.id)?);
+        }
+    }
+
+    Ok(summary)
+}
+
+/// Hook events for a status change that closes an issue.
+fn close_events(old: Status, new: Status) -> Option<(HookEvent, HookEvent)> {
+    match new {
+        Status::Done if old != Status::Done => Some((HookEvent::PreDone, HookEvent::PostDone)),
+        Status::WontFix if old != Status::WontFix => {
+            Some((HookEvent::PreWontFix, HookEvent::PostWontFix))
+        }
+        _ => None,
+    }
+}
+
+/// Who holds the claim lock on an issue: its assignee, unless it is closed.
+fn claim_holder(info: &IssueInfo) -> Option<&str> {
+    if info.issue.status.is_closed() {
+        return None;
+    }
+    info.issue.assignee.as_deref()
+}
+
+/// Bring database claims in line with the imported assignees and statuses,
+/// as claim, release, done and wont-fix would. On failure every claim change
+/// already made is undone.
+fn sync_claims(
+    db: &mut Database,
+    changes: &[(Option<&IssueInfo>, &IssueInfo)],
+) -> Result<Vec<ClaimUndo>> {
+    let mut undo = Vec::new();
+    for &(before, after) in changes {
+        let old_holder = before.and_then(claim_holder);
+        let new_holder = claim_holder(after);
+        if old_holder == new_holder {
+            continue;
+        }
+
+        let id = after.issue.id;
+        let result = (|| {
+            if let Some(old) = old_holder {
+                match db.release(id) {
+                    Ok(()) => undo.push(ClaimUndo::Released(
+                        id,
+                        old.to_string(),
+                        before.and_then(|info| info.issue.epic.clone()),
+                    )),
+                    Err(ItackError::NotClaimed(_)) => {}
+                    Err(e) => return Err(e),
+                }
+            }
+            if let Some(assignee) = new_holder {
+                db.claim(id, assignee, after.issue.epic.as_deref())?;
+                undo.push(ClaimUndo::Claimed(id));
+            }
+            Ok(())
+        })();
+
+        if let Err(e) = result {
+            undo_claims(db, undo);
+            return Err(e);
+        }
+    }
+    Ok(undo)
+}
+
+/// One parsed CSV row. `None` means the column is absent (or, for `title`,
+/// `status` and `id`, empty).
+#[derive(Debug, Default)]
+struct Row {
+    /// Line number in the file, for error messages.
+    line: u64,
+    id: Option<u32>,
+    title: Option<String>,
+    status: Option<Status>,
+    epic: Option<Option<String>>,
+    assignee: Option<Option<String>>,
+    depends_on: Option<Vec<u32>>,
+    labels: Option<Vec<String>>,
+    created: Option<DateTime<Utc>>,
+    body: Option<String>,
+}
+
+impl Row {
+    /// Apply the row's updatable fields to an issue.
+    fn apply(&self, issue: &mut Issue) {
+        if let Some(status) = self.status {
+            issue.status = status;
+        }
+        if let Some(epic) = &self.epic {
+            issue.epic = epic.clone();
+        }
+        if let Some(assignee) = &self.assignee {
+            issue.assignee = assignee.clone();
+        }
+        if let Some(depends_on) = &self.depends_on {
+            issue.depends_on = depends_on.clone();
+        }
+        if let Some(labels) = &self.labels {
+            issue.labels = labels.clone();
+        }
+    }
+}
+
+fn parse_rows(content: &str) -> Result<Vec<Row>> {
+    let mut reader = ::csv::ReaderBuilder::new()
+        .trim(::csv::Trim::All)
+        .from_reader(content.as_bytes());
+
+    let columns = reader
+        .headers()?
+        .iter()
+        .map(|name| {
+            CsvColumn::from_str(name, true)
+                .map_err(|_| ItackError::Other(format!("Unknown CSV column '{}'", name)))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let record = record?;
+        let line = record.position().map(|p| p.line()).unwrap_or_default();
+        let fail = |message: String| ItackError::Other(format!("Row {}: {}", line, message));
+        let optional = |value: &str| (!value.is_empty()).then(|| value.to_string());
+
+        let mut row = Row {
+            line,
+            ..Row::default()
+        };
+        for (column, value) in columns.iter().zip(record.iter()) {
+            match column {
+                CsvColumn::Id => {
+                    row.id = match value {
+                        "" => None,
+                        _ => Some(
+                            value
+                                .trim_start_matches('#')
+                                .parse()
+                                .map_err(|_| fail(format!("invalid id '{}'", value)))?,
+                        ),
+                    }
+                }
+                CsvColumn::Title => row.title = optional(value),
+                CsvColumn::Status => {
+                    row.status = match value {
+                        "" => None,
+                        _ => Some(
+                            Status::from_str(value, true)
+                                .map_err(|_| fail(format!("invalid status '{}'", value)))?,
+                        ),
+                    }
+                }
+                CsvColumn::Epic => row.epic = Some(optional(value)),
+                CsvColumn::Assignee => row.assignee = Some(optional(value)),
+                CsvColumn::DependsOn => {
+                    row.depends_on = Some(
+                        parse_id_list(value)
+                            .map_err(|_| fail(format!("invalid depends_on '{}'", value)))?,
+                    )
+                }
+                CsvColumn::Labels => {
+                    row.labels = Some(split_list(value).map(String::from).collect())
+                }
+                CsvColumn::Created => {
+                    row.created = match value {
+                        "" => None,
+                        _ => Some(
+                            DateTime::parse_from_rfc3339(value)
+                                .map_err(|_| fail(format!("invalid created '{}'", value)))?
+                                .with_timezone(&Utc),
+                        ),
+                    }
+                }
+                CsvColumn::Body => row.body = Some(value.to_string()),
+                CsvColumn::Session | CsvColumn::Branch => {}
+            }
+        }
+        rows.push(row);
+    }
+
+    Ok(rows)
+}
+
+/// Split a list cell on commas, semicolons or whitespace.
+fn split_list(value: &str) -> impl Iterator<Item = &str> {
+    value
+        .split(|c: char| c == ',' || c == ';' || c.is_whitespace())
+        .filter(|item| !item.is_empty())
+}
+
+/// Parse a list of issue IDs such as `1, 2` or `#1 #2`, sorted and deduplicated.
+fn parse_id_list(value: &str) -> std::result::Result<Vec<u32>, std::num::ParseIntError> {
+    let mut ids = split_list(value)
+        .map(|item| item.trim_start_matches('#').parse())
+        .collect::<std::result::Result<Vec<u32>, _>>()?;
+    ids.sort();
+    ids.dedup();
+    Ok(ids)
+}
+
+/// Fields that differ between two versions of an issue, as (field, before, after).
+/// With no `before`, lists the fields of a new issue that are set.
+fn field_changes(
+    before: Option<&IssueInfo>,
+    after: &IssueInfo,
+) -> Vec<(&'static str, String, String)> {
+    fn fields(info: &IssueInfo) -> [(&'static str, String); 6] {
+        let issue = &info.issue;
+        let list = |items: Vec<String>| {
+            if items.is_empty() {
+                "-".to_string()
+            } else {
+                items.join(", ")
+            }
+        };
+        [
+            ("title", info.title.clone()),
+            ("status", issue.status.to_string()),
+            (
+                "epic",
+                issue.epic.clone().unwrap_or_else(|| "-".to_string()),
+            ),
+            (
+                "assignee",
+                issue.assignee.clone().unwrap_or_else(|| "-".to_string()),
+            ),
+            (
+                "depends_on",
+                list(
+                    issue
+                        .depends_on
+                        .iter()
+                        .map(|id| format!("#{}", id))
+                        .collect(),
+                ),
+            ),
+            ("labels", list(issue.labels.clone())),
+        ]
+    }
+
+    let after_fields = fields(after);
+    match before {
+        Some(before) => fields(before)
+            .into_iter()
+            .zip(after_fields)
+            .filter(|((_, old), (_, new))| old != new)
+            .map(|((field, old), (_, new))| (field, old, new))
+            .collect(),
+        // The title is already shown on the `+` line
+        None => after_fields
+            .into_iter()
+            .skip(1)
+            .filter(|(field, value)| *field == "status" || value != "-")
+            .map(|(field, value)| (field, String::new(), value))
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn test_parse_rows() {
+        let rows = parse_rows(
+            "id,title,status,epic,assignee,depends_on\n\
+             ,New issue,,v1,,\n\
+             2,,Done,,alice,\"#1, 3\"\n",
+        )
+        .unwrap();
+
+        assert_eq!(rows[0].id, None);
+        assert_eq!(rows[0].title.as_deref(), Some("New issue"));
+        assert_eq!(rows[0].status, None);
+        assert_eq!(rows[0].epic, Some(Some("v1".to_string())));
+        assert_eq!(rows[0].assignee, Some(None));
+        assert_eq!(rows[0].depends_on, Some(vec![]));
+
+        assert_eq!(rows[1].id, Some(2));
+        assert_eq!(rows[1].title, None);
+        assert_eq!(rows[1].status, Some(Status::Done));
+        assert_eq!(rows[1].depends_on, Some(vec![1, 3]));
+        assert_eq!(rows[1].line, 3);
+    }
+
+    #[test]
+    fn test_parse_rows_rejects_bad_input() {
+        assert!(parse_rows("id,priority\n1,high\n").is_err());
+        assert!(parse_rows("id,status\n1,started\n").is_err());
+        assert!(parse_rows("id,depends_on\n1,two\n").is_err());
+    }
+
+    #[test]
+    fn test_field_changes() {
+        let before = IssueInfo {
+            issue: Issue::new(1),
+            title: "Title".to_string(),
+            body: String::new(),
+            relative_path: Path::new(".itack/x.md").to_path_buf(),
+        };
+        let mut after = before.clone();
+        after.issue.status = Status::Done;
+        after.issue.depends_on = vec![2, 3];
+
+        assert_eq!(
+            field_changes(Some(&before), &after),
+            vec![
+                ("status", "open".to_string(), "done".to_string()),
+                ("depends_on", "-".to_string(), "#2, #3".to_string()),
+            ]
+        );
+        assert!(field_changes(Some(&before), &before).is_empty());
+    }
+}
@@ -0,0 +1,297 @@
+//! itack import github: import a GitHub issues JSON export.
+//!
+//! Accepts the output of `gh issue list --json ...` (camelCase fields) as well
+//! as a REST API dump (snake_case fields). Pull requests in an API dump are
+//! skipped.
+
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use super::{ImportSummary, commit_issues, record_imports, rewrite_references};
+use crate::core::{Issue, Project, Status};
+use crate::error::{ItackError, Result};
+use crate::storage::db::IssueInfo;
+use crate::storage::markdown::append_comment;
+
+/// Source name recorded in the import mapping table and `imported_from`.
+const SOURCE: &str = "github";
+
+/// Arguments for the import github command.
+#[derive(Debug)]
+pub struct GithubImportArgs {
+    /// JSON file containing an array of issues.
+    pub file: PathBuf,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GithubIssue {
+    number: u64,
+    title: String,
+    #[serde(default)]
+    body: Option<String>,
+    #[serde(default)]
+    state: Option<String>,
+    #[serde(default, alias = "state_reason")]
+    state_reason: Option<String>,
+    #[serde(default)]
+    labels: Vec<GithubLabel>,
+    #[serde(default)]
+    assignees: Vec<GithubUser>,
+    #[serde(default)]
+    assignee: Option<GithubUser>,
+    #[serde(default)]
+    milestone: Option<GithubMilestone>,
+    #[serde(default)]
+    comments: Option<GithubComments>,
+    #[serde(default, alias = "created_at")]
+    created_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    url: Option<String>,
+    #[serde(default)]
+    html_url: Option<String>,
+    #[serde(default)]
+    pull_request: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum GithubLabel {
+    Object { name: String },
+    Name(String),
+}
+
+#[derive(Debug, Deserialize)]
+struct GithubUser {
+    login: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GithubMilestone {
+    title: String,
+}
+
+/// `gh` exports comments inline; the REST API only gives a count.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum GithubComments {
+    List(Vec<GithubComment>),
+    Count(#[allow(dead_code)] u64),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GithubComment {
+    #[serde(default, alias = "user")]
+    author: Option<GithubUser>,
+    #[serde(default)]
+    body: String,
+    #[serde(default, alias = "created_at")]
+    created_at: Option<DateTime<Utc>>,
+}
+
+impl GithubIssue {
+    /// The `owner/repo` this issue belongs to, taken from its URL.
+    fn repo(&self) -> Option<String> {
+        let url = self.html_url.as_deref().or(self.url.as_deref())?;
+        let path = url
+            .split_once("/repos/")
+            .or_else(|| url.split_once("github.com/"))?
+            .1;
+        let mut segments = path.split('/');
+        let owner = segments.next().filter(|s| !s.is_empty())?;
+        let repo = segments.next().filter(|s| !s.is_empty())?;
+        Some(format!("{}/{}", owner, repo))
+    }
+
+    fn assignee(&self) -> Option<&str> {
+        self.assignees
+            .first()
+            .or(self.assignee.as_ref())
+            .map(|user| user.login.as_str())
+    }
+
+    fn status(&self) -> Status {
+        let state = self.state.as_deref().unwrap_or("open");
+        if state.eq_ignore_ascii_case("closed") {
+            match self.state_reason.as_deref() {
+                Some(reason) if reason.eq_ignore_ascii_case("not_planned") => Status::WontFix,
+                _ => Status::Done,
+            }
+        } else if self.assignee().is_some() {
+            Status::InProgress
+        } else {
+            Status::Open
+        }
+    }
+}
+
+/// External ID of an issue: `owner/repo#N`, or `#N` when the repository is unknown.
+fn external_id(repo: Option<&str>, number: u64) -> String {
+    format!("{}#{}", repo.unwrap_or(""), number)
+}
+
+/// Import issues from a GitHub JSON export.
+pub fn run(args: GithubImportArgs) -> Result<()> {
+    let project = Project::discover()?;
+    let summary = execute(&project, &args)?;
+
+    print!("Imported {} issues from GitHub", summary.imported.len());
+    if summary.skipped > 0 {
+        print!(" ({} already imported)", summary.skipped);
+    }
+    println!();
+
+    Ok(())
+}
+
+/// Import issues from a GitHub JSON export and commit them to the data branch.
+pub fn execute(project: &Project, args: &GithubImportArgs) -> Result<ImportSummary> {
+    let content = std::fs::read_to_string(&args.file)?;
+    let mut entries: Vec<GithubIssue> = serde_json::from_str(&content).map_err(|e| {
+        ItackError::Other(format!(
+            "Invalid GitHub export {}: {}",
+            args.file.display(),
+            e
+        ))
+    })?;
+    entries.retain(|entry| entry.pull_request.is_none());
+    entries.sort_by_key(|entry| entry.number);
+
+    let db = project.open_db()?;
+
+    // Allocate IDs up front so references between imported issues can be rewritten
+    let mut ids: HashMap<String, u32> = HashMap::new();
+    let mut seen = HashSet::new();
+    let mut new_entries = Vec::new();
+    let mut skipped = 0;
+    for entry in &entries {
+        let repo = entry.repo();
+        let external = external_id(repo.as_deref(), entry.number);
+        if !seen.insert(external.clone()) {
+            continue;
+        }
+        if let Some(id) = db.find_import(SOURCE, &external)? {
+            ids.insert(external, id);
+            skipped += 1;
+            continue;
+        }
+        new_entries.push((entry, repo, external));
+    }
+
+    if new_entries.is_empty() {
+        return Ok(ImportSummary {
+            imported: Vec::new(),
+            skipped,
+        });
+    }
+
+    let count = new_entries.len() as u32;
+    let first_id = db.allocate_issue_ids(count)?;
+    for (offset, (_, _, external)) in new_entries.iter().enumerate() {
+        ids.insert(external.clone(), first_id + offset as u32);
+    }
+
+    let mut imported = Vec::new();
+    for (entry, repo, external) in new_entries {
+        let id = ids[&external];
+        let resolve = |number: u64| {
+            let target = external_id(repo.as_deref(), number);
+            match ids.get(&target) {
+                Some(id) => Some(format!("#{}", id)),
+                None => match db.find_import(SOURCE, &target) {
+                    Ok(Some(id)) => Some(format!("#{}", id)),
+                    // Leave a qualified GitHub reference so it can't be mistaken for an itack issue
+                    _ => repo.as_ref().map(|repo| format!("{}#{}", repo, number)),
+                },
+            }
+        };
+
+        let mut issue = Issue::new(id);
+        if let Some(created) = entry.created_at {
+            issue.created = created;
+        }
+        issue.assignee = entry.assignee().map(str::to_string);
+        issue.epic = entry.milestone.as_ref().map(|m| m.title.clone());
+        issue.imported_from = Some(format!("{}:{}", SOURCE, external));
+        issue.labels = entry
+            .labels
+            .iter()
+            .map(|label| match label {
+                GithubLabel::Object { name } | GithubLabel::Name(name) => name.clone(),
+            })
+            .collect();
+        issue.status = entry.status();
+
+        let mut body = rewrite_references(entry.body.as_deref().unwrap_or("").trim(), resolve);
+        if let Some(GithubComments::List(comments)) = &entry.comments {
+            for comment in comments {
+                let author = comment
+                    .author
+                    .as_ref()
+                    .map(|user| user.login.as_str())
+                    .unwrap_or("ghost");
+                body = append_comment(
+                    &body,
+                    author,
+                    comment.created_at.unwrap_or(issue.created),
+                    &rewrite_references(&comment.body, resolve),
+                );
+            }
+        }
+
+        let relative_path = Project::issue_relative_path(id, &issue.created);
+        imported.push(IssueInfo {
+            issue,
+            title: entry.title.trim().to_string(),
+            body,
+            relative_path,
+        });
+    }
+
+    if let Err(e) = commit_issues(
+        project,
+        &imported,
+        &format!("Import {} issues from GitHub", imported.len()),
+    ) {
+        // Nothing was written, so give the IDs back
+        let _ = db.unallocate_issue_ids(first_id, count);
+        return Err(e);
+    }
+
+    record_imports(&db, &imported)?;
+
+    Ok(ImportSummary { imported, skipped })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_gh_and_api_exports() {
+        let gh: Vec<GithubIssue> = serde_json::from_str(
+            r#"[{"number": 2, "title": "T", "state": "CLOSED", "stateReason": "NOT_PLANNED",
+                 "labels": [{"name": "bug"}], "assignees": [],
+                 "url": "https://github.com/owner/repo/issues/2",
+                 "comments": [{"author": {"login": "ann"}, "body": "hi"}]}]"#,
+        )
+        .unwrap();
+        assert_eq!(gh[0].repo().as_deref(), Some("owner/repo"));
+        assert_eq!(gh[0].status(), Status::WontFix);
+
+        let api: Vec<GithubIssue> = serde_json::from_str(
+            r#"[{"number": 7, "title": "T", "state": "open", "body": null,
+                 "labels": ["bug"], "assignee": {"login": "bob"}, "comments": 3,
+                 "url": "https://api.github.com/repos/owner/repo/issues/7",
+                 "html_url": "https://github.com/owner/repo/issues/7"}]"#,
+        )
+        .unwrap();
+        assert_eq!(api[0].repo().as_deref(), Some("owner/repo"));
+        assert_eq!(api[0].assignee(), Some("bob"));
+        assert_eq!(api[0].status(), Status::InProgress);
+    }
+}
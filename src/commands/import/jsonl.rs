@@ -0,0 +1,243 @@
+//! itack import --format jsonl: load issues written by `itack export --format jsonl`.
+//!
+//! By default issues keep their IDs, which suits restoring a backup. With
+//! `renumber`, they get fresh IDs and their dependencies and `#N` references
+//! are remapped, which suits merging one tracker into another. Either way,
+//! issues that were already imported are skipped. Exported history is
+//! informational and is not replayed.
+
+use std::collections::{HashMap, HashSet};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use super::{ImportSummary, commit_issues, record_imports, rewrite_references};
+use crate::commands::export::ExportRecord;
+use crate::core::Project;
+use crate::error::{ItackError, Result};
+use crate::storage::Database;
+use crate::storage::db::{
+    IssueInfo, load_all_issues_from_data_branch, load_archived_issues_from_data_branch,
+};
+
+/// Source name for issues imported from another itack project.
+const SOURCE: &str = "itack";
+
+/// Arguments for importing a JSONL export.
+#[derive(Debug)]
+pub struct JsonlImportArgs {
+    /// JSONL file, or `-` for stdin.
+    pub file: PathBuf,
+    /// Give issues new IDs instead of keeping the exported ones.
+    pub renumber: bool,
+}
+
+/// Import a JSONL export.
+pub fn run(args: JsonlImportArgs) -> Result<()> {
+    let project = Project::discover()?;
+    let summary = execute(&project, &args)?;
+
+    print!("Imported {} issues", summary.imported.len());
+    if summary.skipped > 0 {
+        print!(" ({} already imported)", summary.skipped);
+    }
+    println!();
+
+    Ok(())
+}
+
+/// Import a JSONL export and commit the new issues to the data branch.
+pub fn execute(project: &Project, args: &JsonlImportArgs) -> Result<ImportSummary> {
+    let records = read_records(&args.file)?;
+    let db = project.open_db()?;
+
+    let data_branch = project
+        .config
+        .data_branch
+        .as_deref()
+        .unwrap_or("data/itack");
+    // Restoring into a project with no issues yet is fine; unreadable issues are not
+    let mut existing = match load_all_issues_from_data_branch(&project.repo_root, data_branch) {
+        Ok(existing) => existing,
+        Err(ItackError::DataBranchNotFound(_) | ItackError::DataBranchEmpty(_)) => Vec::new(),
+        Err(e) => return Err(e),
+    };
+    // Archived issues still hold their IDs
+    match load_archived_issues_from_data_branch(&project.repo_root, data_branch) {
+        Ok(archived) => existing.extend(archived),
+        Err(ItackError::DataBranchNotFound(_)) => {}
+        Err(e) => return Err(e),
+    }
+
+    let (imported, skipped) = if args.renumber {
+        renumber(&db, records)?
+    } else {
+        preserve_ids(&existing, records)?
+    };
+
+    if imported.is_empty() {
+        return Ok(ImportSummary { imported, skipped });
+    }
+
+    if let Err(e) = commit_issues(
+        project,
+        &imported,
+        &format!("Import {} issues from JSONL", imported.len()),
+    ) {
+        // Renumbered issues got a block of fresh IDs; nothing was written, so give it back
+        if args.renumber {
+            let _ = db.unallocate_issue_ids(imported[0].issue.id, imported.len() as u32);
+        }
+        return Err(e);
+    }
+
+    if let Some(max_id) = imported.iter().map(|info| info.issue.id).max() {
+        db.reserve_issue_ids(max_id)?;
+    }
+    record_imports(&db, &imported)?;
+
+    Ok(ImportSummary { imported, skipped })
+}
+
+fn read_records(file: &Path) -> Result<Vec<ExportRecord>> {
+    let mut content = String::new();
+    if file.as_os_str() == "-" {
+        std::io::stdin().read_to_string(&mut content)?;
+    } else {
+        content = std::fs::read_to_string(file)?;
+    }
+
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str(line)
+                .map_err(|e| ItackError::Other(format!("Invalid record on line {}: {}", i + 1, e)))
+        })
+        .collect()
+}
+
+fn to_issue_info(record: ExportRecord) -> IssueInfo {
+    let relative_path =
+        Project::issue_relative_path(record.front_matter.id, &record.front_matter.created);
+    IssueInfo {
+        issue: record.front_matter,
+        title: record.title,
+        body: record.body,
+        relative_path,
+    }
+}
+
+/// Keep exported IDs. An issue already present with the same ID and creation
+/// time is skipped; a different issue holding the ID is a conflict.
+fn preserve_ids(
+    existing: &[IssueInfo],
+    records: Vec<ExportRecord>,
+) -> Result<(Vec<IssueInfo>, usize)> {
+    let existing: HashMap<u32, &IssueInfo> =
+        existing.iter().map(|info| (info.issue.id, info)).collect();
+
+    let mut imported = Vec::new();
+    let mut seen = HashSet::new();
+    let mut skipped = 0;
+    for record in records {
+        let id = record.front_matter.id;
+        if !seen.insert(id) {
+            return Err(ItackError::Other(format!(
+                "Issue #{} appears more than once; use --renumber",
+                id
+            )));
+        }
+        match existing.get(&id) {
+            Some(info) if info.issue.created == record.front_matter.created => skipped += 1,
+            Some(_) => {
+                return Err(ItackError::Other(format!(
+                    "Issue #{} already exists; use --renumber to import with new IDs",
+                    id
+                )));
+            }
+            None => imported.push(to_issue_info(record)),
+        }
+    }
+
+    Ok((imported, skipped))
+}
+
+/// Where a record came from, as `(source, external id)`. Issues that were
+/// themselves imported keep their original source.
+fn origin(record: &ExportRecord) -> (String, String) {
+    match record
+        .front_matter
+        .imported_from
+        .as_deref()
+        .and_then(|from| from.split_once(':'))
+    {
+        Some((source, external_id)) => (source.to_string(), external_id.to_string()),
+        None => (
+            SOURCE.to_string(),
+            format!("{}#{}", record.project, record.front_matter.id),
+        ),
+    }
+}
+
+/// Give every new record a fresh ID, remapping dependencies and `#N` references.
+/// Dependencies on issues that are neither in the file nor imported before are dropped.
+fn renumber(db: &Database, mut records: Vec<ExportRecord>) -> Result<(Vec<IssueInfo>, usize)> {
+    records.sort_by(|a, b| (&a.project, a.front_matter.id).cmp(&(&b.project, b.front_matter.id)));
+
+    // (project, old ID) -> new ID, for this file and earlier imports
+    let mut ids: HashMap<(String, u32), u32> = HashMap::new();
+    let mut new_records = Vec::new();
+    let mut seen = HashSet::new();
+    let mut skipped = 0;
+    for record in records {
+        let (source, external_id) = origin(&record);
+        if !seen.insert((source.clone(), external_id.clone())) {
+            continue;
+        }
+        let key = (record.project.clone(), record.front_matter.id);
+        if let Some(id) = db.find_import(&source, &external_id)? {
+            ids.insert(key, id);
+            skipped += 1;
+            continue;
+        }
+        new_records.push((key, record, source, external_id));
+    }
+
+    if new_records.is_empty() {
+        return Ok((Vec::new(), skipped));
+    }
+    let first_id = db.allocate_issue_ids(new_records.len() as u32)?;
+    for (offset, (key, ..)) in new_records.iter().enumerate() {
+        ids.insert(key.clone(), first_id + offset as u32);
+    }
+
+    let mut imported = Vec::new();
+    for (_, mut record, source, external_id) in new_records {
+        let project = record.project.clone();
+        let resolve = |old: u32| -> Option<u32> {
+            ids.get(&(project.clone(), old)).copied().or_else(|| {
+                db.find_import(SOURCE, &format!("{}#{}", project, old))
+                    .ok()
+                    .flatten()
+            })
+        };
+
+        let issue = &mut record.front_matter;
+        issue.id = ids[&(project.clone(), issue.id)];
+        issue.depends_on = issue
+            .depends_on
+            .iter()
+            .filter_map(|&dep| resolve(dep))
+            .collect();
+        issue.imported_from = Some(format!("{}:{}", source, external_id));
+        record.body = rewrite_references(&record.body, |old| {
+            let old = u32::try_from(old).ok()?;
+            resolve(old).map(|id| format!("#{}", id))
+        });
+
+        imported.push(to_issue_info(record));
+    }
+
+    Ok((imported, skipped))
+}
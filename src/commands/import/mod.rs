@@ -0,0 +1,124 @@
+//! itack import command: bring issues over from other trackers.
+
+pub mod csv;
+pub mod github;
+pub mod jsonl;
+
+use crate::core::Status;
+use crate::core::{Project, TreeEdit};
+use crate::error::Result;
+use crate::storage::Database;
+use crate::storage::db::IssueInfo;
+use crate::storage::markdown::format_issue;
+
+/// Result of an import.
+#[derive(Default)]
+pub struct ImportSummary {
+    /// Issues created by this import, in ID order.
+    pub imported: Vec<IssueInfo>,
+    /// Number of entries skipped because they were imported before.
+    pub skipped: usize,
+}
+
+/// Commit all imported issues to the data branch in a single commit.
+fn commit_issues(project: &Project, issues: &[IssueInfo], message: &str) -> Result<()> {
+    let mut edit = TreeEdit::new();
+    for info in issues {
+        let content = format_issue(&info.issue, &info.title, &info.body)?;
+        edit.write(&info.relative_path, content);
+    }
+
+    let data_branch = project
+        .config
+        .data_branch
+        .as_deref()
+        .unwrap_or("data/itack");
+
+    edit.commit(&project.repo_root, data_branch, message)?;
+    Ok(())
+}
+
+/// Record the import mapping of each imported issue, and claims for the ones in progress.
+fn record_imports(db: &Database, issues: &[IssueInfo]) -> Result<()> {
+    for info in issues {
+        if let Some((source, external_id)) = info
+            .issue
+            .imported_from
+            .as_deref()
+            .and_then(|from| from.split_once(':'))
+        {
+            db.record_import(source, external_id, info.issue.id)?;
+        }
+        if info.issue.status == Status::InProgress
+            && let Some(assignee) = &info.issue.assignee
+        {
+            db.record_claim(info.issue.id, assignee, info.issue.epic.as_deref())?;
+        }
+    }
+    Ok(())
+}
+
+/// Rewrite `#N` issue references using `resolve`, leaving unresolved ones untouched.
+/// References must stand alone: `abc#1` and `/#1` are not rewritten.
+pub fn rewrite_references(text: &str, resolve: impl Fn(u64) -> Option<String>) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    let mut prev: Option<char> = None;
+
+    while let Some(pos) = rest.find('#') {
+        let (before, after) = rest.split_at(pos);
+        result.push_str(before);
+        let prev_char = before.chars().last().or(prev);
+        let digits_len = after[1..]
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(after.len() - 1);
+        let next_char = after[1 + digits_len..].chars().next();
+
+        let standalone = !prev_char.is_some_and(|c| c.is_alphanumeric() || "/&_-".contains(c))
+            && !next_char.is_some_and(|c| c.is_alphanumeric() || c == '_');
+        let replacement = (standalone && digits_len > 0)
+            .then(|| after[1..1 + digits_len].parse().ok())
+            .flatten()
+            .and_then(&resolve);
+
+        match replacement {
+            Some(replacement) => {
+                result.push_str(&replacement);
+                rest = &after[1 + digits_len..];
+                prev = replacement.chars().last();
+            }
+            None => {
+                result.push('#');
+                rest = &after[1..];
+                prev = Some('#');
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rewrite_references() {
+        let resolve = |number: u64| match number {
+            3 => Some("#10".to_string()),
+            4 => Some("owner/repo#4".to_string()),
+            _ => None,
+        };
+
+        assert_eq!(
+            rewrite_references("Fixes #3, see #4 and #5.", resolve),
+            "Fixes #10, see owner/repo#4 and #5."
+        );
+        assert_eq!(rewrite_references("(#3)\n#3", resolve), "(#10)\n#10");
+        // Not standalone references
+        assert_eq!(
+            rewrite_references("abc#3 x/#3 #3a &#3; ## #", resolve),
+            "abc#3 x/#3 #3a &#3; ## #"
+        );
+    }
+}
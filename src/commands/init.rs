@@ -1,9 +1,10 @@
 //! itack init command.
 
+use std::collections::HashSet;
 use std::fs;
 use std::path::Path;
 
-use crate::core::{Config, Project, commit_to_branch, find_issue_in_branch};
+use crate::core::{Config, Project, TreeEdit, find_issue_in_branch};
 use crate::error::Result;
 use crate::storage::{Database, Metadata, markdown};
 
@@ -85,8 +86,9 @@ fn migrate_stray_issues(repo_root: &Path, data_branch: &str) -> Result<()> {
         return Ok(());
     }
 
-    let mut migrated = 0u32;
-    let mut removed = 0u32;
+    let mut edit = TreeEdit::new();
+    let mut migrated = HashSet::new();
+    let mut stray_files = Vec::new();
 
     for entry in fs::read_dir(&itack_dir)? {
         let entry = entry?;
@@ -111,23 +113,31 @@ fn migrate_stray_issues(repo_root: &Path, data_branch: &str) -> Result<()> {
         let relative_path = std::path::PathBuf::from(".itack").join(&filename);
 
         // Migrate to data branch if not already there
-        if find_issue_in_branch(repo_root, data_branch, issue.id)?.is_none() {
+        if !migrated.contains(&issue.id)
+            && find_issue_in_branch(repo_root, data_branch, issue.id)?.is_none()
+        {
             let formatted = markdown::format_issue(&issue, &title, &body)?;
-            commit_to_branch(
-                repo_root,
-                data_branch,
-                &relative_path,
-                formatted.as_bytes(),
-                &format!("Migrate issue #{} from working directory", issue.id),
-            )?;
-            migrated += 1;
+            edit.write(relative_path, formatted);
+            migrated.insert(issue.id);
         }
 
-        // Remove from working directory
-        fs::remove_file(&path)?;
-        removed += 1;
+        stray_files.push(path);
     }
 
+    // Commit every migrated issue at once, then clean up the working directory
+    if !edit.is_empty() {
+        edit.commit(
+            repo_root,
+            data_branch,
+            &format!("Migrate {} issues from working directory", migrated.len()),
+        )?;
+    }
+    for path in &stray_files {
+        fs::remove_file(path)?;
+    }
+    let removed = stray_files.len();
+    let migrated = migrated.len();
+
     if removed > 0 {
         println!(
             "Cleaned up {} stray issue file(s) from working directory ({} migrated to data branch).",
@@ -0,0 +1,110 @@
+//! itack link-commit command: record the code commits that implemented an issue.
+
+use crate::commands::reconcile::mainline;
+use crate::core::commit_refs::mentions_issue;
+use crate::core::git::{
+    branch_tip, commit_message, commit_time, commits_between, merge_base, resolve_commit,
+};
+use crate::core::{Project, commit_to_branch};
+use crate::error::{ItackError, Result};
+use crate::output::short_sha;
+use crate::storage::db::load_issue_from_data_branch;
+use crate::storage::markdown::format_issue;
+
+/// Arguments for the link-commit command.
+#[derive(Debug)]
+pub struct LinkCommitArgs {
+    /// Issue ID.
+    pub id: u32,
+    /// Commit to link (SHA or any revision). When None, the issue's branch is
+    /// scanned for commits that mention `#id`.
+    pub commit: Option<String>,
+}
+
+/// Link commits to an issue and print them.
+pub fn run(args: LinkCommitArgs) -> Result<()> {
+    let project = Project::discover()?;
+    let linked = execute(&project, &args)?;
+
+    if linked.is_empty() {
+        println!("No new commits to link to issue #{}", args.id);
+    }
+    for sha in &linked {
+        println!("Linked {} to issue #{}", short_sha(sha), args.id);
+    }
+
+    Ok(())
+}
+
+/// Link a commit, or the commits on the issue's branch that mention it and
+/// aren't on the mainline yet, and commit the change to the data branch.
+/// Returns the newly linked SHAs.
+pub fn execute(project: &Project, args: &LinkCommitArgs) -> Result<Vec<String>> {
+    let data_branch = project
+        .config
+        .data_branch
+        .as_deref()
+        .unwrap_or("data/itack");
+
+    let mut issue_info = load_issue_from_data_branch(&project.repo_root, data_branch, args.id)?;
+
+    let candidates = match &args.commit {
+        Some(rev) => vec![resolve_commit(&project.repo_root, rev)?],
+        None => {
+            let branch = issue_info.issue.branch.as_deref().ok_or_else(|| {
+                ItackError::Other(format!(
+                    "Issue #{} has no branch; give a commit to link",
+                    args.id
+                ))
+            })?;
+            let tip = branch_tip(&project.repo_root, branch)?
+                .ok_or_else(|| ItackError::Other(format!("Branch {} not found", branch)))?;
+
+            // Only the branch's own work: commits already on the mainline
+            // aren't, and neither are commits older than the issue
+            let fork_point = match branch_tip(&project.repo_root, mainline(project))? {
+                Some(mainline_tip) => merge_base(&project.repo_root, tip, mainline_tip)?,
+                None => None,
+            };
+            let mut mentioning = Vec::new();
+            for commit in commits_between(&project.repo_root, fork_point, tip)? {
+                // Commit times only have second precision
+                if commit_time(&project.repo_root, commit)?.timestamp()
+                    >= issue_info.issue.created.timestamp()
+                    && mentions_issue(&commit_message(&project.repo_root, commit)?, args.id)
+                {
+                    mentioning.push(commit);
+                }
+            }
+            mentioning
+        }
+    };
+
+    let mut linked = Vec::new();
+    for commit in candidates {
+        let sha = commit.to_string();
+        if !issue_info.issue.commits.contains(&sha) {
+            issue_info.issue.commits.push(sha.clone());
+            linked.push(sha);
+        }
+    }
+
+    if linked.is_empty() {
+        return Ok(linked);
+    }
+
+    let content = format_issue(&issue_info.issue, &issue_info.title, &issue_info.body)?;
+    let message = match linked.as_slice() {
+        [sha] => format!("Link commit {} to issue #{}", short_sha(sha), args.id),
+        _ => format!("Link {} commits to issue #{}", linked.len(), args.id),
+    };
+    commit_to_branch(
+        &project.repo_root,
+        data_branch,
+        &issue_info.relative_path,
+        content.as_bytes(),
+        &message,
+    )?;
+
+    Ok(linked)
+}
@@ -1,27 +1,88 @@
 //! itack list command.
 
+use std::collections::HashSet;
+
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+use crate::core::deps::{is_blocked, is_ready};
 use crate::core::{Project, Status};
 use crate::error::Result;
 use crate::output::{self, OutputFormat};
-use crate::storage::db::load_all_issues_from_data_branch;
+use crate::storage::db::{
+    IssueInfo, load_all_issues_from_data_branch, load_archived_issues_from_data_branch, sort_issues,
+};
 
 /// Arguments for the list command.
+#[derive(Debug, Default, Deserialize, JsonSchema)]
 pub struct ListArgs {
+    /// Only issues with this status.
     pub status: Option<Status>,
+    /// Only issues in this epic.
     pub epic: Option<String>,
+    /// Only issues claimed by this assignee.
     pub assignee: Option<String>,
+    /// Only open issues whose dependencies are all closed.
+    #[serde(default)]
+    pub ready: bool,
+    /// Only issues waiting on unfinished dependencies.
+    #[serde(default)]
+    pub blocked: bool,
+    /// Also include issues moved to `.itack/archive/`.
+    #[serde(default)]
+    pub include_archived: bool,
+    #[serde(skip)]
     pub format: OutputFormat,
 }
 
 /// List issues with optional filters.
 pub fn run(args: ListArgs) -> Result<()> {
     let project = Project::discover()?;
+    let issues = execute(&project, &args)?;
+
+    match args.format {
+        OutputFormat::Table => {
+            output::print_issues_table(&issues);
+        }
+        OutputFormat::Json => {
+            output::print_issues_json(&issues)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Load the issues matching the filters.
+pub fn execute(project: &Project, args: &ListArgs) -> Result<Vec<IssueInfo>> {
     let data_branch = project
         .config
         .data_branch
         .as_deref()
         .unwrap_or("data/itack");
     let mut issues = load_all_issues_from_data_branch(&project.repo_root, data_branch)?;
+    if args.include_archived {
+        issues.extend(load_archived_issues_from_data_branch(
+            &project.repo_root,
+            data_branch,
+        )?);
+        sort_issues(&mut issues);
+    }
+
+    // Dependency filters need the full issue set, so apply them first
+    if args.ready || args.blocked {
+        let keep: HashSet<u32> = issues
+            .iter()
+            .filter(|info| {
+                if args.ready {
+                    is_ready(&info.issue, &issues)
+                } else {
+                    is_blocked(&info.issue, &issues)
+                }
+            })
+            .map(|info| info.issue.id)
+            .collect();
+        issues.retain(|info| keep.contains(&info.issue.id));
+    }
 
     // Apply filters
     if let Some(status) = args.status {
@@ -36,14 +97,5 @@ pub fn run(args: ListArgs) -> Result<()> {
         issues.retain(|i| i.issue.assignee.as_ref() == Some(assignee));
     }
 
-    match args.format {
-        OutputFormat::Table => {
-            output::print_issues_table(&issues);
-        }
-        OutputFormat::Json => {
-            output::print_issues_json(&issues)?;
-        }
-    }
-
-    Ok(())
+    Ok(issues)
 }
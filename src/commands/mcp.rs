@@ -0,0 +1,258 @@
+//! itack mcp command: a Model Context Protocol server over stdio.
+//!
+//! Messages are newline-delimited JSON-RPC 2.0. Each tool maps onto a command's
+//! `execute` function, and its input schema is derived from the command's
+//! argument struct.
+
+use std::io::{BufRead, Write};
+
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+use serde_json::{Value, json};
+
+use crate::commands::{claim, comment, create, done, list, next, release, set, show};
+use crate::core::{Project, Status};
+use crate::error::{ItackError, Result};
+use crate::output;
+
+/// MCP protocol version used when the client does not request one.
+const PROTOCOL_VERSION: &str = "2025-06-18";
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// A JSON-RPC error to send back to the client.
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        RpcError {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+/// Serve MCP requests on stdin/stdout until stdin is closed.
+pub fn run() -> Result<()> {
+    let stdin = std::io::stdin();
+    let mut stdout = std::io::stdout();
+
+    for line in stdin.lock().lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        if let Some(response) = handle_message(&line) {
+            writeln!(stdout, "{}", serde_json::to_string(&response)?)?;
+            stdout.flush()?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Handle one JSON-RPC message. Returns None for notifications.
+fn handle_message(line: &str) -> Option<Value> {
+    let message: Value = match serde_json::from_str(line) {
+        Ok(message) => message,
+        Err(e) => {
+            return Some(error_response(
+                Value::Null,
+                RpcError::new(PARSE_ERROR, e.to_string()),
+            ));
+        }
+    };
+
+    // Requests without an id are notifications and never get a response
+    let id = message.get("id")?.clone();
+
+    let Some(method) = message.get("method").and_then(Value::as_str) else {
+        return Some(error_response(
+            id,
+            RpcError::new(INVALID_REQUEST, "Missing method"),
+        ));
+    };
+    let params = message.get("params").cloned().unwrap_or(Value::Null);
+
+    let response = match handle_request(method, params) {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(error) => error_response(id, error),
+    };
+    Some(response)
+}
+
+fn error_response(id: Value, error: RpcError) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": error.code, "message": error.message },
+    })
+}
+
+fn handle_request(method: &str, params: Value) -> std::result::Result<Value, RpcError> {
+    match method {
+        "initialize" => {
+            let version = params
+                .get("protocolVersion")
+                .and_then(Value::as_str)
+                .unwrap_or(PROTOCOL_VERSION);
+            Ok(json!({
+                "protocolVersion": version,
+                "capabilities": { "tools": {} },
+                "serverInfo": { "name": "itack", "version": env!("CARGO_PKG_VERSION") },
+            }))
+        }
+        "ping" => Ok(json!({})),
+        "tools/list" => Ok(json!({ "tools": tools() })),
+        "tools/call" => {
+            let Some(name) = params.get("name").and_then(Value::as_str) else {
+                return Err(RpcError::new(INVALID_PARAMS, "Missing tool name"));
+            };
+            let arguments = params.get("arguments").cloned().unwrap_or(json!({}));
+            if !tools().iter().any(|tool| tool["name"] == name) {
+                return Err(RpcError::new(
+                    INVALID_PARAMS,
+                    format!("Unknown tool: {}", name),
+                ));
+            }
+
+            // Command failures are reported to the model as tool errors
+            let (text, is_error) = match call_tool(name, arguments) {
+                Ok(text) => (text, false),
+                Err(e) => (e.to_string(), true),
+            };
+            Ok(json!({
+                "content": [{ "type": "text", "text": text }],
+                "isError": is_error,
+            }))
+        }
+        _ => Err(RpcError::new(
+            METHOD_NOT_FOUND,
+            format!("Method not found: {}", method),
+        )),
+    }
+}
+
+/// Describe a tool, deriving its input schema from the command's argument struct.
+fn tool<T: JsonSchema>(name: &str, description: &str) -> Value {
+    json!({
+        "name": name,
+        "description": description,
+        "inputSchema": schemars::schema_for!(T),
+    })
+}
+
+/// The tools exposed by the server.
+fn tools() -> Vec<Value> {
+    vec![
+        tool::<list::ListArgs>("list", "List issues, optionally filtered"),
+        tool::<show::ShowArgs>("show", "Show an issue with its body"),
+        tool::<create::CreateArgs>("create", "Create a new issue"),
+        tool::<claim::ClaimArgs>("claim", "Claim an issue and mark it in-progress"),
+        tool::<release::ReleaseArgs>("release", "Release a claimed issue"),
+        tool::<set::SetArgs>("set", "Set fields of an issue in one commit"),
+        tool::<done::DoneArgs>(
+            "done",
+            "Mark an issue as done, reporting issues that became ready",
+        ),
+        tool::<comment::CommentArgs>("comment", "Add a comment to an issue"),
+        tool::<next::NextArgs>(
+            "next",
+            "Get (and optionally claim) the next issue that is ready to work on",
+        ),
+    ]
+}
+
+fn parse_args<T: DeserializeOwned>(arguments: Value) -> Result<T> {
+    serde_json::from_value(arguments)
+        .map_err(|e| ItackError::Other(format!("Invalid arguments: {}", e)))
+}
+
+/// Run a tool and return its result as JSON text (the `--json` shape).
+fn call_tool(name: &str, arguments: Value) -> Result<String> {
+    let project = Project::discover()?;
+
+    match name {
+        "list" => {
+            let issues = list::execute(&project, &parse_args(arguments)?)?;
+            output::issues_json(&issues)
+        }
+        "show" => {
+            let info = show::execute(&project, &parse_args(arguments)?)?;
+            output::issue_json(&info.issue, &info.title, &info.body)
+        }
+        "create" => {
+            let info = create::execute(&project, parse_args(arguments)?)?;
+            output::issue_json(&info.issue, &info.title, &info.body)
+        }
+        "claim" => {
+            let info = claim::execute(&project, parse_args(arguments)?)?;
+            output::issue_json(&info.issue, &info.title, &info.body)
+        }
+        "release" => {
+            let (info, _) = release::execute(&project, &parse_args(arguments)?)?;
+            output::issue_json(&info.issue, &info.title, &info.body)
+        }
+        "set" => {
+            let outcome = set::execute(&project, &parse_args(arguments)?)?;
+            let info = outcome.issue_info;
+            output::issue_json(&info.issue, &info.title, &info.body)
+        }
+        "done" => {
+            let args: done::DoneArgs = parse_args(arguments)?;
+            let (old_status, unblocked) = done::execute(&project, &args)?;
+            output::status_change_json(args.id, old_status, Status::Done, &unblocked)
+        }
+        "comment" => {
+            let info = comment::execute(&project, parse_args(arguments)?)?;
+            output::issue_json(&info.issue, &info.title, &info.body)
+        }
+        "next" => match next::execute(&project, &parse_args(arguments)?)? {
+            Some(info) => output::issue_json(&info.issue, &info.title, &info.body),
+            None => Ok("null".to_string()),
+        },
+        _ => Err(ItackError::Other(format!("Unknown tool: {}", name))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tool_schemas_follow_args() {
+        let tools = tools();
+        let claim = tools.iter().find(|tool| tool["name"] == "claim").unwrap();
+        let properties = &claim["inputSchema"]["properties"];
+
+        assert!(properties.get("id").is_some());
+        assert!(properties.get("ignore_deps").is_some());
+        // Blocking options are not available over MCP
+        assert!(properties.get("wait").is_none());
+        assert!(properties.get("timeout").is_none());
+        assert_eq!(claim["inputSchema"]["required"], json!(["id"]));
+    }
+
+    #[test]
+    fn test_notifications_get_no_response() {
+        let notification = r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#;
+        assert!(handle_message(notification).is_none());
+    }
+
+    #[test]
+    fn test_protocol_errors() {
+        let response = handle_message("not json").unwrap();
+        assert_eq!(response["error"]["code"], PARSE_ERROR);
+
+        let response = handle_message(r#"{"jsonrpc":"2.0","id":1,"method":"nope"}"#).unwrap();
+        assert_eq!(response["id"], 1);
+        assert_eq!(response["error"]["code"], METHOD_NOT_FOUND);
+    }
+}
@@ -1,25 +1,47 @@
 //! Command implementations.
 
+pub mod agents;
+pub mod archive;
+pub mod batch;
 pub mod board;
 pub mod claim;
+pub mod comment;
 pub mod completions;
 pub mod create;
+pub mod delete;
 pub mod depend;
 pub mod doctor;
 pub mod done;
 pub mod edit;
+pub mod export;
+pub mod export_html;
+pub mod finish;
+pub mod hook;
+pub mod import;
 pub mod init;
+pub mod link_commit;
 pub mod list;
+pub mod mcp;
+pub mod move_issue;
+pub mod next;
+pub mod reconcile;
 pub mod release;
+pub mod scan_commits;
 pub mod search;
+pub mod serve;
+pub mod set;
 pub mod set_session;
 pub mod show;
+pub mod start;
 pub mod undepend;
+pub mod watch;
+pub mod whoami;
 pub mod wontfix;
 
-use crate::cli::{Cli, Commands};
-use crate::error::Result;
+use crate::cli::{Cli, Commands, HookAction, ImportSource};
+use crate::error::{ItackError, Result};
 use crate::output::OutputFormat;
+use export::DataFormat;
 
 /// Dispatch a command based on CLI arguments.
 pub fn dispatch(cli: Cli) -> Result<()> {
@@ -32,12 +54,20 @@ pub fn dispatch(cli: Cli) -> Result<()> {
             body,
             message,
             depends_on,
+            priority,
+            labels,
+            template,
+            edit,
         } => create::run(create::CreateArgs {
             title,
             epic,
             body,
             message,
             depends_on,
+            labels,
+            priority,
+            template,
+            edit,
         }),
 
         Commands::Show { id, json } => show::run(show::ShowArgs {
@@ -51,22 +81,97 @@ pub fn dispatch(cli: Cli) -> Result<()> {
 
         Commands::Edit { id, body, message } => edit::run(edit::EditArgs { id, body, message }),
 
-        Commands::Done { id } => done::run(done::DoneArgs { id }),
+        Commands::Set {
+            id,
+            fields,
+            ignore_deps,
+        } => set::run(set::SetArgs {
+            id,
+            fields: set::parse_assignments(&fields)?,
+            ignore_deps,
+        }),
+
+        Commands::Done { id, json } => done::run(done::DoneArgs {
+            id,
+            format: if json {
+                OutputFormat::Json
+            } else {
+                OutputFormat::Table
+            },
+        }),
 
-        Commands::WontFix { id } => wontfix::run(wontfix::WontFixArgs { id }),
+        Commands::WontFix { id, json } => wontfix::run(wontfix::WontFixArgs {
+            id,
+            format: if json {
+                OutputFormat::Json
+            } else {
+                OutputFormat::Table
+            },
+        }),
 
         Commands::Claim {
             id,
             assignee,
             session,
+            wait,
+            wait_deps,
+            ignore_deps,
+            timeout,
         } => claim::run(claim::ClaimArgs {
             id,
             assignee,
             session,
+            wait,
+            wait_deps,
+            ignore_deps,
+            timeout,
+            branch: None,
         }),
 
         Commands::Release { id } => release::run(release::ReleaseArgs { id }),
 
+        Commands::Start {
+            id,
+            assignee,
+            session,
+            ignore_deps,
+        } => start::run(start::StartArgs {
+            id,
+            assignee,
+            session,
+            ignore_deps,
+        }),
+
+        Commands::Finish {
+            id,
+            remove_worktree,
+        } => finish::run(finish::FinishArgs {
+            id,
+            remove_worktree,
+        }),
+
+        Commands::Comment { id, text, author } => {
+            comment::run(comment::CommentArgs { id, text, author })
+        }
+
+        Commands::Next {
+            epic,
+            claim,
+            assignee,
+            session,
+            json,
+        } => next::run(next::NextArgs {
+            epic,
+            claim,
+            assignee,
+            session,
+            format: if json {
+                OutputFormat::Json
+            } else {
+                OutputFormat::Table
+            },
+        }),
+
         Commands::Depend { id, deps } => depend::run(depend::DependArgs { id, deps }),
 
         Commands::Undepend { id, deps } => undepend::run(undepend::UndependArgs { id, deps }),
@@ -75,15 +180,106 @@ pub fn dispatch(cli: Cli) -> Result<()> {
             set_session::run(set_session::SetSessionArgs { id, session })
         }
 
+        Commands::Whoami => whoami::run(),
+
+        Commands::Agents { json } => agents::run(agents::AgentsArgs {
+            format: if json {
+                OutputFormat::Json
+            } else {
+                OutputFormat::Table
+            },
+        }),
+
+        Commands::Batch { json } => batch::run(batch::BatchArgs {
+            format: if json {
+                OutputFormat::Json
+            } else {
+                OutputFormat::Table
+            },
+        }),
+
+        Commands::ScanCommits { range } => {
+            scan_commits::run(scan_commits::ScanCommitsArgs { range })
+        }
+
+        Commands::LinkCommit { id, commit } => {
+            link_commit::run(link_commit::LinkCommitArgs { id, commit })
+        }
+
+        Commands::Hook { action } => match action {
+            HookAction::Install { post_merge, force } => {
+                hook::run(hook::HookInstallArgs { post_merge, force })
+            }
+        },
+
+        Commands::Mcp => mcp::run(),
+
+        Commands::ExportHtml { dir } => export_html::run(export_html::ExportHtmlArgs { dir }),
+
+        Commands::Export {
+            format,
+            history,
+            columns,
+            output,
+        } => export::run(export::ExportArgs {
+            format,
+            history,
+            columns,
+            output,
+        }),
+
+        Commands::Import {
+            source,
+            format,
+            file,
+            renumber,
+            dry_run,
+        } => match (source, format, file) {
+            (Some(ImportSource::Github { file }), _, _) => {
+                import::github::run(import::github::GithubImportArgs { file })
+            }
+            (None, Some(DataFormat::Jsonl), Some(file)) if !dry_run => {
+                import::jsonl::run(import::jsonl::JsonlImportArgs { file, renumber })
+            }
+            (None, Some(DataFormat::Csv), Some(file)) if !renumber => {
+                import::csv::run(import::csv::CsvImportArgs { file, dry_run })
+            }
+            (None, Some(_), Some(_)) => Err(ItackError::Other(
+                "--renumber only applies to JSONL imports and --dry-run to CSV imports".to_string(),
+            )),
+            _ => Err(ItackError::Other(
+                "Specify a source (e.g. `itack import github <file>`) or `--format <format> <file>`"
+                    .to_string(),
+            )),
+        },
+
+        Commands::Serve { bind } => serve::run(serve::ServeArgs { bind }),
+
+        Commands::Watch {
+            since,
+            interval,
+            once,
+        } => watch::run(watch::WatchArgs {
+            since,
+            interval,
+            once,
+        }),
+
         Commands::List {
             status,
             epic,
             assignee,
+            ready,
+            blocked,
+            include_archived,
             json,
         } => list::run(list::ListArgs {
             status,
             epic,
             assignee,
+            ready,
+            blocked,
+            include_archived,
             format: if json {
                 OutputFormat::Json
             } else {
@@ -99,15 +295,25 @@ pub fn dispatch(cli: Cli) -> Result<()> {
             },
         }),
 
+        Commands::Delete { id } => delete::run(delete::DeleteArgs { id }),
+
+        Commands::Move { id, to } => move_issue::run(move_issue::MoveArgs { id, to }),
+
+        Commands::Archive { days } => archive::run(archive::ArchiveArgs { days }),
+
+        Commands::Reconcile { yes } => reconcile::run(reconcile::ReconcileArgs { yes }),
+
         Commands::Doctor => doctor::run(),
 
         Commands::Search {
             query,
             all_branches,
+            include_archived,
             json,
         } => search::run(search::SearchArgs {
             query,
             all_branches,
+            include_archived,
             format: if json {
                 OutputFormat::Json
             } else {
@@ -0,0 +1,246 @@
+//! itack move command: move an issue to another itack project.
+
+use std::path::{Path, PathBuf};
+
+use crate::commands::import::rewrite_references;
+use crate::core::hooks::{self, HookEvent};
+use crate::core::{Issue, Project, Status, commit_to_branch};
+use crate::error::{ItackError, Result};
+use crate::output;
+use crate::storage::db::{
+    IssueInfo, load_all_issues_from_data_branch, load_archived_issues_from_data_branch,
+    load_issue_from_data_branch,
+};
+use crate::storage::markdown::format_issue;
+
+/// Arguments for the move command.
+#[derive(Debug)]
+pub struct MoveArgs {
+    /// Issue ID in the current project.
+    pub id: u32,
+    /// Any path inside the target project's repository.
+    pub to: PathBuf,
+}
+
+/// Result of moving an issue.
+pub struct MoveOutcome {
+    /// The issue as created in the target project.
+    pub moved: IssueInfo,
+    /// The target repository, as recorded in the tombstone's `moved_to`.
+    pub target: String,
+    /// Dependencies that have no counterpart in the target project.
+    pub dropped_dependencies: Vec<u32>,
+    /// Open source issues that still depend on the moved issue's tombstone.
+    pub dependents: Vec<u32>,
+}
+
+/// Move an issue to another project and print what changed.
+pub fn run(args: MoveArgs) -> Result<()> {
+    let project = Project::discover()?;
+    let target = Project::discover_from(&args.to).map_err(|e| {
+        ItackError::Other(format!("No itack project at {}: {}", args.to.display(), e))
+    })?;
+    let outcome = execute(&project, &target, args.id)?;
+
+    println!(
+        "Moved issue #{} to {} as #{}",
+        args.id, outcome.target, outcome.moved.issue.id
+    );
+    if !outcome.dropped_dependencies.is_empty() {
+        println!(
+            "Dropped dependencies that are not in {}: {}",
+            outcome.target,
+            output::format_issue_refs(&outcome.dropped_dependencies)
+        );
+    }
+    if !outcome.dependents.is_empty() {
+        println!(
+            "Still depending on it here: {}",
+            output::format_issue_refs(&outcome.dependents)
+        );
+    }
+
+    Ok(())
+}
+
+/// Copy the issue into `target` under a newly allocated ID, then turn the
+/// original into a wont-fix tombstone pointing at it. Dependencies on issues
+/// that were moved to the same target earlier are remapped to their new IDs;
+/// others are dropped. `#N` references in the body are remapped the same way,
+/// and references to issues that stay behind are qualified with the source
+/// location. Source issues that depend on the moved issue keep pointing at the
+/// tombstone, so they can be remapped in turn if they are moved to the same
+/// target; they are reported, not treated as unblocked.
+pub fn execute(project: &Project, target: &Project, id: u32) -> Result<MoveOutcome> {
+    let source_location = location(&project.repo_root);
+    let target_location = location(&target.repo_root);
+    if source_location == target_location {
+        return Err(ItackError::Other(format!(
+            "Issue {} is already in {}",
+            id, target_location
+        )));
+    }
+
+    let data_branch = project
+        .config
+        .data_branch
+        .as_deref()
+        .unwrap_or("data/itack");
+    let target_data_branch = target.config.data_branch.as_deref().unwrap_or("data/itack");
+
+    let original = load_issue_from_data_branch(&project.repo_root, data_branch, id)?;
+    if original.issue.status == Status::InProgress {
+        return Err(ItackError::Other(format!(
+            "Issue {} is in progress; release it before moving it",
+            id
+        )));
+    }
+    if original.issue.moved_to.is_some() {
+        return Err(ItackError::Other(format!(
+            "Issue {} was already moved to {}",
+            id,
+            original.issue.moved_to.as_deref().unwrap_or_default()
+        )));
+    }
+
+    let mut source_issues = load_all_issues_from_data_branch(&project.repo_root, data_branch)?;
+    source_issues.extend(load_archived_issues_from_data_branch(
+        &project.repo_root,
+        data_branch,
+    )?);
+
+    // Issues moved to the same target earlier, by their ID here
+    let moved_here = |id: u32| {
+        source_issues
+            .iter()
+            .find(|info| info.issue.id == id)
+            .and_then(|info| info.issue.moved_to.as_deref())
+            .and_then(|moved_to| moved_to.rsplit_once('#'))
+            .filter(|(location, _)| *location == target_location)
+            .and_then(|(_, new_id)| new_id.parse::<u32>().ok())
+    };
+
+    let mut depends_on = Vec::new();
+    let mut dropped_dependencies = Vec::new();
+    for dep in &original.issue.depends_on {
+        match moved_here(*dep) {
+            Some(new_id) => depends_on.push(new_id),
+            None => dropped_dependencies.push(*dep),
+        }
+    }
+    depends_on.sort();
+
+    let mut dependents: Vec<u32> = source_issues
+        .iter()
+        .filter(|info| !info.issue.status.is_closed() && info.issue.depends_on.contains(&id))
+        .map(|info| info.issue.id)
+        .collect();
+    dependents.sort();
+
+    // The issue as it will be created in the target project
+    let target_db = target.open_db()?;
+    let new_id = target_db.next_issue_id()?;
+    let body = rewrite_references(&original.body, |number| {
+        let number = u32::try_from(number).ok()?;
+        if number == id {
+            return Some(format!("#{}", new_id));
+        }
+        if let Some(moved_id) = moved_here(number) {
+            return Some(format!("#{}", moved_id));
+        }
+        source_issues
+            .iter()
+            .any(|info| info.issue.id == number)
+            .then(|| format!("{}#{}", source_location, number))
+    });
+    let mut issue = Issue::with_epic(new_id, original.issue.epic.clone());
+    issue.created = original.issue.created;
+    issue.status = original.issue.status;
+    issue.depends_on = depends_on;
+    issue.labels = original.issue.labels.clone();
+    issue.priority = original.issue.priority;
+    issue.imported_from = Some(
+        original
+            .issue
+            .imported_from
+            .clone()
+            .unwrap_or_else(|| format!("itack:{}#{}", source_location, id)),
+    );
+    let moved = IssueInfo {
+        relative_path: Project::issue_relative_path(new_id, &issue.created),
+        issue,
+        title: original.title.clone(),
+        body,
+    };
+
+    // The tombstone left behind in the source project
+    let moved_to = format!("{}#{}", target_location, new_id);
+    let mut tombstone = original.clone();
+    let old_status = tombstone.issue.status;
+    tombstone.issue.status = Status::WontFix;
+    tombstone.issue.assignee = None;
+    tombstone.issue.branch = None;
+    tombstone.issue.session = None;
+    tombstone.issue.moved_to = Some(moved_to.clone());
+    tombstone.body = format!("Moved to {} as #{}.\n", target_location, new_id);
+
+    // Either hook can still veto the move before anything is committed
+    let hook_env = [("ITACK_OLD_STATUS", old_status.to_string())];
+    hooks::run(target, HookEvent::PreCreate, &moved, &[])?;
+    hooks::run(project, HookEvent::PreWontFix, &tombstone, &hook_env)?;
+
+    // Commit to the target first, so a failure leaves the source untouched
+    let content = format_issue(&moved.issue, &moved.title, &moved.body)?;
+    commit_to_branch(
+        &target.repo_root,
+        target_data_branch,
+        &moved.relative_path,
+        content.as_bytes(),
+        &format!(
+            "Move issue #{} from {}: {}",
+            id, source_location, moved.title
+        ),
+    )?;
+    if let Some((source, external_id)) = moved
+        .issue
+        .imported_from
+        .as_deref()
+        .and_then(|from| from.split_once(':'))
+    {
+        target_db.record_import(source, external_id, new_id)?;
+    }
+
+    let content = format_issue(&tombstone.issue, &tombstone.title, &tombstone.body)?;
+    commit_to_branch(
+        &project.repo_root,
+        data_branch,
+        &tombstone.relative_path,
+        content.as_bytes(),
+        &format!("Move issue #{} to {}", id, moved_to),
+    )?;
+
+    let mut db = project.open_db()?;
+    match db.release(id) {
+        Ok(()) | Err(ItackError::NotClaimed(_)) => {}
+        Err(e) => return Err(e),
+    }
+
+    hooks::run(target, HookEvent::PostCreate, &moved, &[])?;
+    hooks::run(project, HookEvent::PostWontFix, &tombstone, &hook_env)?;
+
+    Ok(MoveOutcome {
+        moved,
+        target: target_location,
+        dropped_dependencies,
+        dependents,
+    })
+}
+
+/// How a project is referred to in `moved_to` and `imported_from`: the
+/// canonical path of its repository.
+fn location(repo_root: &Path) -> String {
+    let path = repo_root
+        .canonicalize()
+        .unwrap_or_else(|_| repo_root.to_path_buf());
+    path.display().to_string()
+}
@@ -0,0 +1,91 @@
+//! itack next command.
+
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+use crate::commands::claim::{self, ClaimArgs};
+use crate::core::Project;
+use crate::core::deps::is_ready;
+use crate::error::{ItackError, Result};
+use crate::output::{self, OutputFormat};
+use crate::storage::db::{IssueInfo, load_all_issues_from_data_branch};
+
+/// Arguments for the next command.
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct NextArgs {
+    /// Only consider issues in this epic.
+    pub epic: Option<String>,
+    /// Claim the issue as well.
+    #[serde(default)]
+    pub claim: bool,
+    /// Assignee for the claim; defaults to the current agent identity.
+    pub assignee: Option<String>,
+    /// Session ID for the claim.
+    pub session: Option<String>,
+    #[serde(skip)]
+    pub format: OutputFormat,
+}
+
+/// Show (and optionally claim) the next issue that is ready to work on.
+pub fn run(args: NextArgs) -> Result<()> {
+    let project = Project::discover()?;
+    let next = execute(&project, &args)?;
+
+    match (args.format, next) {
+        (OutputFormat::Table, Some(info)) => {
+            if args.claim {
+                println!(
+                    "Claimed issue #{} for {}",
+                    info.issue.id,
+                    info.issue.assignee.as_deref().unwrap_or_default()
+                );
+            }
+            output::print_issue_detail(&info.issue, &info.title, &info.body);
+        }
+        (OutputFormat::Table, None) => println!("No ready issues"),
+        (OutputFormat::Json, Some(info)) => {
+            output::print_issue_json(&info.issue, &info.title, &info.body)?;
+        }
+        (OutputFormat::Json, None) => println!("null"),
+    }
+
+    Ok(())
+}
+
+/// Find the lowest-numbered ready issue, claiming it if requested.
+/// Issues claimed by someone else in the meantime are skipped.
+pub fn execute(project: &Project, args: &NextArgs) -> Result<Option<IssueInfo>> {
+    let data_branch = project
+        .config
+        .data_branch
+        .as_deref()
+        .unwrap_or("data/itack");
+
+    let issues = load_all_issues_from_data_branch(&project.repo_root, data_branch)?;
+    let mut candidates: Vec<&IssueInfo> = issues
+        .iter()
+        .filter(|info| is_ready(&info.issue, &issues))
+        .filter(|info| args.epic.is_none() || info.issue.epic == args.epic)
+        .collect();
+    candidates.sort_by_key(|info| info.issue.id);
+
+    if !args.claim {
+        return Ok(candidates.first().map(|info| (*info).clone()));
+    }
+
+    for info in candidates {
+        let claim_args = ClaimArgs {
+            id: info.issue.id,
+            assignee: args.assignee.clone(),
+            session: args.session.clone(),
+            ..ClaimArgs::default()
+        };
+        match claim::execute(project, claim_args) {
+            Ok(claimed) => return Ok(Some(claimed)),
+            Err(ItackError::AlreadyClaimed(..) | ItackError::Blocked { .. }) => continue,
+            Err(e) => return Err(e),
+        }
+    }
+
+    Ok(None)
+}
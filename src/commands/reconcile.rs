@@ -0,0 +1,156 @@
+//! itack reconcile command: close in-progress issues whose branch is already merged.
+
+use std::io::{self, BufRead, IsTerminal, Write};
+
+use crate::commands::done::cascade_unblocked;
+use crate::core::git::{branch_tip, is_branch_merged};
+use crate::core::hooks::{self, HookEvent};
+use crate::core::{Project, Status, TreeEdit};
+use crate::error::{ItackError, Result};
+use crate::output;
+use crate::storage::db::{IssueInfo, load_all_issues_from_data_branch};
+use crate::storage::markdown::format_issue;
+
+/// Arguments for the reconcile command.
+#[derive(Debug)]
+pub struct ReconcileArgs {
+    /// Mark merged issues done without asking.
+    pub yes: bool,
+}
+
+/// List in-progress issues with merged branches and offer to close them.
+pub fn run(args: ReconcileArgs) -> Result<()> {
+    let project = Project::discover()?;
+    let mainline = mainline(&project);
+
+    let data_branch = project
+        .config
+        .data_branch
+        .as_deref()
+        .unwrap_or("data/itack");
+    let issues = load_all_issues_from_data_branch(&project.repo_root, data_branch)?;
+
+    if branch_tip(&project.repo_root, mainline)?.is_none() {
+        return Err(ItackError::Other(format!(
+            "Mainline branch {} not found; set `mainline` in ~/.itack/config.toml",
+            mainline
+        )));
+    }
+    let merged = merged_in_progress(&project, &issues)?;
+
+    if merged.is_empty() {
+        println!(
+            "No in-progress issues have branches merged into {}",
+            mainline
+        );
+        return Ok(());
+    }
+
+    println!("In-progress issues with branches merged into {}:", mainline);
+    for info in &merged {
+        println!(
+            "  #{}: {} ({})",
+            info.issue.id,
+            info.title,
+            info.issue.branch.as_deref().unwrap_or_default()
+        );
+    }
+
+    if !args.yes {
+        if !io::stdin().is_terminal() {
+            println!("Run 'itack reconcile --yes' to mark them done and release their claims.");
+            return Ok(());
+        }
+        print!("Mark them done and release their claims? [y/N] ");
+        io::stdout().flush()?;
+        let mut answer = String::new();
+        io::stdin().lock().read_line(&mut answer)?;
+        if !matches!(answer.trim(), "y" | "Y" | "yes") {
+            return Ok(());
+        }
+    }
+
+    let (closed, unblocked) = execute(&project, merged)?;
+    println!("Marked {} issues done", closed);
+    output::print_unblocked(&unblocked);
+
+    Ok(())
+}
+
+/// The mainline branch merged work lands on.
+pub fn mainline(project: &Project) -> &str {
+    project.config.mainline.as_deref().unwrap_or("main")
+}
+
+/// Find the in-progress issues whose recorded branch exists and is fully merged
+/// into the mainline. Issues claimed on the mainline itself are not included.
+pub fn merged_in_progress(project: &Project, issues: &[IssueInfo]) -> Result<Vec<IssueInfo>> {
+    let mainline = mainline(project);
+
+    let mut merged = Vec::new();
+    for info in issues {
+        let Some(branch) = info.issue.branch.as_deref() else {
+            continue;
+        };
+        if info.issue.status != Status::InProgress
+            || branch == mainline
+            || branch_tip(&project.repo_root, branch)?.is_none()
+        {
+            continue;
+        }
+        if is_branch_merged(&project.repo_root, branch, mainline)? {
+            merged.push(info.clone());
+        }
+    }
+
+    merged.sort_by_key(|info| info.issue.id);
+    Ok(merged)
+}
+
+/// Mark the issues done in one data branch commit and release their claims.
+/// Returns how many issues were closed and the issues that became ready.
+pub fn execute(project: &Project, mut merged: Vec<IssueInfo>) -> Result<(usize, Vec<IssueInfo>)> {
+    let data_branch = project
+        .config
+        .data_branch
+        .as_deref()
+        .unwrap_or("data/itack");
+
+    let hook_env = [("ITACK_OLD_STATUS", Status::InProgress.to_string())];
+    for info in &mut merged {
+        info.issue.status = Status::Done;
+        hooks::run(project, HookEvent::PreDone, info, &hook_env)?;
+    }
+
+    let mut edit = TreeEdit::new();
+    for info in &merged {
+        let content = format_issue(&info.issue, &info.title, &info.body)?;
+        edit.write(&info.relative_path, content);
+    }
+    let ids: Vec<String> = merged
+        .iter()
+        .map(|info| format!("#{}", info.issue.id))
+        .collect();
+    let message = format!("Mark merged issues as done: {}", ids.join(", "));
+    edit.commit(&project.repo_root, data_branch, &message)?;
+
+    let mut db = project.open_db()?;
+    for info in &merged {
+        match db.release(info.issue.id) {
+            Ok(()) | Err(ItackError::NotClaimed(_)) => {}
+            Err(e) => return Err(e),
+        }
+    }
+
+    let mut unblocked: Vec<IssueInfo> = Vec::new();
+    for info in &merged {
+        hooks::run(project, HookEvent::PostDone, info, &hook_env)?;
+        for ready in cascade_unblocked(project, data_branch, info.issue.id)? {
+            if !unblocked.iter().any(|u| u.issue.id == ready.issue.id) {
+                unblocked.push(ready);
+            }
+        }
+    }
+
+    Ok((merged.len(), unblocked))
+}
@@ -1,18 +1,38 @@
 //! itack release command.
 
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+use crate::core::hooks::{self, HookEvent};
 use crate::core::{Project, commit_to_branch};
 use crate::error::Result;
-use crate::storage::db::load_issue_from_data_branch;
+use crate::storage::db::{IssueInfo, load_issue_from_data_branch};
 use crate::storage::markdown::format_issue;
 
 /// Arguments for the release command.
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct ReleaseArgs {
+    /// Issue ID.
     pub id: u32,
 }
 
 /// Release a claim on an issue.
 pub fn run(args: ReleaseArgs) -> Result<()> {
     let project = Project::discover()?;
+    let (_, old_assignee) = execute(&project, &args)?;
+
+    if let Some(assignee) = old_assignee {
+        println!("Released issue #{} from {}", args.id, assignee);
+    } else {
+        println!("Released issue #{}", args.id);
+    }
+
+    Ok(())
+}
+
+/// Release a claim and commit the change to the data branch.
+/// Returns the released issue and its previous assignee.
+pub fn execute(project: &Project, args: &ReleaseArgs) -> Result<(IssueInfo, Option<String>)> {
     let mut db = project.open_db()?;
 
     let data_branch = project
@@ -24,14 +44,20 @@ pub fn run(args: ReleaseArgs) -> Result<()> {
     // Load the issue from data branch (source of truth)
     let mut issue_info = load_issue_from_data_branch(&project.repo_root, data_branch, args.id)?;
 
-    // Release in database
-    db.release(args.id)?;
-
     // Update issue fields
     let old_assignee = issue_info.issue.assignee.take();
     issue_info.issue.branch = None;
     issue_info.issue.session = None;
 
+    let hook_env: Vec<(&str, String)> = old_assignee
+        .iter()
+        .map(|a| ("ITACK_OLD_ASSIGNEE", a.clone()))
+        .collect();
+    hooks::run(project, HookEvent::PreRelease, &issue_info, &hook_env)?;
+
+    // Release in database
+    db.release(args.id)?;
+
     // Format issue content in memory and commit directly to data branch
     let content = format_issue(&issue_info.issue, &issue_info.title, &issue_info.body)?;
     let message = format!("Release issue #{}", args.id);
@@ -43,11 +69,11 @@ pub fn run(args: ReleaseArgs) -> Result<()> {
         &message,
     )?;
 
-    if let Some(assignee) = old_assignee {
-        println!("Released issue #{} from {}", args.id, assignee);
-    } else {
-        println!("Released issue #{}", args.id);
+    hooks::run(project, HookEvent::PostRelease, &issue_info, &hook_env)?;
+
+    if let Some(assignee) = &old_assignee {
+        db.touch_agent(assignee, None, None)?;
     }
 
-    Ok(())
+    Ok((issue_info, old_assignee))
 }
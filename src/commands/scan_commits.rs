@@ -0,0 +1,153 @@
+//! itack scan-commits command: close and link issues from commit messages.
+
+use std::collections::HashMap;
+
+use crate::commands::done::cascade_unblocked;
+use crate::core::commit_refs::{CommitAction, parse_commit_refs};
+use crate::core::git::{commit_message, commits_in_range};
+use crate::core::hooks::{self, HookEvent};
+use crate::core::{Project, Status, TreeEdit};
+use crate::error::{ItackError, Result};
+use crate::output::{self, short_sha};
+use crate::storage::db::{IssueInfo, load_all_issues_from_data_branch};
+use crate::storage::markdown::format_issue;
+
+/// Arguments for the scan-commits command.
+#[derive(Debug)]
+pub struct ScanCommitsArgs {
+    /// Revision range (`A..B`) or a single commit.
+    pub range: String,
+}
+
+/// What scanning a range changed.
+#[derive(Default)]
+pub struct ScanSummary {
+    /// Issues marked done, with the commit that closed them.
+    pub closed: Vec<(u32, String)>,
+    /// Commits newly linked to issues.
+    pub linked: Vec<(u32, String)>,
+    /// Issues mentioned in a commit message that don't exist.
+    pub unknown: Vec<(u32, String)>,
+    /// Issues that became ready because of the closed ones.
+    pub unblocked: Vec<IssueInfo>,
+}
+
+/// Scan commits and print what changed.
+pub fn run(args: ScanCommitsArgs) -> Result<()> {
+    let project = Project::discover()?;
+    let summary = execute(&project, &args)?;
+
+    for (id, sha) in &summary.unknown {
+        eprintln!(
+            "Warning: commit {} mentions unknown issue #{}",
+            short_sha(sha),
+            id
+        );
+    }
+    for (id, sha) in &summary.linked {
+        println!("Linked {} to issue #{}", short_sha(sha), id);
+    }
+    for (id, sha) in &summary.closed {
+        println!("Closed issue #{} by {}", id, short_sha(sha));
+    }
+    output::print_unblocked(&summary.unblocked);
+
+    Ok(())
+}
+
+/// Scan the commit messages in a range, link each mentioned issue to its commit
+/// and mark closed issues done, all in one data branch commit. Commits already
+/// linked are skipped, so scanning a range again changes nothing.
+pub fn execute(project: &Project, args: &ScanCommitsArgs) -> Result<ScanSummary> {
+    let data_branch = project
+        .config
+        .data_branch
+        .as_deref()
+        .unwrap_or("data/itack");
+
+    let mut issues: HashMap<u32, IssueInfo> =
+        load_all_issues_from_data_branch(&project.repo_root, data_branch)?
+            .into_iter()
+            .map(|info| (info.issue.id, info))
+            .collect();
+
+    let mut summary = ScanSummary::default();
+    let mut old_statuses: HashMap<u32, Status> = HashMap::new();
+    let mut changed: Vec<u32> = Vec::new();
+
+    for commit in commits_in_range(&project.repo_root, &args.range)? {
+        let sha = commit.to_string();
+        let message = commit_message(&project.repo_root, commit)?;
+        for commit_ref in parse_commit_refs(&message) {
+            let Some(info) = issues.get_mut(&commit_ref.id) else {
+                summary.unknown.push((commit_ref.id, sha.clone()));
+                continue;
+            };
+            let issue = &mut info.issue;
+
+            let mut touched = false;
+            if !issue.commits.contains(&sha) {
+                issue.commits.push(sha.clone());
+                summary.linked.push((issue.id, sha.clone()));
+                touched = true;
+            }
+            if commit_ref.action == CommitAction::Close && !issue.status.is_closed() {
+                old_statuses.insert(issue.id, issue.status);
+                issue.status = Status::Done;
+                summary.closed.push((issue.id, sha.clone()));
+                touched = true;
+            }
+            if touched && !changed.contains(&issue.id) {
+                changed.push(issue.id);
+            }
+        }
+    }
+
+    if changed.is_empty() {
+        return Ok(summary);
+    }
+
+    let hook_env = |id: u32| [("ITACK_OLD_STATUS", old_statuses[&id].to_string())];
+    for (id, _) in &summary.closed {
+        hooks::run(project, HookEvent::PreDone, &issues[id], &hook_env(*id))?;
+    }
+
+    let mut edit = TreeEdit::new();
+    for id in &changed {
+        let info = &issues[id];
+        let content = format_issue(&info.issue, &info.title, &info.body)?;
+        edit.write(&info.relative_path, content);
+    }
+    let message = format!(
+        "Scan commits {}: {} linked, {} closed",
+        args.range,
+        summary.linked.len(),
+        summary.closed.len()
+    );
+    edit.commit(&project.repo_root, data_branch, &message)?;
+
+    let mut db = project.open_db()?;
+    for (id, _) in &summary.closed {
+        match db.release(*id) {
+            Ok(()) | Err(ItackError::NotClaimed(_)) => {}
+            Err(e) => return Err(e),
+        }
+    }
+
+    for (id, _) in &summary.closed {
+        hooks::run(project, HookEvent::PostDone, &issues[id], &hook_env(*id))?;
+    }
+    for (id, _) in &summary.closed {
+        for info in cascade_unblocked(project, data_branch, *id)? {
+            if !summary
+                .unblocked
+                .iter()
+                .any(|u| u.issue.id == info.issue.id)
+            {
+                summary.unblocked.push(info);
+            }
+        }
+    }
+
+    Ok(summary)
+}
@@ -5,12 +5,15 @@ use std::process::Command;
 use crate::core::Project;
 use crate::error::Result;
 use crate::output::{self, OutputFormat};
-use crate::storage::db::load_all_issues_from_data_branch;
+use crate::storage::db::{
+    load_all_issues_from_data_branch, load_archived_issues_from_data_branch, sort_issues,
+};
 
 /// Arguments for the search command.
 pub struct SearchArgs {
     pub query: String,
     pub all_branches: bool,
+    pub include_archived: bool,
     pub format: OutputFormat,
 }
 
@@ -28,7 +31,14 @@ pub fn run(args: SearchArgs) -> Result<()> {
             .data_branch
             .as_deref()
             .unwrap_or("data/itack");
-        let issues = load_all_issues_from_data_branch(&project.repo_root, data_branch)?;
+        let mut issues = load_all_issues_from_data_branch(&project.repo_root, data_branch)?;
+        if args.include_archived {
+            issues.extend(load_archived_issues_from_data_branch(
+                &project.repo_root,
+                data_branch,
+            )?);
+            sort_issues(&mut issues);
+        }
         let query_lower = args.query.to_lowercase();
 
         let matching: Vec<_> = issues
@@ -0,0 +1,541 @@
+//! itack serve command: a local HTTP REST API.
+//!
+//! Routes:
+//! - `GET /issues` (filters: `status`, `epic`, `assignee`, `ready`, `blocked`)
+//! - `POST /issues`, `GET /issues/{id}`, `PATCH /issues/{id}`
+//! - `POST /issues/{id}/claim`, `DELETE /issues/{id}/claim`, `GET /claims`
+//! - `GET /board`
+//! - `GET /events` (server-sent events, resumable with `since` or `Last-Event-ID`)
+//!
+//! ETags are the git object IDs of the issue file (single issue) or of the
+//! `.itack` tree (collections) on the data branch.
+
+use std::io::Write;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::Deserialize;
+use serde_json::{Value, json};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use crate::commands::archive::unarchive_reopened;
+use crate::commands::{board, claim, create, done, list, release, wontfix};
+use crate::core::events::events_between;
+use crate::core::git::{branch_tip, path_oid_in_branch, resolve_commit};
+use crate::core::hooks::{self, HookEvent};
+use crate::core::{Project, Status, TreeEdit};
+use crate::error::{ItackError, Result};
+use crate::output::{self, OutputFormat};
+use crate::storage::db::{IssueInfo, load_issue_from_data_branch};
+use crate::storage::markdown::format_issue;
+
+/// How often the event feed checks the data branch for new commits.
+const EVENT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How often the event feed sends a comment line to detect closed connections.
+const KEEPALIVE_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Arguments for the serve command.
+pub struct ServeArgs {
+    pub bind: String,
+}
+
+/// Shared state for request handlers.
+struct ServerState {
+    project: Project,
+    /// Serializes data branch writes made by concurrent requests.
+    write_lock: Mutex<()>,
+}
+
+impl ServerState {
+    fn data_branch(&self) -> &str {
+        self.project
+            .config
+            .data_branch
+            .as_deref()
+            .unwrap_or("data/itack")
+    }
+
+    /// ETag for a path on the data branch, from its git object ID.
+    fn etag(&self, path: &Path) -> Result<Option<String>> {
+        let oid = path_oid_in_branch(&self.project.repo_root, self.data_branch(), path)?;
+        Ok(oid.map(|oid| format!("\"{}\"", oid)))
+    }
+}
+
+/// A JSON response.
+struct Reply {
+    status: u16,
+    body: String,
+    etag: Option<String>,
+    location: Option<String>,
+}
+
+impl Reply {
+    fn ok(body: String) -> Self {
+        Reply {
+            status: 200,
+            body,
+            etag: None,
+            location: None,
+        }
+    }
+
+    fn error(status: u16, message: &str) -> Self {
+        Reply::ok(json!({ "error": message }).to_string()).with_status(status)
+    }
+
+    fn with_status(mut self, status: u16) -> Self {
+        self.status = status;
+        self
+    }
+
+    fn with_etag(mut self, etag: Option<String>) -> Self {
+        self.etag = etag;
+        self
+    }
+}
+
+/// Serve the REST API until the process is stopped.
+pub fn run(args: ServeArgs) -> Result<()> {
+    let project = Project::discover()?;
+
+    // Fail before listening if the database is missing
+    project.open_db()?;
+
+    let server = Server::http(&args.bind)
+        .map_err(|e| ItackError::Other(format!("Failed to bind {}: {}", args.bind, e)))?;
+    println!("Listening on http://{}", server.server_addr());
+    std::io::stdout().flush()?;
+
+    let state = Arc::new(ServerState {
+        project,
+        write_lock: Mutex::new(()),
+    });
+
+    for request in server.incoming_requests() {
+        let state = Arc::clone(&state);
+        thread::spawn(move || handle(&state, request));
+    }
+
+    Ok(())
+}
+
+fn handle(state: &ServerState, mut request: Request) {
+    let url = request.url().to_string();
+    let (path, query) = split_url(&url);
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    let method = request.method().clone();
+
+    if method == Method::Get && segments == ["events"] {
+        stream_events(state, request, &query);
+        return;
+    }
+
+    let reply = match route(state, &mut request, &method, &segments, &query) {
+        Ok(reply) => reply,
+        Err(e) => Reply::error(e.http_status(), &e.to_string()),
+    };
+
+    // Conditional GET: nothing changed since the client's copy
+    let not_modified = method == Method::Get
+        && reply.status == 200
+        && reply.etag.is_some()
+        && header(&request, "If-None-Match") == reply.etag;
+    let reply = if not_modified {
+        Reply {
+            body: String::new(),
+            ..reply.with_status(304)
+        }
+    } else {
+        reply
+    };
+
+    let mut response = Response::from_string(reply.body).with_status_code(reply.status);
+    response.add_header(header_value("Content-Type", "application/json"));
+    if let Some(etag) = &reply.etag {
+        response.add_header(header_value("ETag", etag));
+    }
+    if let Some(location) = &reply.location {
+        response.add_header(header_value("Location", location));
+    }
+
+    // The client may have gone away; there is nobody left to tell
+    let _ = request.respond(response);
+}
+
+fn route(
+    state: &ServerState,
+    request: &mut Request,
+    method: &Method,
+    segments: &[&str],
+    query: &[(String, String)],
+) -> Result<Reply> {
+    let id = segments.get(1).map(|s| s.parse::<u32>());
+
+    match (method, segments, id) {
+        (Method::Get, ["issues"], _) => list_issues(state, query),
+        (Method::Post, ["issues"], _) => create_issue(state, request),
+        (Method::Get, ["issues", _], Some(Ok(id))) => get_issue(state, id),
+        (Method::Patch, ["issues", _], Some(Ok(id))) => patch_issue(state, request, id),
+        (Method::Post, ["issues", _, "claim"], Some(Ok(id))) => claim_issue(state, request, id),
+        (Method::Delete, ["issues", _, "claim"], Some(Ok(id))) => release_issue(state, id),
+        (Method::Get, ["claims"], _) => list_claims(state),
+        (Method::Get, ["board"], _) => get_board(state),
+        _ => Ok(Reply::error(404, "Not found")),
+    }
+}
+
+fn list_issues(state: &ServerState, query: &[(String, String)]) -> Result<Reply> {
+    let status = query_param(query, "status")
+        .map(|s| serde_json::from_value::<Status>(Value::String(s)))
+        .transpose()?;
+    let args = list::ListArgs {
+        status,
+        epic: query_param(query, "epic"),
+        assignee: query_param(query, "assignee"),
+        ready: query_flag(query, "ready"),
+        blocked: query_flag(query, "blocked"),
+        include_archived: query_flag(query, "include_archived"),
+        format: OutputFormat::Json,
+    };
+
+    let issues = list::execute(&state.project, &args)?;
+    Ok(Reply::ok(output::issues_json(&issues)?).with_etag(state.etag(Path::new(".itack"))?))
+}
+
+fn get_issue(state: &ServerState, id: u32) -> Result<Reply> {
+    let info = load_issue_from_data_branch(&state.project.repo_root, state.data_branch(), id)?;
+    issue_reply(state, &info)
+}
+
+fn create_issue(state: &ServerState, request: &mut Request) -> Result<Reply> {
+    let args: create::CreateArgs = read_json(request)?;
+
+    let _guard = state.write_lock.lock().unwrap_or_else(|e| e.into_inner());
+    let info = create::execute(&state.project, args)?;
+
+    let mut reply = issue_reply(state, &info)?.with_status(201);
+    reply.location = Some(format!("/issues/{}", info.issue.id));
+    Ok(reply)
+}
+
+/// Fields that can be changed with `PATCH /issues/{id}`.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct IssuePatch {
+    title: Option<String>,
+    body: Option<String>,
+    /// An empty string removes the epic.
+    epic: Option<String>,
+    status: Option<Status>,
+}
+
+fn patch_issue(state: &ServerState, request: &mut Request, id: u32) -> Result<Reply> {
+    let patch: IssuePatch = read_json(request)?;
+    let project = &state.project;
+    let data_branch = state.data_branch();
+
+    let _guard = state.write_lock.lock().unwrap_or_else(|e| e.into_inner());
+    let info = load_issue_from_data_branch(&project.repo_root, data_branch, id)?;
+
+    // Optimistic concurrency: refuse to overwrite changes the client hasn't seen
+    if let Some(expected) = header(request, "If-Match")
+        && expected != "*"
+        && Some(expected) != state.etag(&info.relative_path)?
+    {
+        return Ok(Reply::error(412, "Issue changed since it was read"));
+    }
+
+    // Starting and stopping work go through the claim endpoints, which keep the
+    // claim lock and assignee in step; reopening a closed issue is a plain edit
+    if let Some(status @ (Status::Open | Status::InProgress)) = patch.status
+        && !info.issue.status.can_edit_to(status)
+    {
+        return Ok(Reply::error(
+            422,
+            &format!(
+                "Status can't be changed from {} to {} with PATCH; use POST or DELETE /issues/{}/claim",
+                info.issue.status, status, id
+            ),
+        ));
+    }
+
+    let mut updated = info.clone();
+    if let Some(title) = patch.title {
+        updated.title = title;
+    }
+    if let Some(body) = patch.body {
+        updated.body = body;
+    }
+    if let Some(epic) = patch.epic {
+        updated.issue.epic = (!epic.is_empty()).then_some(epic);
+    }
+    if patch.status == Some(Status::Open) && info.issue.status.is_closed() {
+        updated.issue.status = Status::Open;
+        updated.issue.assignee = None;
+        updated.issue.branch = None;
+        updated.issue.session = None;
+    }
+
+    let edited = updated.title != info.title
+        || updated.body != info.body
+        || updated.issue.epic != info.issue.epic
+        || updated.issue.status != info.issue.status;
+    if edited {
+        hooks::run(project, HookEvent::PreEdit, &updated, &[])?;
+        let mut edit = TreeEdit::new();
+        unarchive_reopened(&mut edit, &mut updated);
+        let content = format_issue(&updated.issue, &updated.title, &updated.body)?;
+        edit.write(&updated.relative_path, content);
+        edit.commit(
+            &project.repo_root,
+            data_branch,
+            &format!("Edit issue #{}", id),
+        )?;
+        hooks::run(project, HookEvent::PostEdit, &updated, &[])?;
+    }
+
+    // Closing goes through the regular commands so hooks and unblocking still apply
+    match patch.status {
+        Some(Status::Done) if updated.issue.status != Status::Done => {
+            let args = done::DoneArgs {
+                id,
+                format: OutputFormat::Json,
+            };
+            done::execute(project, &args)?;
+        }
+        Some(Status::WontFix) if updated.issue.status != Status::WontFix => {
+            let args = wontfix::WontFixArgs {
+                id,
+                format: OutputFormat::Json,
+            };
+            wontfix::execute(project, &args)?;
+        }
+        _ => {}
+    }
+
+    get_issue(state, id)
+}
+
+fn claim_issue(state: &ServerState, request: &mut Request, id: u32) -> Result<Reply> {
+    let mut args: Value = read_json(request)?;
+    if let Some(fields) = args.as_object_mut() {
+        fields.insert("id".to_string(), json!(id));
+    }
+    let args: claim::ClaimArgs = serde_json::from_value(args)?;
+
+    let _guard = state.write_lock.lock().unwrap_or_else(|e| e.into_inner());
+    let info = claim::execute(&state.project, args)?;
+    issue_reply(state, &info)
+}
+
+fn release_issue(state: &ServerState, id: u32) -> Result<Reply> {
+    let _guard = state.write_lock.lock().unwrap_or_else(|e| e.into_inner());
+    let (info, _) = release::execute(&state.project, &release::ReleaseArgs { id })?;
+    issue_reply(state, &info)
+}
+
+fn list_claims(state: &ServerState) -> Result<Reply> {
+    let db = state.project.open_db()?;
+    let claims: Vec<Value> = db
+        .list_claims()?
+        .into_iter()
+        .map(|(issue_id, assignee, claimed_at)| {
+            json!({
+                "issue_id": issue_id,
+                "assignee": assignee,
+                "claimed_at": claimed_at.to_rfc3339(),
+            })
+        })
+        .collect();
+    Ok(Reply::ok(serde_json::to_string_pretty(&claims)?))
+}
+
+fn get_board(state: &ServerState) -> Result<Reply> {
+    let (summary, issues) = board::execute(&state.project)?;
+    Ok(Reply::ok(output::board_json(&summary, &issues)?)
+        .with_etag(state.etag(Path::new(".itack"))?))
+}
+
+fn issue_reply(state: &ServerState, info: &IssueInfo) -> Result<Reply> {
+    let body = output::issue_json(&info.issue, &info.title, &info.body)?;
+    Ok(Reply::ok(body).with_etag(state.etag(&info.relative_path)?))
+}
+
+/// Stream issue events as server-sent events until the client disconnects.
+fn stream_events(state: &ServerState, request: Request, query: &[(String, String)]) {
+    let repo_root = &state.project.repo_root;
+    let data_branch = state.data_branch();
+
+    let since = query_param(query, "since").or_else(|| header(&request, "Last-Event-ID"));
+    let start = match since {
+        Some(rev) => resolve_commit(repo_root, &rev).map(Some),
+        None => branch_tip(repo_root, data_branch),
+    };
+    let mut last = match start {
+        Ok(last) => last,
+        Err(e) => {
+            let response = Response::from_string(json!({ "error": e.to_string() }).to_string())
+                .with_status_code(400)
+                .with_header(header_value("Content-Type", "application/json"));
+            let _ = request.respond(response);
+            return;
+        }
+    };
+
+    // tiny_http buffers chunked bodies, so write the stream to the connection directly
+    let mut writer = request.into_writer();
+    let head = "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nConnection: close\r\n\r\n";
+    if writer
+        .write_all(head.as_bytes())
+        .and_then(|_| writer.flush())
+        .is_err()
+    {
+        return;
+    }
+
+    let mut last_write = Instant::now();
+    loop {
+        let mut chunk = String::new();
+        if let Ok(Some(tip)) = branch_tip(repo_root, data_branch)
+            && Some(tip) != last
+        {
+            let Ok(events) = events_between(repo_root, last, tip) else {
+                return;
+            };
+            for event in &events {
+                let (Ok(kind), Ok(data)) = (
+                    serde_json::to_value(event.event),
+                    serde_json::to_string(event),
+                ) else {
+                    return;
+                };
+                let kind = kind.as_str().unwrap_or_default().to_string();
+                chunk.push_str(&format!(
+                    "id: {}\nevent: {}\ndata: {}\n\n",
+                    event.commit, kind, data
+                ));
+            }
+            last = Some(tip);
+        }
+
+        if chunk.is_empty() && last_write.elapsed() >= KEEPALIVE_INTERVAL {
+            chunk.push_str(": keepalive\n\n");
+        }
+
+        if !chunk.is_empty() {
+            if writer
+                .write_all(chunk.as_bytes())
+                .and_then(|_| writer.flush())
+                .is_err()
+            {
+                return;
+            }
+            last_write = Instant::now();
+        }
+
+        thread::sleep(EVENT_POLL_INTERVAL);
+    }
+}
+
+/// Parse the request body as JSON, treating an empty body as `{}`.
+fn read_json<T: serde::de::DeserializeOwned>(request: &mut Request) -> Result<T> {
+    let mut body = String::new();
+    request.as_reader().read_to_string(&mut body)?;
+    let body = if body.trim().is_empty() { "{}" } else { &body };
+    Ok(serde_json::from_str(body)?)
+}
+
+fn header(request: &Request, name: &str) -> Option<String> {
+    request
+        .headers()
+        .iter()
+        .find(|h| h.field.as_str().as_str().eq_ignore_ascii_case(name))
+        .map(|h| h.value.as_str().to_string())
+}
+
+fn header_value(name: &str, value: &str) -> Header {
+    Header::from_bytes(name.as_bytes(), value.as_bytes()).expect("valid header")
+}
+
+fn query_param(query: &[(String, String)], name: &str) -> Option<String> {
+    query
+        .iter()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.clone())
+}
+
+/// A boolean query flag: present without a value, or `true`/`1`.
+fn query_flag(query: &[(String, String)], name: &str) -> bool {
+    query_param(query, name).is_some_and(|v| v.is_empty() || v == "true" || v == "1")
+}
+
+/// Split a request URL into its path and decoded query parameters.
+fn split_url(url: &str) -> (&str, Vec<(String, String)>) {
+    let (path, query) = url.split_once('?').unwrap_or((url, ""));
+    let params = query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (percent_decode(key), percent_decode(value))
+        })
+        .collect();
+    (path, params)
+}
+
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => decoded.push(b' '),
+            b'%' if i + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok();
+                match hex.and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                    Some(byte) => {
+                        decoded.push(byte);
+                        i += 2;
+                    }
+                    None => decoded.push(b'%'),
+                }
+            }
+            byte => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_url() {
+        let (path, query) = split_url("/issues?epic=Phase%201&ready&status=in-progress");
+        assert_eq!(path, "/issues");
+        assert_eq!(query_param(&query, "epic").as_deref(), Some("Phase 1"));
+        assert!(query_flag(&query, "ready"));
+        assert!(!query_flag(&query, "blocked"));
+        assert_eq!(
+            query_param(&query, "status").as_deref(),
+            Some("in-progress")
+        );
+
+        let (path, query) = split_url("/board");
+        assert_eq!(path, "/board");
+        assert!(query.is_empty());
+    }
+
+    #[test]
+    fn test_percent_decode() {
+        assert_eq!(percent_decode("a+b%2Fc"), "a b/c");
+        assert_eq!(percent_decode("100%"), "100%");
+        assert_eq!(percent_decode("%zz"), "%zz");
+    }
+}
@@ -0,0 +1,418 @@
+//! itack set command: change individual fields of an issue without an editor.
+
+use std::collections::BTreeMap;
+
+use clap::ValueEnum;
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+use crate::commands::archive::unarchive_reopened;
+use crate::commands::done::cascade_unblocked;
+use crate::core::deps::open_dependencies;
+use crate::core::hooks::{self, HookEvent};
+use crate::core::{Issue, Priority, Project, Status, TreeEdit};
+use crate::error::{ItackError, Result};
+use crate::output;
+use crate::storage::db::{
+    Database, IssueInfo, load_all_issues_from_data_branch, load_issue_from_data_branch,
+};
+use crate::storage::markdown::format_issue;
+
+/// Fields `set` can change, in the order changes are reported.
+pub const FIELDS: &[&str] = &[
+    "title", "epic", "status", "assignee", "branch", "session", "priority", "labels",
+];
+
+/// Arguments for the set command.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SetArgs {
+    /// Issue ID.
+    pub id: u32,
+    /// New values by field name: title, epic, status, assignee, branch, session,
+    /// priority, or labels (comma-separated). An empty value clears any of them
+    /// except title and status. Giving an open issue an assignee starts it, and
+    /// status in-progress needs an assignee.
+    pub fields: BTreeMap<String, String>,
+    /// Start the issue even if issues it depends on are still open.
+    #[serde(default)]
+    pub ignore_deps: bool,
+}
+
+/// Result of setting fields on an issue.
+pub struct SetOutcome {
+    pub issue_info: IssueInfo,
+    /// Fields whose value changed, in [`FIELDS`] order. Empty if nothing was committed.
+    pub changed: Vec<&'static str>,
+    /// Issues that became ready because the status was set to done or wont-fix.
+    pub unblocked: Vec<IssueInfo>,
+}
+
+/// Set fields on an issue and print what changed.
+pub fn run(args: SetArgs) -> Result<()> {
+    let project = Project::discover()?;
+    let outcome = execute(&project, &args)?;
+
+    if outcome.changed.is_empty() {
+        println!("No changes to issue #{}", args.id);
+    } else {
+        println!("Updated issue #{}: {}", args.id, outcome.changed.join(", "));
+        output::print_unblocked(&outcome.unblocked);
+    }
+
+    Ok(())
+}
+
+/// Parse `key=value` arguments into field values.
+pub fn parse_assignments(assignments: &[String]) -> Result<BTreeMap<String, String>> {
+    let mut fields = BTreeMap::new();
+    for assignment in assignments {
+        let (key, value) = assignment.split_once('=').ok_or_else(|| {
+            ItackError::Other(format!("Expected key=value, got '{}'", assignment))
+        })?;
+        let key = key.trim();
+        if fields.insert(key.to_string(), value.to_string()).is_some() {
+            return Err(ItackError::Other(format!(
+                "Field '{}' given more than once",
+                key
+            )));
+        }
+    }
+    Ok(fields)
+}
+
+/// Apply the field values to the issue and commit them to the data branch in
+/// one commit. Status and assignee move together as they do with `claim`,
+/// `release`, `done` and `wont-fix`, and the claim lock moves with them.
+pub fn execute(project: &Project, args: &SetArgs) -> Result<SetOutcome> {
+    if args.fields.is_empty() {
+        return Err(ItackError::Other(format!(
+            "No fields given (fields: {})",
+            FIELDS.join(", ")
+        )));
+    }
+
+    let data_branch = project
+        .config
+        .data_branch
+        .as_deref()
+        .unwrap_or("data/itack");
+
+    let original = load_issue_from_data_branch(&project.repo_root, data_branch, args.id)?;
+    let mut issue_info = original.clone();
+
+    // Validate every value before changing anything
+    let mut changed = Vec::new();
+    for (key, value) in &args.fields {
+        if apply_field(&mut issue_info, key, value)? {
+            changed.push(FIELDS.iter().copied().find(|f| f == key).unwrap());
+        }
+    }
+    sync_status_and_assignee(&original.issue, &mut issue_info.issue, &args.fields)?;
+    let implied = [
+        ("status", original.issue.status != issue_info.issue.status),
+        (
+            "assignee",
+            original.issue.assignee != issue_info.issue.assignee,
+        ),
+        ("branch", original.issue.branch != issue_info.issue.branch),
+        (
+            "session",
+            original.issue.session != issue_info.issue.session,
+        ),
+    ];
+    for (field, differs) in implied {
+        if differs && !changed.contains(&field) {
+            changed.push(field);
+        }
+    }
+    changed.sort_by_key(|field| FIELDS.iter().position(|f| f == field));
+
+    if changed.is_empty() {
+        return Ok(SetOutcome {
+            issue_info,
+            changed,
+            unblocked: Vec::new(),
+        });
+    }
+
+    let old_status = original.issue.status;
+    let new_status = issue_info.issue.status;
+
+    // Starting an issue is a claim, so it waits for dependencies the same way
+    if new_status == Status::InProgress && old_status != Status::InProgress && !args.ignore_deps {
+        let all = load_all_issues_from_data_branch(&project.repo_root, data_branch)?;
+        let open = open_dependencies(&issue_info.issue, &all);
+        if !open.is_empty() {
+            return Err(ItackError::Blocked {
+                id: args.id,
+                blockers: open,
+            });
+        }
+    }
+
+    let close_events = match new_status {
+        Status::Done if old_status != Status::Done => {
+            Some((HookEvent::PreDone, HookEvent::PostDone))
+        }
+        Status::WontFix if old_status != Status::WontFix => {
+            Some((HookEvent::PreWontFix, HookEvent::PostWontFix))
+        }
+        _ => None,
+    };
+    let hook_env = [("ITACK_OLD_STATUS", old_status.to_string())];
+    hooks::run(project, HookEvent::PreEdit, &issue_info, &[])?;
+    if let Some((pre, _)) = close_events {
+        hooks::run(project, pre, &issue_info, &hook_env)?;
+    }
+
+    let old_holder = claim_holder(&original);
+    let new_holder = claim_holder(&issue_info);
+    let mut db = project.open_db()?;
+    let mut released = false;
+    let mut claimed = false;
+    if old_holder != new_holder {
+        if old_holder.is_some() {
+            match db.release(args.id) {
+                Ok(()) => released = true,
+                Err(ItackError::NotClaimed(_)) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        if let Some(assignee) = new_holder {
+            if let Err(e) = db.claim(args.id, assignee, issue_info.issue.epic.as_deref()) {
+                restore_claim(&mut db, &original, released, claimed);
+                return Err(e);
+            }
+            claimed = true;
+        }
+    }
+
+    let mut edit = TreeEdit::new();
+    unarchive_reopened(&mut edit, &mut issue_info);
+    let content = format_issue(&issue_info.issue, &issue_info.title, &issue_info.body)?;
+    edit.write(&issue_info.relative_path, content);
+    let message = format!("Set {} on issue #{}", changed.join(", "), args.id);
+    if let Err(e) = edit.commit(&project.repo_root, data_branch, &message) {
+        restore_claim(&mut db, &original, released, claimed);
+        return Err(e);
+    }
+
+    let old_assignee = original.issue.assignee.as_deref();
+    let new_assignee = issue_info.issue.assignee.as_deref();
+    if old_assignee != new_assignee {
+        if let Some(assignee) = old_assignee {
+            db.touch_agent(assignee, None, None)?;
+        }
+        if let Some(assignee) = new_assignee {
+            db.touch_agent(
+                assignee,
+                issue_info.issue.session.as_deref(),
+                issue_info.issue.branch.as_deref(),
+            )?;
+        }
+    }
+
+    hooks::run(project, HookEvent::PostEdit, &issue_info, &[])?;
+    if let Some((_, post)) = close_events {
+        hooks::run(project, post, &issue_info, &hook_env)?;
+    }
+
+    let unblocked = if new_status.is_closed() && !old_status.is_closed() {
+        cascade_unblocked(project, data_branch, args.id)?
+    } else {
+        Vec::new()
+    };
+
+    Ok(SetOutcome {
+        issue_info,
+        changed,
+        unblocked,
+    })
+}
+
+/// Keep status and assignee in step after the fields were applied: giving an
+/// open issue an assignee starts it, an in-progress issue needs an assignee,
+/// and setting an issue back to open unassigns it. Closed issues keep their
+/// assignee as a record.
+fn sync_status_and_assignee(
+    original: &Issue,
+    issue: &mut Issue,
+    fields: &BTreeMap<String, String>,
+) -> Result<()> {
+    let status_given = fields.contains_key("status");
+    match issue.status {
+        Status::Open if status_given && original.status != Status::Open => {
+            if fields.contains_key("assignee") && issue.assignee.is_some() {
+                return Err(ItackError::Other(
+                    "An open issue can't have an assignee; set status=in-progress to start it"
+                        .to_string(),
+                ));
+            }
+            issue.assignee = None;
+            issue.branch = None;
+            issue.session = None;
+        }
+        Status::Open if issue.assignee.is_some() => issue.status = Status::InProgress,
+        Status::InProgress if issue.assignee.is_none() => {
+            if status_given {
+                return Err(ItackError::Other(
+                    "An in-progress issue needs an assignee; set assignee too".to_string(),
+                ));
+            }
+            issue.status = Status::Open;
+            issue.branch = None;
+            issue.session = None;
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Who holds the claim lock on an issue: the assignee while it is in progress.
+fn claim_holder(info: &IssueInfo) -> Option<&str> {
+    match info.issue.status {
+        Status::InProgress => info.issue.assignee.as_deref(),
+        _ => None,
+    }
+}
+
+/// Undo the claim changes made for a set that failed: drop the new
+/// assignee's claim and give the old assignee theirs back.
+fn restore_claim(db: &mut Database, original: &IssueInfo, released: bool, claimed: bool) {
+    if claimed {
+        let _ = db.release(original.issue.id);
+    }
+    if let (true, Some(assignee)) = (released, &original.issue.assignee) {
+        let _ = db.record_claim(original.issue.id, assignee, original.issue.epic.as_deref());
+    }
+}
+
+/// Set one field from its string value. Returns whether the value changed.
+fn apply_field(info: &mut IssueInfo, key: &str, value: &str) -> Result<bool> {
+    let issue = &mut info.issue;
+    let changed = match key {
+        "title" => {
+            let title = value.trim();
+            if title.is_empty() || title.contains('\n') {
+                return Err(ItackError::Other(
+                    "title must be a single non-empty line".to_string(),
+                ));
+            }
+            replace(&mut info.title, title.to_string())
+        }
+        "epic" => replace(&mut issue.epic, optional(value)),
+        "status" => replace(&mut issue.status, parse_enum::<Status>(key, value)?),
+        "assignee" => replace(&mut issue.assignee, optional(value)),
+        "branch" => replace(&mut issue.branch, optional(value)),
+        "session" => replace(&mut issue.session, optional(value)),
+        "priority" => {
+            let priority = optional(value)
+                .map(|value| parse_enum::<Priority>(key, &value))
+                .transpose()?;
+            replace(&mut issue.priority, priority)
+        }
+        "labels" => {
+            let mut labels: Vec<String> = Vec::new();
+            for label in value.split(',').map(str::trim).filter(|l| !l.is_empty()) {
+                if !labels.iter().any(|l| l == label) {
+                    labels.push(label.to_string());
+                }
+            }
+            replace(&mut issue.labels, labels)
+        }
+        _ => {
+            return Err(ItackError::Other(format!(
+                "Unknown field '{}' (fields: {})",
+                key,
+                FIELDS.join(", ")
+            )));
+        }
+    };
+    Ok(changed)
+}
+
+/// Store `value` in `field`, returning whether it differed.
+fn replace<T: PartialEq>(field: &mut T, value: T) -> bool {
+    if *field == value {
+        return false;
+    }
+    *field = value;
+    true
+}
+
+/// An optional field value; empty clears the field.
+fn optional(value: &str) -> Option<String> {
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+/// Parse an enum field value by its kebab-case name.
+fn parse_enum<T: ValueEnum>(key: &str, value: &str) -> Result<T> {
+    T::from_str(value.trim(), false).map_err(|_| {
+        let names: Vec<String> = T::value_variants()
+            .iter()
+            .filter_map(|v| v.to_possible_value())
+            .map(|v| v.get_name().to_string())
+            .collect();
+        ItackError::Other(format!(
+            "Invalid {} '{}' (expected one of: {})",
+            key,
+            value,
+            names.join(", ")
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn issue_info() -> IssueInfo {
+        IssueInfo {
+            issue: Issue::new(1),
+            title: "Original".to_string(),
+            body: String::new(),
+            relative_path: "issue.md".into(),
+        }
+    }
+
+    #[test]
+    fn test_apply_field_parses_typed_values() {
+        let mut info = issue_info();
+        assert!(apply_field(&mut info, "status", "wont-fix").unwrap());
+        assert!(apply_field(&mut info, "priority", "high").unwrap());
+        assert!(apply_field(&mut info, "labels", "bug, ui,bug").unwrap());
+        assert_eq!(info.issue.status, Status::WontFix);
+        assert_eq!(info.issue.priority, Some(Priority::High));
+        assert_eq!(info.issue.labels, vec!["bug", "ui"]);
+
+        // Setting the current value or clearing an unset field is not a change
+        assert!(!apply_field(&mut info, "status", "wont-fix").unwrap());
+        assert!(!apply_field(&mut info, "epic", "").unwrap());
+        assert!(apply_field(&mut info, "priority", "").unwrap());
+        assert_eq!(info.issue.priority, None);
+    }
+
+    #[test]
+    fn test_apply_field_rejects_invalid_values() {
+        let mut info = issue_info();
+        let err = apply_field(&mut info, "status", "finished").unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("open, in-progress, done, wont-fix")
+        );
+        assert!(apply_field(&mut info, "priority", "urgent").is_err());
+        assert!(apply_field(&mut info, "title", " ").is_err());
+        assert!(apply_field(&mut info, "status", "").is_err());
+        assert!(apply_field(&mut info, "id", "7").is_err());
+    }
+
+    #[test]
+    fn test_parse_assignments() {
+        let fields = parse_assignments(&["title=a = b".to_string(), "epic=".to_string()]).unwrap();
+        assert_eq!(fields["title"], "a = b");
+        assert_eq!(fields["epic"], "");
+        assert!(parse_assignments(&["title".to_string()]).is_err());
+        assert!(parse_assignments(&["epic=a".to_string(), " epic=b".to_string()]).is_err());
+    }
+}
@@ -1,36 +1,60 @@
 //! itack show command.
 
+use schemars::JsonSchema;
+use serde::Deserialize;
+
 use crate::core::Project;
+use crate::core::git::find_commit_summary;
 use crate::error::Result;
 use crate::output::{self, OutputFormat};
-use crate::storage::db::load_issue_from_data_branch;
+use crate::storage::db::{IssueInfo, load_issue_from_data_branch};
 
 /// Arguments for the show command.
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct ShowArgs {
+    /// Issue ID.
     pub id: u32,
+    #[serde(skip)]
     pub format: OutputFormat,
 }
 
 /// Show an issue.
 pub fn run(args: ShowArgs) -> Result<()> {
     let project = Project::discover()?;
-    let data_branch = project
-        .config
-        .data_branch
-        .as_deref()
-        .unwrap_or("data/itack");
+    let issue_info = execute(&project, &args)?;
 
-    // Load issue from data branch (source of truth)
-    let issue_info = load_issue_from_data_branch(&project.repo_root, data_branch, args.id)?;
+    let mut commits = Vec::new();
+    for sha in &issue_info.issue.commits {
+        let subject = find_commit_summary(&project.repo_root, sha)?.map(|commit| commit.summary);
+        commits.push((sha.clone(), subject));
+    }
 
     match args.format {
         OutputFormat::Table => {
             output::print_issue_detail(&issue_info.issue, &issue_info.title, &issue_info.body);
+            output::print_linked_commits(&commits);
         }
         OutputFormat::Json => {
-            output::print_issue_json(&issue_info.issue, &issue_info.title, &issue_info.body)?;
+            output::print_issue_json_with_commits(
+                &issue_info.issue,
+                &issue_info.title,
+                &issue_info.body,
+                &commits,
+            )?;
         }
     }
 
     Ok(())
 }
+
+/// Load an issue.
+pub fn execute(project: &Project, args: &ShowArgs) -> Result<IssueInfo> {
+    let data_branch = project
+        .config
+        .data_branch
+        .as_deref()
+        .unwrap_or("data/itack");
+
+    // Load issue from data branch (source of truth)
+    load_issue_from_data_branch(&project.repo_root, data_branch, args.id)
+}
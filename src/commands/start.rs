@@ -0,0 +1,150 @@
+//! itack start command: claim an issue and set up a worktree to work on it.
+
+use std::fs;
+use std::path::PathBuf;
+
+use crate::commands::claim::{self, ClaimArgs};
+use crate::core::Project;
+use crate::core::git::{branch_tip, create_worktree, delete_branch, remove_worktree};
+use crate::error::{ItackError, Result};
+use crate::storage::db::{IssueInfo, load_issue_from_data_branch};
+
+/// Branch name template used when `branch_template` isn't configured.
+pub const DEFAULT_BRANCH_TEMPLATE: &str = "issue-{id}-{slug}";
+
+/// Longest slug taken from a title, so branch and directory names stay readable.
+const MAX_SLUG_LEN: usize = 40;
+
+/// Arguments for the start command.
+#[derive(Debug)]
+pub struct StartArgs {
+    /// Issue ID.
+    pub id: u32,
+    /// Assignee name; defaults to the current agent identity.
+    pub assignee: Option<String>,
+    /// Session ID working on the issue.
+    pub session: Option<String>,
+    /// Claim even if issues this one depends on are still open.
+    pub ignore_deps: bool,
+}
+
+/// Claim an issue, create its worktree and print where to work.
+pub fn run(args: StartArgs) -> Result<()> {
+    let project = Project::discover()?;
+    let (issue_info, worktree) = execute(&project, &args)?;
+
+    println!(
+        "Claimed issue #{} for {}",
+        issue_info.issue.id,
+        issue_info.issue.assignee.as_deref().unwrap_or_default()
+    );
+    println!(
+        "Created worktree {} on branch {}",
+        worktree.display(),
+        issue_info.issue.branch.as_deref().unwrap_or_default()
+    );
+
+    Ok(())
+}
+
+/// Create a branch and sibling worktree for the issue, then claim it with that
+/// branch recorded. If the claim fails, the worktree (and the branch, if it
+/// was new) is removed again. Returns the claimed issue and the worktree path.
+pub fn execute(project: &Project, args: &StartArgs) -> Result<(IssueInfo, PathBuf)> {
+    let data_branch = project
+        .config
+        .data_branch
+        .as_deref()
+        .unwrap_or("data/itack");
+
+    let issue_info = load_issue_from_data_branch(&project.repo_root, data_branch, args.id)?;
+    let assignee = project.resolve_assignee(args.assignee.clone())?;
+
+    let template = project
+        .config
+        .branch_template
+        .as_deref()
+        .unwrap_or(DEFAULT_BRANCH_TEMPLATE);
+    let branch = template
+        .replace("{id}", &args.id.to_string())
+        .replace("{slug}", &slugify(&issue_info.title))
+        .replace("{assignee}", &slugify(&assignee))
+        .trim_matches('-')
+        .to_string();
+
+    let parent = project.repo_root.parent().ok_or_else(|| {
+        ItackError::Other("Repository has no parent directory for worktrees".to_string())
+    })?;
+    let worktree = parent.join(branch.replace('/', "-"));
+    if worktree.exists() {
+        return Err(ItackError::Other(format!(
+            "Worktree path {} already exists",
+            worktree.display()
+        )));
+    }
+
+    let new_branch = branch_tip(&project.repo_root, &branch)?.is_none();
+    create_worktree(&project.repo_root, &branch, &worktree)?;
+
+    // Issue metadata is usually untracked, so copy it for itack to work in the worktree
+    let metadata = project.itack_dir.join("metadata.toml");
+    let worktree_metadata = worktree.join(".itack").join("metadata.toml");
+    if !worktree_metadata.exists() {
+        fs::create_dir_all(worktree.join(".itack"))?;
+        fs::copy(&metadata, &worktree_metadata)?;
+    }
+
+    let claim_args = ClaimArgs {
+        id: args.id,
+        assignee: Some(assignee),
+        session: args.session.clone(),
+        ignore_deps: args.ignore_deps,
+        branch: Some(branch.clone()),
+        ..ClaimArgs::default()
+    };
+    match claim::execute(project, claim_args) {
+        Ok(claimed) => Ok((claimed, worktree)),
+        Err(e) => {
+            let _ = remove_worktree(&project.repo_root, &worktree);
+            if new_branch {
+                let _ = delete_branch(&project.repo_root, &branch);
+            }
+            Err(e)
+        }
+    }
+}
+
+/// Turn a title into a lowercase, dash-separated name fragment.
+pub fn slugify(title: &str) -> String {
+    let mut slug = String::new();
+    for word in title
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+    {
+        if !slug.is_empty() && slug.len() + word.len() + 1 > MAX_SLUG_LEN {
+            break;
+        }
+        if !slug.is_empty() {
+            slug.push('-');
+        }
+        slug.push_str(&word.to_ascii_lowercase());
+    }
+    slug.truncate(MAX_SLUG_LEN);
+    slug
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("Fix the parser!"), "fix-the-parser");
+        assert_eq!(slugify("  CSV import: --dry-run "), "csv-import-dry-run");
+        assert_eq!(
+            slugify("A very long title that keeps going well past the limit"),
+            "a-very-long-title-that-keeps-going-well"
+        );
+        assert_eq!(slugify("日本語"), "");
+    }
+}
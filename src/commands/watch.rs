@@ -0,0 +1,56 @@
+//! itack watch command.
+
+use std::io::Write;
+use std::thread;
+use std::time::Duration;
+
+use crate::core::Project;
+use crate::core::events::events_between;
+use crate::core::git::{branch_tip, resolve_commit};
+use crate::error::Result;
+
+/// Arguments for the watch command.
+pub struct WatchArgs {
+    /// Resume after this data branch commit instead of starting at the current tip.
+    pub since: Option<String>,
+    pub interval: Duration,
+    /// Print pending events and exit instead of watching.
+    pub once: bool,
+}
+
+/// Watch the data branch and print issue events as newline-delimited JSON.
+pub fn run(args: WatchArgs) -> Result<()> {
+    let project = Project::discover()?;
+    let data_branch = project
+        .config
+        .data_branch
+        .as_deref()
+        .unwrap_or("data/itack");
+
+    // Without a resume token, only report changes made from now on
+    let mut last = match &args.since {
+        Some(rev) => Some(resolve_commit(&project.repo_root, rev)?),
+        None if args.once => None,
+        None => branch_tip(&project.repo_root, data_branch)?,
+    };
+
+    let stdout = std::io::stdout();
+    loop {
+        if let Some(tip) = branch_tip(&project.repo_root, data_branch)?
+            && Some(tip) != last
+        {
+            let events = events_between(&project.repo_root, last, tip)?;
+            let mut out = stdout.lock();
+            for event in &events {
+                writeln!(out, "{}", serde_json::to_string(event)?)?;
+            }
+            out.flush()?;
+            last = Some(tip);
+        }
+
+        if args.once {
+            return Ok(());
+        }
+        thread::sleep(args.interval);
+    }
+}
@@ -0,0 +1,14 @@
+//! itack whoami command.
+
+use crate::core::Project;
+use crate::error::Result;
+
+/// Print the current agent identity and where it was resolved from.
+pub fn run() -> Result<()> {
+    let project = Project::discover()?;
+    let agent = project.agent()?;
+
+    println!("{} (from {})", agent.name, agent.source);
+
+    Ok(())
+}
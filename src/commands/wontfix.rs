@@ -1,18 +1,45 @@
 //! itack wont-fix command.
 
+use crate::commands::done::cascade_unblocked;
+use crate::core::hooks::{self, HookEvent};
 use crate::core::{Project, Status, commit_to_branch};
 use crate::error::{ItackError, Result};
-use crate::storage::db::load_issue_from_data_branch;
+use crate::output::{self, OutputFormat};
+use crate::storage::db::{IssueInfo, load_issue_from_data_branch};
 use crate::storage::markdown::format_issue;
 
 /// Arguments for the wont-fix command.
 pub struct WontFixArgs {
     pub id: u32,
+    pub format: OutputFormat,
 }
 
 /// Mark an issue as wont-fix.
 pub fn run(args: WontFixArgs) -> Result<()> {
     let project = Project::discover()?;
+    let (old_status, unblocked) = execute(&project, &args)?;
+
+    match args.format {
+        OutputFormat::Table => {
+            println!(
+                "Updated issue #{} status: {} -> {}",
+                args.id,
+                old_status,
+                Status::WontFix
+            );
+            output::print_unblocked(&unblocked);
+        }
+        OutputFormat::Json => {
+            output::print_status_change_json(args.id, old_status, Status::WontFix, &unblocked)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Mark an issue as wont-fix and commit the change to the data branch.
+/// Returns the previous status and the issues that became ready.
+pub fn execute(project: &Project, args: &WontFixArgs) -> Result<(Status, Vec<IssueInfo>)> {
     let data_branch = project
         .config
         .data_branch
@@ -29,6 +56,9 @@ pub fn run(args: WontFixArgs) -> Result<()> {
     let old_status = issue_info.issue.status;
     issue_info.issue.status = Status::WontFix;
 
+    let hook_env = [("ITACK_OLD_STATUS", old_status.to_string())];
+    hooks::run(project, HookEvent::PreWontFix, &issue_info, &hook_env)?;
+
     // Format issue content in memory and commit directly to data branch
     let content = format_issue(&issue_info.issue, &issue_info.title, &issue_info.body)?;
     let message = format!("Mark issue #{} as wont-fix", args.id);
@@ -40,12 +70,20 @@ pub fn run(args: WontFixArgs) -> Result<()> {
         &message,
     )?;
 
-    println!(
-        "Updated issue #{} status: {} -> {}",
-        args.id,
-        old_status,
-        Status::WontFix
-    );
+    // The claim lock only lasts while the work is open; the assignee stays as a record
+    let mut db = project.open_db()?;
+    match db.release(args.id) {
+        Ok(()) | Err(ItackError::NotClaimed(_)) => {}
+        Err(e) => return Err(e),
+    }
 
-    Ok(())
+    hooks::run(project, HookEvent::PostWontFix, &issue_info, &hook_env)?;
+
+    let unblocked = if old_status.is_closed() {
+        Vec::new()
+    } else {
+        cascade_unblocked(project, data_branch, args.id)?
+    };
+
+    Ok((old_status, unblocked))
 }
@@ -0,0 +1,74 @@
+//! Agent identity resolution.
+
+use std::fmt;
+use std::path::Path;
+
+use git2::Repository;
+
+use crate::core::Config;
+
+/// Environment variable that names the current agent.
+pub const AGENT_ENV_VAR: &str = "ITACK_AGENT";
+
+/// Where an agent identity was resolved from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AgentSource {
+    /// The `ITACK_AGENT` environment variable.
+    Env,
+    /// `default_assignee` in the global config.
+    Config,
+    /// The git `user.name` of the repository.
+    Git,
+}
+
+impl fmt::Display for AgentSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AgentSource::Env => write!(f, "{}", AGENT_ENV_VAR),
+            AgentSource::Config => write!(f, "config default_assignee"),
+            AgentSource::Git => write!(f, "git user.name"),
+        }
+    }
+}
+
+/// The identity of the agent running itack.
+#[derive(Debug, Clone)]
+pub struct AgentIdentity {
+    pub name: String,
+    pub source: AgentSource,
+}
+
+/// Resolve the current agent identity.
+/// Checks `ITACK_AGENT`, then `default_assignee` from the config, then the git user name.
+pub fn resolve_agent(config: &Config, repo_root: &Path) -> Option<AgentIdentity> {
+    let non_empty = |s: String| {
+        let s = s.trim().to_string();
+        (!s.is_empty()).then_some(s)
+    };
+
+    if let Some(name) = std::env::var(AGENT_ENV_VAR).ok().and_then(non_empty) {
+        return Some(AgentIdentity {
+            name,
+            source: AgentSource::Env,
+        });
+    }
+
+    if let Some(name) = config.default_assignee.clone().and_then(non_empty) {
+        return Some(AgentIdentity {
+            name,
+            source: AgentSource::Config,
+        });
+    }
+
+    let repo = Repository::discover(repo_root).ok()?;
+    let name = repo
+        .config()
+        .ok()?
+        .get_string("user.name")
+        .ok()
+        .and_then(non_empty)?;
+    Some(AgentIdentity {
+        name,
+        source: AgentSource::Git,
+    })
+}
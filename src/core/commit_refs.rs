@@ -0,0 +1,129 @@
+//! Issue references in commit messages, like `Fix parser, closes #12`.
+//!
+//! A closing keyword (`close`, `fix`, `resolve` and their `-s`/`-d` forms) marks
+//! the issues that follow it as done; `ref`, `refs` and `references` only link
+//! the commit. A keyword applies to a list of issues: `fixes #3, #4 and #5`.
+//! Linking an issue's branch counts any `#N` mention, keyword or not.
+
+/// What a commit message asks for an issue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommitAction {
+    /// Mark the issue done and link the commit.
+    Close,
+    /// Only link the commit.
+    Reference,
+}
+
+/// An issue mentioned after a keyword in a commit message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommitRef {
+    pub id: u32,
+    pub action: CommitAction,
+}
+
+fn keyword_action(word: &str) -> Option<CommitAction> {
+    match word.to_lowercase().as_str() {
+        "close" | "closes" | "closed" | "fix" | "fixes" | "fixed" | "resolve" | "resolves"
+        | "resolved" => Some(CommitAction::Close),
+        "ref" | "refs" | "references" => Some(CommitAction::Reference),
+        _ => None,
+    }
+}
+
+/// Find the issues a commit message closes or references, in order of appearance.
+/// An issue mentioned more than once is listed once, closing if any mention closes.
+pub fn parse_commit_refs(message: &str) -> Vec<CommitRef> {
+    let mut refs: Vec<CommitRef> = Vec::new();
+    let mut action = None;
+
+    for word in message.split_whitespace() {
+        let word = word.trim_end_matches([',', '.', ';', ':', ')', '!']);
+        if let Some(keyword) = keyword_action(word) {
+            action = Some(keyword);
+            continue;
+        }
+        let Some(current) = action else {
+            continue;
+        };
+
+        if let Some(id) = word.strip_prefix('#').and_then(|n| n.parse().ok()) {
+            match refs.iter_mut().find(|r| r.id == id) {
+                Some(existing) if current == CommitAction::Close => existing.action = current,
+                Some(_) => {}
+                None => refs.push(CommitRef {
+                    id,
+                    action: current,
+                }),
+            }
+        } else if !word.is_empty() && !word.eq_ignore_ascii_case("and") {
+            action = None;
+        }
+    }
+
+    refs
+}
+
+/// Whether a commit message mentions `#id` anywhere, with or without a keyword.
+/// Cross-repository references like `owner/repo#4` don't count.
+pub fn mentions_issue(message: &str, id: u32) -> bool {
+    let mention = format!("#{}", id);
+    message
+        .split(|c: char| !(c.is_alphanumeric() || c == '#' || c == '/' || c == '-' || c == '_'))
+        .any(|word| word == mention)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn refs(message: &str) -> Vec<(u32, CommitAction)> {
+        parse_commit_refs(message)
+            .into_iter()
+            .map(|r| (r.id, r.action))
+            .collect()
+    }
+
+    #[test]
+    fn test_closing_keywords() {
+        assert_eq!(
+            refs("Fix parser, closes #12"),
+            vec![(12, CommitAction::Close)]
+        );
+        assert_eq!(refs("Resolved: #3."), vec![(3, CommitAction::Close)]);
+        assert_eq!(
+            refs("FIXES #3, #4 and #5"),
+            vec![
+                (3, CommitAction::Close),
+                (4, CommitAction::Close),
+                (5, CommitAction::Close)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_references() {
+        assert_eq!(
+            refs("Refactor lexer\n\nrefs #7, fixes #8"),
+            vec![(7, CommitAction::Reference), (8, CommitAction::Close)]
+        );
+        assert_eq!(
+            refs("refs #2 then closes #2"),
+            vec![(2, CommitAction::Close)]
+        );
+    }
+
+    #[test]
+    fn test_mentions_without_keyword_are_ignored() {
+        assert!(refs("Follow-up to #4").is_empty());
+        assert!(refs("Fix the bug in #4").is_empty());
+        assert!(refs("closes owner/repo#4").is_empty());
+    }
+
+    #[test]
+    fn test_mentions_issue() {
+        assert!(mentions_issue("Tidy up (#4)", 4));
+        assert!(mentions_issue("Part of #4: lexer", 4));
+        assert!(!mentions_issue("Part of #41", 4));
+        assert!(!mentions_issue("See owner/repo#4", 4));
+    }
+}
@@ -1,9 +1,12 @@
 //! Global configuration (~/.itack/config.toml).
 
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::PathBuf;
+use std::time::Duration;
 
+use crate::core::duration::parse_duration;
 use crate::error::Result;
 
 /// Default value for data_branch config.
@@ -25,6 +28,32 @@ pub struct Config {
     /// Branch where itack stores issue data (default: "data/itack").
     #[serde(default = "default_data_branch")]
     pub data_branch: Option<String>,
+
+    /// How long a claim is held before it expires and can be taken over (e.g. "2h").
+    /// Claims never expire when unset.
+    #[serde(default)]
+    pub claim_ttl: Option<String>,
+
+    /// Maximum number of issues one assignee may claim at once. Unlimited when unset.
+    #[serde(default)]
+    pub max_claims: Option<u32>,
+
+    /// Per-epic maximum number of issues one assignee may claim at once.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub epic_claim_limits: BTreeMap<String, u32>,
+
+    /// Branch name template for `itack start` (default: "issue-{id}-{slug}").
+    /// Placeholders: `{id}`, `{slug}` (the slugged title) and `{assignee}`.
+    #[serde(default)]
+    pub branch_template: Option<String>,
+
+    /// Open the editor on `itack create` when no body is given.
+    #[serde(default)]
+    pub edit_on_create: bool,
+
+    /// Branch that finished work is merged into (default: "main").
+    #[serde(default)]
+    pub mainline: Option<String>,
 }
 
 impl Default for Config {
@@ -33,6 +62,12 @@ impl Default for Config {
             default_assignee: None,
             editor: None,
             data_branch: default_data_branch(),
+            claim_ttl: None,
+            max_claims: None,
+            epic_claim_limits: BTreeMap::new(),
+            branch_template: None,
+            edit_on_create: false,
+            mainline: None,
         }
     }
 }
@@ -103,4 +138,9 @@ impl Config {
             .or_else(|| std::env::var("VISUAL").ok())
             .unwrap_or_else(|| "vi".to_string())
     }
+
+    /// Get the parsed claim time-to-live, if configured.
+    pub fn claim_ttl(&self) -> Result<Option<Duration>> {
+        self.claim_ttl.as_deref().map(parse_duration).transpose()
+    }
 }
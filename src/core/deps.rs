@@ -0,0 +1,108 @@
+//! Dependency resolution between issues.
+
+use crate::core::{Issue, Status};
+use crate::storage::db::IssueInfo;
+
+/// Get the IDs of the issues `issue` depends on that are not yet closed.
+/// Dependencies that no longer exist on the data branch are not considered blocking.
+pub fn open_dependencies(issue: &Issue, issues: &[IssueInfo]) -> Vec<u32> {
+    issue
+        .depends_on
+        .iter()
+        .copied()
+        .filter(|dep| {
+            issues
+                .iter()
+                .any(|info| info.issue.id == *dep && !info.issue.status.is_closed())
+        })
+        .collect()
+}
+
+/// Whether the issue is open and all of its dependencies are closed.
+pub fn is_ready(issue: &Issue, issues: &[IssueInfo]) -> bool {
+    issue.status == Status::Open && open_dependencies(issue, issues).is_empty()
+}
+
+/// Whether the issue is still active but waiting on unfinished dependencies.
+pub fn is_blocked(issue: &Issue, issues: &[IssueInfo]) -> bool {
+    !issue.status.is_closed() && !open_dependencies(issue, issues).is_empty()
+}
+
+/// Get the issues that became ready because `closed_id` was closed.
+/// `issues` must reflect the state after the close.
+pub fn newly_unblocked(closed_id: u32, issues: &[IssueInfo]) -> Vec<&IssueInfo> {
+    issues
+        .iter()
+        .filter(|info| info.issue.depends_on.contains(&closed_id) && is_ready(&info.issue, issues))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(id: u32, status: Status, depends_on: Vec<u32>) -> IssueInfo {
+        let mut issue = Issue::new(id);
+        issue.status = status;
+        issue.depends_on = depends_on;
+        IssueInfo {
+            issue,
+            title: format!("Issue {}", id),
+            body: String::new(),
+            relative_path: std::path::PathBuf::from(format!(".itack/issue-{:03}.md", id)),
+        }
+    }
+
+    #[test]
+    fn test_open_dependencies() {
+        let issues = vec![
+            info(1, Status::Done, vec![]),
+            info(2, Status::InProgress, vec![]),
+            info(3, Status::WontFix, vec![]),
+            info(4, Status::Open, vec![1, 2, 3, 99]),
+        ];
+
+        assert_eq!(open_dependencies(&issues[3].issue, &issues), vec![2]);
+        assert!(open_dependencies(&issues[0].issue, &issues).is_empty());
+    }
+
+    #[test]
+    fn test_ready_and_blocked() {
+        let issues = vec![
+            info(1, Status::Open, vec![]),
+            info(2, Status::Open, vec![1]),
+            info(3, Status::Done, vec![1]),
+            info(4, Status::InProgress, vec![]),
+        ];
+
+        assert!(is_ready(&issues[0].issue, &issues));
+        assert!(!is_blocked(&issues[0].issue, &issues));
+
+        assert!(!is_ready(&issues[1].issue, &issues));
+        assert!(is_blocked(&issues[1].issue, &issues));
+
+        // Closed issues are neither ready nor blocked
+        assert!(!is_ready(&issues[2].issue, &issues));
+        assert!(!is_blocked(&issues[2].issue, &issues));
+
+        // Already being worked on, so not ready to pick up
+        assert!(!is_ready(&issues[3].issue, &issues));
+    }
+
+    #[test]
+    fn test_newly_unblocked() {
+        let issues = vec![
+            info(1, Status::Done, vec![]),
+            info(2, Status::Open, vec![]),
+            info(3, Status::Open, vec![1]),
+            info(4, Status::Open, vec![1, 2]),
+            info(5, Status::Done, vec![1]),
+        ];
+
+        let ids: Vec<u32> = newly_unblocked(1, &issues)
+            .iter()
+            .map(|info| info.issue.id)
+            .collect();
+        assert_eq!(ids, vec![3]);
+    }
+}
@@ -0,0 +1,60 @@
+//! Parsing of short human durations (e.g. `30s`, `10m`, `2h`, `1d`).
+
+use std::time::Duration;
+
+use crate::error::{ItackError, Result};
+
+/// Parse a duration like `90`, `30s`, `10m`, `2h` or `1d`.
+/// A bare number is interpreted as seconds.
+pub fn parse_duration(input: &str) -> Result<Duration> {
+    let input = input.trim();
+    let split = input
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(input.len());
+    let (number, unit) = input.split_at(split);
+
+    let value: u64 = number
+        .parse()
+        .map_err(|_| ItackError::Other(format!("Invalid duration: '{}'", input)))?;
+
+    let multiplier = match unit.trim() {
+        "" | "s" | "sec" | "secs" => 1,
+        "m" | "min" | "mins" => 60,
+        "h" | "hr" | "hrs" => 60 * 60,
+        "d" | "day" | "days" => 24 * 60 * 60,
+        other => {
+            return Err(ItackError::Other(format!(
+                "Invalid duration unit '{}' in '{}' (use s, m, h or d)",
+                other, input
+            )));
+        }
+    };
+
+    let seconds = value
+        .checked_mul(multiplier)
+        .ok_or_else(|| ItackError::Other(format!("Duration too long: '{}'", input)))?;
+    Ok(Duration::from_secs(seconds))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration_units() {
+        assert_eq!(parse_duration("90").unwrap(), Duration::from_secs(90));
+        assert_eq!(parse_duration("30s").unwrap(), Duration::from_secs(30));
+        assert_eq!(parse_duration("10m").unwrap(), Duration::from_secs(600));
+        assert_eq!(parse_duration("2h").unwrap(), Duration::from_secs(7200));
+        assert_eq!(parse_duration("1d").unwrap(), Duration::from_secs(86400));
+    }
+
+    #[test]
+    fn test_parse_duration_invalid() {
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("m").is_err());
+        assert!(parse_duration("10w").is_err());
+        assert!(parse_duration("999999999999999999d").is_err());
+        assert!(parse_duration("99999999999999999999d").is_err());
+    }
+}
@@ -0,0 +1,284 @@
+//! Issue change events derived from data branch history.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use git2::Oid;
+use serde::Serialize;
+
+use crate::core::git::{commit_time, commits_between, first_parent};
+use crate::error::Result;
+use crate::storage::db::{IssueInfo, load_all_issues_at_commit};
+
+/// Kind of change to an issue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    Created,
+    Claimed,
+    Released,
+    StatusChanged,
+    Edited,
+    DependencyChanged,
+    Deleted,
+}
+
+/// A change to an issue, introduced by a commit on the data branch.
+#[derive(Debug, Clone, Serialize)]
+pub struct IssueEvent {
+    pub event: EventKind,
+    pub issue_id: u32,
+    pub title: String,
+    /// Data branch commit that introduced the change (usable as a `--since` resume token).
+    pub commit: String,
+    pub timestamp: DateTime<Utc>,
+    /// Previous value (status or assignee), when relevant.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
+    /// New value (status or assignee), when relevant.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<String>,
+    /// Dependencies added, for `dependency_changed`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub added: Vec<u32>,
+    /// Dependencies removed, for `dependency_changed`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub removed: Vec<u32>,
+}
+
+impl IssueEvent {
+    fn new(event: EventKind, info: &IssueInfo) -> Self {
+        IssueEvent {
+            event,
+            issue_id: info.issue.id,
+            title: info.title.clone(),
+            commit: String::new(),
+            timestamp: DateTime::default(),
+            from: None,
+            to: None,
+            added: Vec::new(),
+            removed: Vec::new(),
+        }
+    }
+
+    fn change(mut self, from: Option<String>, to: Option<String>) -> Self {
+        self.from = from;
+        self.to = to;
+        self
+    }
+}
+
+/// Compute the events that turn `before` into `after`.
+/// Events are ordered by issue ID; the commit and timestamp are left unset.
+pub fn diff_issues(before: &[IssueInfo], after: &[IssueInfo]) -> Vec<IssueEvent> {
+    let before_by_id: HashMap<u32, &IssueInfo> =
+        before.iter().map(|info| (info.issue.id, info)).collect();
+    let after_by_id: HashMap<u32, &IssueInfo> =
+        after.iter().map(|info| (info.issue.id, info)).collect();
+
+    let mut ids: Vec<u32> = before_by_id
+        .keys()
+        .chain(after_by_id.keys())
+        .copied()
+        .collect();
+    ids.sort();
+    ids.dedup();
+
+    let mut events = Vec::new();
+    for id in ids {
+        let (old, new) = match (before_by_id.get(&id), after_by_id.get(&id)) {
+            (None, Some(new)) => {
+                events.push(IssueEvent::new(EventKind::Created, new));
+                continue;
+            }
+            (Some(old), None) => {
+                events.push(IssueEvent::new(EventKind::Deleted, old));
+                continue;
+            }
+            (Some(old), Some(new)) => (old, new),
+            (None, None) => continue,
+        };
+
+        let assignee_changed = old.issue.assignee != new.issue.assignee;
+        if assignee_changed {
+            let kind = if new.issue.assignee.is_some() {
+                EventKind::Claimed
+            } else {
+                EventKind::Released
+            };
+            events.push(
+                IssueEvent::new(kind, new)
+                    .change(old.issue.assignee.clone(), new.issue.assignee.clone()),
+            );
+        }
+
+        if old.issue.status != new.issue.status {
+            events.push(IssueEvent::new(EventKind::StatusChanged, new).change(
+                Some(old.issue.status.to_string()),
+                Some(new.issue.status.to_string()),
+            ));
+        }
+
+        if old.issue.depends_on != new.issue.depends_on {
+            let mut event = IssueEvent::new(EventKind::DependencyChanged, new);
+            event.added = new
+                .issue
+                .depends_on
+                .iter()
+                .filter(|d| !old.issue.depends_on.contains(d))
+                .copied()
+                .collect();
+            event.removed = old
+                .issue
+                .depends_on
+                .iter()
+                .filter(|d| !new.issue.depends_on.contains(d))
+                .copied()
+                .collect();
+            events.push(event);
+        }
+
+        // Any other front matter change is an edit. Session and branch are
+        // rewritten by claim/release, so only count them as edits when the
+        // assignee stayed the same.
+        let mut rest = old.issue.clone();
+        rest.assignee.clone_from(&new.issue.assignee);
+        rest.status = new.issue.status;
+        rest.depends_on.clone_from(&new.issue.depends_on);
+        rest.session.clone_from(&new.issue.session);
+        rest.branch.clone_from(&new.issue.branch);
+        let content_changed = old.title != new.title || old.body != new.body || rest != new.issue;
+        let session_changed = !assignee_changed
+            && (old.issue.session != new.issue.session || old.issue.branch != new.issue.branch);
+        if content_changed || session_changed {
+            events.push(IssueEvent::new(EventKind::Edited, new));
+        }
+    }
+
+    events
+}
+
+/// Collect the events introduced by the data branch commits after `since` up to `tip`.
+/// With no `since`, the whole history up to `tip` is replayed.
+pub fn events_between(repo_root: &Path, since: Option<Oid>, tip: Oid) -> Result<Vec<IssueEvent>> {
+    let mut events = Vec::new();
+    let mut previous: Option<(Oid, Vec<IssueInfo>)> = None;
+
+    for commit in commits_between(repo_root, since, tip)? {
+        let before = match first_parent(repo_root, commit)? {
+            // History is usually linear, so reuse the issues loaded for the last commit
+            Some(parent) => match previous.take() {
+                Some((oid, issues)) if oid == parent => issues,
+                _ => load_all_issues_at_commit(repo_root, parent)?,
+            },
+            None => Vec::new(),
+        };
+        let after = load_all_issues_at_commit(repo_root, commit)?;
+        let timestamp = commit_time(repo_root, commit)?;
+
+        for mut event in diff_issues(&before, &after) {
+            event.commit = commit.to_string();
+            event.timestamp = timestamp;
+            events.push(event);
+        }
+
+        previous = Some((commit, after));
+    }
+
+    Ok(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Issue, Status};
+
+    fn info(id: u32, title: &str) -> IssueInfo {
+        // A fixed creation time, so separately built versions of an issue compare equal
+        let mut issue = Issue::new(id);
+        issue.created = DateTime::default();
+        IssueInfo {
+            issue,
+            title: title.to_string(),
+            body: String::new(),
+            relative_path: std::path::PathBuf::from(format!(".itack/issue-{:03}.md", id)),
+        }
+    }
+
+    fn kinds(events: &[IssueEvent]) -> Vec<(EventKind, u32)> {
+        events.iter().map(|e| (e.event, e.issue_id)).collect()
+    }
+
+    #[test]
+    fn test_diff_created_and_deleted() {
+        let before = vec![info(1, "One")];
+        let after = vec![info(2, "Two")];
+
+        assert_eq!(
+            kinds(&diff_issues(&before, &after)),
+            vec![(EventKind::Deleted, 1), (EventKind::Created, 2)]
+        );
+    }
+
+    #[test]
+    fn test_diff_claim_and_release() {
+        let before = vec![info(1, "One")];
+        let mut claimed = info(1, "One");
+        claimed.issue.assignee = Some("agent-1".to_string());
+        claimed.issue.status = Status::InProgress;
+        claimed.issue.session = Some("s1".to_string());
+
+        let events = diff_issues(&before, std::slice::from_ref(&claimed));
+        assert_eq!(
+            kinds(&events),
+            vec![(EventKind::Claimed, 1), (EventKind::StatusChanged, 1)]
+        );
+        assert_eq!(events[0].to.as_deref(), Some("agent-1"));
+        assert_eq!(events[1].from.as_deref(), Some("open"));
+        assert_eq!(events[1].to.as_deref(), Some("in-progress"));
+
+        let mut released = claimed.clone();
+        released.issue.assignee = None;
+        released.issue.session = None;
+        let events = diff_issues(&[claimed], &[released]);
+        assert_eq!(kinds(&events), vec![(EventKind::Released, 1)]);
+        assert_eq!(events[0].from.as_deref(), Some("agent-1"));
+    }
+
+    #[test]
+    fn test_diff_dependencies_and_edits() {
+        let mut old = info(1, "One");
+        old.issue.depends_on = vec![2, 3];
+        let mut new = info(1, "One, renamed");
+        new.issue.depends_on = vec![3, 4];
+
+        let events = diff_issues(&[old], &[new]);
+        assert_eq!(
+            kinds(&events),
+            vec![(EventKind::DependencyChanged, 1), (EventKind::Edited, 1)]
+        );
+        assert_eq!(events[0].added, vec![4]);
+        assert_eq!(events[0].removed, vec![2]);
+    }
+
+    #[test]
+    fn test_diff_front_matter_edits() {
+        let before = [info(1, "One")];
+        let mut labelled = info(1, "One");
+        labelled.issue.labels = vec!["bug".to_string()];
+        assert_eq!(
+            kinds(&diff_issues(&before, &[labelled])),
+            vec![(EventKind::Edited, 1)]
+        );
+
+        let mut linked = info(1, "One");
+        linked.issue.commits = vec!["abc123".to_string()];
+        assert_eq!(
+            kinds(&diff_issues(&before, &[linked])),
+            vec![(EventKind::Edited, 1)]
+        );
+
+        assert!(diff_issues(&before, &before).is_empty());
+    }
+}
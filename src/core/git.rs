@@ -1,10 +1,12 @@
 //! Git operations for itack.
 
-use std::path::Path;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
+use chrono::{DateTime, Utc};
 use git2::{FileMode, Oid, Repository, Signature};
 
-use crate::error::Result;
+use crate::error::{ItackError, Result};
 
 /// Commit a file to a specific branch without checking it out.
 /// Creates the branch as orphan if it doesn't exist.
@@ -16,95 +18,195 @@ pub fn commit_to_branch(
     content: &[u8],
     message: &str,
 ) -> Result<Option<Oid>> {
-    let repo = Repository::discover(repo_path)?;
-    let signature = repo
-        .signature()
-        .or_else(|_| Signature::now("itack", "itack@localhost"))?;
+    let mut edit = TreeEdit::new();
+    edit.write(file_path, content);
+    edit.commit(repo_path, branch_name, message)
+}
 
-    // Convert file_path to a string for the tree
-    let file_path_str = file_path.to_string_lossy();
+/// A change staged in a `TreeEdit`.
+#[derive(Debug, Clone)]
+enum TreeChange {
+    Write(PathBuf, Vec<u8>),
+    Remove(PathBuf),
+    Rename(PathBuf, PathBuf),
+}
 
-    // Write the blob
-    let blob_oid = repo.blob(content)?;
+/// Edits to a branch's tree that are committed together.
+///
+/// Stage any number of writes, renames and deletions, then `commit` them as a
+/// single commit, so a failure part-way never leaves the branch half-updated.
+/// Changes are applied in the order they were staged.
+#[derive(Debug, Clone, Default)]
+pub struct TreeEdit {
+    changes: Vec<TreeChange>,
+}
 
-    // Try to find the branch
-    let branch_ref = format!("refs/heads/{}", branch_name);
-    let parent_commit = match repo.find_reference(&branch_ref) {
-        Ok(reference) => Some(reference.peel_to_commit()?),
-        Err(e) if e.code() == git2::ErrorCode::NotFound => None,
-        Err(e) => return Err(e.into()),
-    };
+impl TreeEdit {
+    /// Create an empty edit.
+    pub fn new() -> Self {
+        Self::default()
+    }
 
-    // Build the new tree
-    let new_tree = if let Some(ref parent) = parent_commit {
-        // Update existing tree
-        let parent_tree = parent.tree()?;
-        let mut builder = repo.treebuilder(Some(&parent_tree))?;
+    /// Write a file, creating or replacing it.
+    pub fn write(&mut self, path: impl Into<PathBuf>, content: impl Into<Vec<u8>>) -> &mut Self {
+        self.changes
+            .push(TreeChange::Write(path.into(), content.into()));
+        self
+    }
 
-        // Handle nested paths by building tree hierarchy
-        build_nested_tree(&repo, &mut builder, &file_path_str, blob_oid)?;
+    /// Remove a file. Removing a file that doesn't exist is not an error.
+    pub fn remove(&mut self, path: impl Into<PathBuf>) -> &mut Self {
+        self.changes.push(TreeChange::Remove(path.into()));
+        self
+    }
 
-        let tree_oid = builder.write()?;
-        repo.find_tree(tree_oid)?
-    } else {
-        // Create new tree from scratch
-        let mut builder = repo.treebuilder(None)?;
-        build_nested_tree(&repo, &mut builder, &file_path_str, blob_oid)?;
-        let tree_oid = builder.write()?;
-        repo.find_tree(tree_oid)?
-    };
+    /// Move a file, keeping its content.
+    pub fn rename(&mut self, from: impl Into<PathBuf>, to: impl Into<PathBuf>) -> &mut Self {
+        self.changes
+            .push(TreeChange::Rename(from.into(), to.into()));
+        self
+    }
 
-    // Check if tree is different from parent (no changes)
-    if let Some(ref parent) = parent_commit
-        && parent.tree()?.id() == new_tree.id()
-    {
-        return Ok(None);
+    /// Whether no changes are staged.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
     }
 
-    // Create the commit
-    let parents: Vec<&git2::Commit> = parent_commit.iter().collect();
-    let commit_oid = repo.commit(
-        Some(&branch_ref),
-        &signature,
-        &signature,
-        message,
-        &new_tree,
-        &parents,
-    )?;
-
-    Ok(Some(commit_oid))
-}
+    /// Apply the staged changes to a branch in one commit.
+    /// Creates the branch as orphan if it doesn't exist.
+    /// Returns the commit OID if a commit was created, None if the tree is unchanged.
+    pub fn commit(
+        &self,
+        repo_path: &Path,
+        branch_name: &str,
+        message: &str,
+    ) -> Result<Option<Oid>> {
+        let repo = Repository::discover(repo_path)?;
+        let signature = repo
+            .signature()
+            .or_else(|_| Signature::now("itack", "itack@localhost"))?;
+
+        let branch_ref = format!("refs/heads/{}", branch_name);
+        let parent_commit = match repo.find_reference(&branch_ref) {
+            Ok(reference) => Some(reference.peel_to_commit()?),
+            Err(e) if e.code() == git2::ErrorCode::NotFound => None,
+            Err(e) => return Err(e.into()),
+        };
+        let parent_tree = parent_commit.as_ref().map(|c| c.tree()).transpose()?;
+
+        let mut root = parent_tree.as_ref().map(|tree| tree.id());
+        for change in &self.changes {
+            root = match change {
+                TreeChange::Write(path, content) => {
+                    let blob = repo.blob(content)?;
+                    set_entry(&repo, root, &path_parts(path), Some((blob, FileMode::Blob)))?
+                }
+                TreeChange::Remove(path) => set_entry(&repo, root, &path_parts(path), None)?,
+                TreeChange::Rename(from, to) => {
+                    let entry = root
+                        .map(|oid| repo.find_tree(oid))
+                        .transpose()?
+                        .and_then(|tree| tree.get_path(from).ok())
+                        .ok_or_else(|| {
+                            ItackError::Other(format!(
+                                "Cannot rename {}: not found in {}",
+                                from.display(),
+                                branch_name
+                            ))
+                        })?;
+                    let mode = if entry.filemode() == i32::from(FileMode::BlobExecutable) {
+                        FileMode::BlobExecutable
+                    } else {
+                        FileMode::Blob
+                    };
+                    let root = set_entry(&repo, root, &path_parts(from), None)?;
+                    set_entry(&repo, root, &path_parts(to), Some((entry.id(), mode)))?
+                }
+            };
+        }
 
-/// Build a nested tree structure for a file path like ".itack/2024-01-28-issue-001.md".
-fn build_nested_tree(
-    repo: &Repository,
-    builder: &mut git2::TreeBuilder,
-    path: &str,
-    blob_oid: Oid,
-) -> Result<()> {
-    let parts: Vec<&str> = path.split('/').collect();
-
-    if parts.len() == 1 {
-        // Simple file at root level
-        builder.insert(parts[0], blob_oid, FileMode::Blob.into())?;
-    } else {
-        // Nested path - we need to build/update subtrees
-        let dir_name = parts[0];
-        let remaining_path = parts[1..].join("/");
+        // Git trees can't be empty, so an emptied branch still gets an (empty) root tree
+        let new_tree = match root {
+            Some(oid) => repo.find_tree(oid)?,
+            None => repo.find_tree(repo.treebuilder(None)?.write()?)?,
+        };
+
+        if let Some(ref parent_tree) = parent_tree
+            && parent_tree.id() == new_tree.id()
+        {
+            return Ok(None);
+        }
 
-        // Try to get existing subtree
-        let existing_tree = builder
-            .get(dir_name)?
-            .and_then(|entry| repo.find_tree(entry.id()).ok());
+        // Fails if the branch moved since it was read, instead of losing that commit
+        let parents: Vec<&git2::Commit> = parent_commit.iter().collect();
+        let commit_oid = repo.commit(
+            Some(&branch_ref),
+            &signature,
+            &signature,
+            message,
+            &new_tree,
+            &parents,
+        )?;
+
+        Ok(Some(commit_oid))
+    }
+}
 
-        let mut sub_builder = repo.treebuilder(existing_tree.as_ref())?;
-        build_nested_tree(repo, &mut sub_builder, &remaining_path, blob_oid)?;
-        let sub_tree_oid = sub_builder.write()?;
+/// Split a repository-relative path into its components.
+fn path_parts(path: &Path) -> Vec<String> {
+    path.components()
+        .map(|part| part.as_os_str().to_string_lossy().into_owned())
+        .collect()
+}
 
-        builder.insert(dir_name, sub_tree_oid, FileMode::Tree.into())?;
+/// Return a copy of `tree` with the entry at `path` set to `entry`, or removed
+/// when `entry` is None. Directories left empty are dropped, so the result is
+/// None when the whole tree ends up empty.
+fn set_entry(
+    repo: &Repository,
+    tree: Option<Oid>,
+    path: &[String],
+    entry: Option<(Oid, FileMode)>,
+) -> Result<Option<Oid>> {
+    let tree = tree.map(|oid| repo.find_tree(oid)).transpose()?;
+    let mut builder = repo.treebuilder(tree.as_ref())?;
+    let (name, rest) = path
+        .split_first()
+        .ok_or_else(|| ItackError::Other("Empty path".to_string()))?;
+
+    if rest.is_empty() {
+        match entry {
+            Some((oid, mode)) => {
+                builder.insert(name, oid, mode.into())?;
+            }
+            None => {
+                if builder.get(name)?.is_some() {
+                    builder.remove(name)?;
+                }
+            }
+        }
+    } else {
+        let subtree = builder
+            .get(name)?
+            .filter(|existing| existing.kind() == Some(git2::ObjectType::Tree))
+            .map(|existing| existing.id());
+        match set_entry(repo, subtree, rest, entry)? {
+            Some(oid) => {
+                builder.insert(name, oid, FileMode::Tree.into())?;
+            }
+            None => {
+                if builder.get(name)?.is_some() {
+                    builder.remove(name)?;
+                }
+            }
+        }
     }
 
-    Ok(())
+    if builder.is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(builder.write()?))
+    }
 }
 
 /// Read a file from a specific branch without checking it out.
@@ -141,75 +243,7 @@ pub fn read_file_from_branch(
     }
 }
 
-/// Remove a file from a specific branch.
-/// Returns the commit OID if a commit was created, None if the file didn't exist.
-pub fn remove_file_from_branch(
-    repo_path: &Path,
-    branch_name: &str,
-    file_path: &Path,
-    message: &str,
-) -> Result<Option<Oid>> {
-    let repo = Repository::discover(repo_path)?;
-    let signature = repo
-        .signature()
-        .or_else(|_| Signature::now("itack", "itack@localhost"))?;
-
-    let file_path_str = file_path.to_string_lossy();
-
-    let branch_ref = format!("refs/heads/{}", branch_name);
-    let reference = repo.find_reference(&branch_ref)?;
-    let parent_commit = reference.peel_to_commit()?;
-    let parent_tree = parent_commit.tree()?;
-
-    // Navigate to the parent directory and remove the entry
-    let parts: Vec<&str> = file_path_str.split('/').collect();
-    let new_tree = if parts.len() == 1 {
-        let mut builder = repo.treebuilder(Some(&parent_tree))?;
-        builder.remove(parts[0])?;
-        let tree_oid = builder.write()?;
-        repo.find_tree(tree_oid)?
-    } else {
-        // Nested path (e.g. ".itack/filename.md")
-        let dir_name = parts[0];
-        let file_name = parts[1..].join("/");
-
-        let dir_entry = match parent_tree.get_name(dir_name) {
-            Some(entry) => entry,
-            None => return Ok(None),
-        };
-        let dir_tree = repo.find_tree(dir_entry.id())?;
-
-        let mut sub_builder = repo.treebuilder(Some(&dir_tree))?;
-        if sub_builder.get(&file_name)?.is_none() {
-            return Ok(None);
-        }
-        sub_builder.remove(&file_name)?;
-        let sub_tree_oid = sub_builder.write()?;
-
-        let mut builder = repo.treebuilder(Some(&parent_tree))?;
-        builder.insert(dir_name, sub_tree_oid, FileMode::Tree.into())?;
-        let tree_oid = builder.write()?;
-        repo.find_tree(tree_oid)?
-    };
-
-    // Check if tree actually changed
-    if parent_tree.id() == new_tree.id() {
-        return Ok(None);
-    }
-
-    let commit_oid = repo.commit(
-        Some(&branch_ref),
-        &signature,
-        &signature,
-        message,
-        &new_tree,
-        &[&parent_commit],
-    )?;
-
-    Ok(Some(commit_oid))
-}
-
-/// Find an issue file in a branch by issue ID.
+/// Find an issue file in a branch by issue ID, including archived issues.
 /// Returns the relative path if found.
 pub fn find_issue_in_branch(
     repo_path: &Path,
@@ -253,5 +287,446 @@ pub fn find_issue_in_branch(
         return Ok(Some(std::path::PathBuf::from(".itack").join(old_name)));
     }
 
+    // Then look among archived issues
+    if let Some(archive_entry) = itack_tree.get_name("archive") {
+        let archive_tree = repo.find_tree(archive_entry.id())?;
+        let archive_dir = std::path::PathBuf::from(".itack").join("archive");
+        for entry in archive_tree.iter() {
+            if let Some(name) = entry.name()
+                && (name.ends_with(&suffix) || name == old_name)
+            {
+                return Ok(Some(archive_dir.join(name)));
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+/// Get the commit a branch currently points to.
+/// Returns None if the branch doesn't exist.
+pub fn branch_tip(repo_path: &Path, branch_name: &str) -> Result<Option<Oid>> {
+    let repo = Repository::discover(repo_path)?;
+
+    let branch_ref = format!("refs/heads/{}", branch_name);
+    match repo.find_reference(&branch_ref) {
+        Ok(reference) => Ok(Some(reference.peel_to_commit()?.id())),
+        Err(e) if e.code() == git2::ErrorCode::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Resolve a revision (SHA, ref name, `HEAD~1`, ...) to a commit OID.
+pub fn resolve_commit(repo_path: &Path, rev: &str) -> Result<Oid> {
+    let repo = Repository::discover(repo_path)?;
+    let commit = repo.revparse_single(rev)?.peel_to_commit()?;
+    Ok(commit.id())
+}
+
+/// List the commits reachable from `to` but not from `from`, oldest first.
+pub fn commits_between(repo_path: &Path, from: Option<Oid>, to: Oid) -> Result<Vec<Oid>> {
+    let repo = Repository::discover(repo_path)?;
+
+    let mut revwalk = repo.revwalk()?;
+    revwalk.set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::REVERSE)?;
+    revwalk.push(to)?;
+    if let Some(from) = from {
+        revwalk.hide(from)?;
+    }
+
+    let mut commits = Vec::new();
+    for oid in revwalk {
+        commits.push(oid?);
+    }
+    Ok(commits)
+}
+
+/// Find the best common ancestor of two commits, if they share history.
+pub fn merge_base(repo_path: &Path, one: Oid, two: Oid) -> Result<Option<Oid>> {
+    let repo = Repository::discover(repo_path)?;
+    match repo.merge_base(one, two) {
+        Ok(base) => Ok(Some(base)),
+        Err(e) if e.code() == git2::ErrorCode::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Resolve a revision range to its commits, oldest first. `A..B` lists the
+/// commits reachable from `B` (default `HEAD`) but not from `A`; a single
+/// revision lists just that commit.
+pub fn commits_in_range(repo_path: &Path, range: &str) -> Result<Vec<Oid>> {
+    match range.split_once("..") {
+        Some((from, to)) => {
+            let from = if from.is_empty() {
+                None
+            } else {
+                Some(resolve_commit(repo_path, from)?)
+            };
+            let to = resolve_commit(repo_path, if to.is_empty() { "HEAD" } else { to })?;
+            commits_between(repo_path, from, to)
+        }
+        None => Ok(vec![resolve_commit(repo_path, range)?]),
+    }
+}
+
+/// Get the full message of a commit.
+pub fn commit_message(repo_path: &Path, commit_id: Oid) -> Result<String> {
+    let repo = Repository::discover(repo_path)?;
+    let commit = repo.find_commit(commit_id)?;
+    Ok(commit.message().unwrap_or_default().to_string())
+}
+
+/// Look up a commit by SHA for display.
+/// Returns None if the SHA is malformed or the commit is gone (e.g. rebased away).
+pub fn find_commit_summary(repo_path: &Path, sha: &str) -> Result<Option<CommitSummary>> {
+    let repo = Repository::discover(repo_path)?;
+    let Ok(oid) = Oid::from_str(sha) else {
+        return Ok(None);
+    };
+    let commit = match repo.find_commit(oid) {
+        Ok(commit) => commit,
+        Err(e) if e.code() == git2::ErrorCode::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+
+    Ok(Some(CommitSummary {
+        id: commit.id(),
+        time: DateTime::from_timestamp(commit.time().seconds(), 0).unwrap_or_default(),
+        author: commit.author().name().unwrap_or_default().to_string(),
+        summary: commit.summary().unwrap_or_default().to_string(),
+    }))
+}
+
+/// Get the directory git runs hooks from: `core.hooksPath` if set, else `.git/hooks`.
+pub fn hooks_dir(repo_path: &Path) -> Result<PathBuf> {
+    let repo = Repository::discover(repo_path)?;
+    let configured = repo.config()?.get_path("core.hooksPath").ok();
+    Ok(match configured {
+        Some(path) if path.is_absolute() => path,
+        Some(path) => repo.workdir().unwrap_or(repo.path()).join(path),
+        // Linked worktrees live in `.git/worktrees/<name>` but share `.git/hooks`
+        None if repo.is_worktree() => repo
+            .path()
+            .ancestors()
+            .nth(2)
+            .unwrap_or(repo.path())
+            .join("hooks"),
+        None => repo.path().join("hooks"),
+    })
+}
+
+/// Whether `branch` has commits of its own and every one of them is reachable
+/// from `into`. A branch still at the commit it was created from (the oldest
+/// entry of its reflog) has nothing to merge, so it doesn't count.
+/// Errors if either branch doesn't exist.
+pub fn is_branch_merged(repo_path: &Path, branch: &str, into: &str) -> Result<bool> {
+    let repo = Repository::discover(repo_path)?;
+    let tip = |name: &str| -> Result<Oid> {
+        match repo.find_branch(name, git2::BranchType::Local) {
+            Ok(branch) => Ok(branch.get().peel_to_commit()?.id()),
+            Err(e) if e.code() == git2::ErrorCode::NotFound => {
+                Err(ItackError::Other(format!("Branch {} not found", name)))
+            }
+            Err(e) => Err(e.into()),
+        }
+    };
+
+    let branch_tip = tip(branch)?;
+    let into_tip = tip(into)?;
+
+    let reflog = repo.reflog(&format!("refs/heads/{}", branch))?;
+    let created_at = reflog.iter().next_back().map(|entry| entry.id_new());
+    if created_at == Some(branch_tip) {
+        return Ok(false);
+    }
+
+    Ok(branch_tip == into_tip || repo.graph_descendant_of(into_tip, branch_tip)?)
+}
+
+/// Create a worktree at `path` with `branch` checked out. The branch is created
+/// from HEAD unless it already exists.
+pub fn create_worktree(repo_path: &Path, branch: &str, path: &Path) -> Result<()> {
+    let repo = Repository::discover(repo_path)?;
+    if !git2::Branch::name_is_valid(branch)? {
+        return Err(ItackError::Other(format!(
+            "Invalid branch name: {}",
+            branch
+        )));
+    }
+
+    let branch_ref = match repo.find_branch(branch, git2::BranchType::Local) {
+        Ok(existing) => existing,
+        Err(e) if e.code() == git2::ErrorCode::NotFound => {
+            let head = repo.head()?.peel_to_commit()?;
+            repo.branch(branch, &head, false)?
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    // Worktree names can't contain slashes, so use the directory name
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .ok_or_else(|| ItackError::Other(format!("Invalid worktree path: {}", path.display())))?;
+    let mut options = git2::WorktreeAddOptions::new();
+    options.reference(Some(branch_ref.get()));
+    repo.worktree(&name, path, Some(&options))?;
+    Ok(())
+}
+
+/// Delete a local branch. Deleting a branch that doesn't exist is not an error.
+pub fn delete_branch(repo_path: &Path, branch: &str) -> Result<()> {
+    let repo = Repository::discover(repo_path)?;
+    match repo.find_branch(branch, git2::BranchType::Local) {
+        Ok(mut existing) => Ok(existing.delete()?),
+        Err(e) if e.code() == git2::ErrorCode::NotFound => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Find the linked worktree that has `branch` checked out.
+pub fn find_worktree(repo_path: &Path, branch: &str) -> Result<Option<PathBuf>> {
+    let repo = Repository::discover(repo_path)?;
+    for name in repo.worktrees()?.iter().flatten() {
+        let worktree = repo.find_worktree(name)?;
+        let Ok(worktree_repo) = Repository::open_from_worktree(&worktree) else {
+            continue;
+        };
+        let head = worktree_repo.head().ok();
+        if head.as_ref().and_then(|head| head.shorthand()) == Some(branch) {
+            return Ok(Some(worktree.path().to_path_buf()));
+        }
+    }
     Ok(None)
 }
+
+/// Delete a linked worktree's directory and its administrative files. Refuses
+/// if the worktree has uncommitted changes outside `.itack/`.
+pub fn remove_worktree(repo_path: &Path, path: &Path) -> Result<()> {
+    let repo = Repository::discover(repo_path)?;
+    let worktree_repo = Repository::open(path)?;
+
+    let mut options = git2::StatusOptions::new();
+    options.include_untracked(true).recurse_untracked_dirs(true);
+    let dirty = worktree_repo
+        .statuses(Some(&mut options))?
+        .iter()
+        .any(|entry| entry.path().is_some_and(|p| !p.starts_with(".itack/")));
+    if dirty {
+        return Err(ItackError::Other(format!(
+            "Worktree {} has uncommitted changes",
+            path.display()
+        )));
+    }
+
+    let name = worktree_repo
+        .path()
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let worktree = repo.find_worktree(&name)?;
+    worktree.prune(Some(
+        git2::WorktreePruneOptions::new()
+            .valid(true)
+            .working_tree(true),
+    ))?;
+    Ok(())
+}
+
+/// Get the first parent of a commit, if any.
+pub fn first_parent(repo_path: &Path, commit_id: Oid) -> Result<Option<Oid>> {
+    let repo = Repository::discover(repo_path)?;
+    let commit = repo.find_commit(commit_id)?;
+    Ok(commit.parent_ids().next())
+}
+
+/// Get the commit time of a commit.
+pub fn commit_time(repo_path: &Path, commit_id: Oid) -> Result<DateTime<Utc>> {
+    let repo = Repository::discover(repo_path)?;
+    let commit = repo.find_commit(commit_id)?;
+    Ok(DateTime::from_timestamp(commit.time().seconds(), 0).unwrap_or_default())
+}
+
+/// Get the object ID of a file or directory in a branch without reading it.
+/// Returns None if the branch or path doesn't exist.
+pub fn path_oid_in_branch(repo_path: &Path, branch_name: &str, path: &Path) -> Result<Option<Oid>> {
+    let repo = Repository::discover(repo_path)?;
+
+    let branch_ref = format!("refs/heads/{}", branch_name);
+    let reference = match repo.find_reference(&branch_ref) {
+        Ok(r) => r,
+        Err(e) if e.code() == git2::ErrorCode::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+
+    let tree = reference.peel_to_commit()?.tree()?;
+    match tree.get_path(path) {
+        Ok(entry) => Ok(Some(entry.id())),
+        Err(e) if e.code() == git2::ErrorCode::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// A commit, summarized for display.
+#[derive(Debug, Clone)]
+pub struct CommitSummary {
+    pub id: Oid,
+    pub time: DateTime<Utc>,
+    pub author: String,
+    /// First line of the commit message.
+    pub summary: String,
+}
+
+/// Get the history of each file directly inside `dir` on a branch, newest first.
+/// Keys are file names. A commit is listed for a file when it added, changed or
+/// removed the file compared with its first parent.
+pub fn dir_history(
+    repo_path: &Path,
+    branch_name: &str,
+    dir: &Path,
+) -> Result<HashMap<String, Vec<CommitSummary>>> {
+    let repo = Repository::discover(repo_path)?;
+    let mut history: HashMap<String, Vec<CommitSummary>> = HashMap::new();
+
+    let branch_ref = format!("refs/heads/{}", branch_name);
+    let tip = match repo.find_reference(&branch_ref) {
+        Ok(reference) => reference.peel_to_commit()?.id(),
+        Err(e) if e.code() == git2::ErrorCode::NotFound => return Ok(history),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut revwalk = repo.revwalk()?;
+    revwalk.set_sorting(git2::Sort::TOPOLOGICAL)?;
+    revwalk.simplify_first_parent()?;
+    revwalk.push(tip)?;
+
+    // Walking first parents newest to oldest, each commit's parent is the next
+    // commit, so its entries are only read once
+    let mut entries: Option<HashMap<String, Oid>> = None;
+    for oid in revwalk {
+        let commit = repo.find_commit(oid?)?;
+        let current = match entries.take() {
+            Some(current) => current,
+            None => dir_entries(&repo, &commit.tree()?, dir)?,
+        };
+        let parent = match commit.parents().next() {
+            Some(parent) => dir_entries(&repo, &parent.tree()?, dir)?,
+            None => HashMap::new(),
+        };
+
+        let summary = CommitSummary {
+            id: commit.id(),
+            time: DateTime::from_timestamp(commit.time().seconds(), 0).unwrap_or_default(),
+            author: commit.author().name().unwrap_or_default().to_string(),
+            summary: commit.summary().unwrap_or_default().to_string(),
+        };
+
+        let changed = current
+            .iter()
+            .filter(|(name, id)| parent.get(*name) != Some(*id))
+            .map(|(name, _)| name)
+            .chain(parent.keys().filter(|name| !current.contains_key(*name)));
+        for name in changed {
+            history
+                .entry(name.clone())
+                .or_default()
+                .push(summary.clone());
+        }
+
+        entries = Some(parent);
+    }
+
+    Ok(history)
+}
+
+/// Map the names of the entries directly inside `dir` to their object IDs.
+fn dir_entries(repo: &Repository, tree: &git2::Tree, dir: &Path) -> Result<HashMap<String, Oid>> {
+    let dir_tree = match tree.get_path(dir) {
+        Ok(entry) => repo.find_tree(entry.id())?,
+        Err(e) if e.code() == git2::ErrorCode::NotFound => return Ok(HashMap::new()),
+        Err(e) => return Err(e.into()),
+    };
+
+    Ok(dir_tree
+        .iter()
+        .filter_map(|entry| Some((entry.name()?.to_string(), entry.id())))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const BRANCH: &str = "data/itack";
+
+    fn setup_repo() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        Repository::init(dir.path()).unwrap();
+        dir
+    }
+
+    fn read(dir: &TempDir, path: &str) -> Option<String> {
+        read_file_from_branch(dir.path(), BRANCH, Path::new(path))
+            .unwrap()
+            .map(|content| String::from_utf8(content).unwrap())
+    }
+
+    fn commit_count(dir: &TempDir) -> usize {
+        let repo = Repository::open(dir.path()).unwrap();
+        let mut walk = repo.revwalk().unwrap();
+        walk.push_ref(&format!("refs/heads/{}", BRANCH)).unwrap();
+        walk.count()
+    }
+
+    #[test]
+    fn test_tree_edit_applies_changes_in_one_commit() {
+        let dir = setup_repo();
+        let mut edit = TreeEdit::new();
+        edit.write(".itack/1.md", "one")
+            .write(".itack/2.md", "two")
+            .write(".itack/3.md", "three");
+        edit.commit(dir.path(), BRANCH, "Add issues").unwrap();
+
+        let mut edit = TreeEdit::new();
+        edit.remove(".itack/1.md")
+            .rename(".itack/2.md", ".itack/archive/2.md")
+            .write(".itack/3.md", "changed");
+        assert!(
+            edit.commit(dir.path(), BRANCH, "Edit issues")
+                .unwrap()
+                .is_some()
+        );
+
+        assert_eq!(commit_count(&dir), 2);
+        assert_eq!(read(&dir, ".itack/1.md"), None);
+        assert_eq!(read(&dir, ".itack/2.md"), None);
+        assert_eq!(read(&dir, ".itack/archive/2.md").as_deref(), Some("two"));
+        assert_eq!(read(&dir, ".itack/3.md").as_deref(), Some("changed"));
+    }
+
+    #[test]
+    fn test_tree_edit_failed_rename_commits_nothing() {
+        let dir = setup_repo();
+        commit_to_branch(dir.path(), BRANCH, Path::new(".itack/1.md"), b"one", "Add").unwrap();
+
+        let mut edit = TreeEdit::new();
+        edit.write(".itack/2.md", "two")
+            .rename(".itack/missing.md", ".itack/other.md");
+        assert!(edit.commit(dir.path(), BRANCH, "Edit").is_err());
+
+        assert_eq!(commit_count(&dir), 1);
+        assert_eq!(read(&dir, ".itack/2.md"), None);
+    }
+
+    #[test]
+    fn test_tree_edit_unchanged_tree_makes_no_commit() {
+        let dir = setup_repo();
+        commit_to_branch(dir.path(), BRANCH, Path::new(".itack/1.md"), b"one", "Add").unwrap();
+
+        let mut edit = TreeEdit::new();
+        edit.write(".itack/1.md", "one").remove(".itack/missing.md");
+        assert!(edit.commit(dir.path(), BRANCH, "Edit").unwrap().is_none());
+        assert_eq!(commit_count(&dir), 1);
+    }
+}
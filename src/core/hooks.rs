@@ -0,0 +1,184 @@
+//! Lifecycle hooks: executables in `.itack/hooks/` run when issues change.
+//!
+//! Each hook receives the issue as JSON (the `show --json` shape) on stdin, and
+//! `ITACK_EVENT`, `ITACK_ISSUE_ID`, `ITACK_STATUS`, `ITACK_TITLE` and `ITACK_ACTOR`
+//! in its environment. A `pre-*` hook that exits non-zero aborts the change before
+//! anything is committed; `post-*` hook failures are only reported as warnings.
+//! Hook output goes to stderr so it never mixes with itack's own output.
+
+use std::fmt;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus, Stdio};
+
+use crate::core::Project;
+use crate::error::{ItackError, Result};
+use crate::output::IssueDetail;
+use crate::storage::db::IssueInfo;
+
+/// A lifecycle event that can trigger a hook.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookEvent {
+    PreCreate,
+    PostCreate,
+    PreClaim,
+    PostClaim,
+    PreRelease,
+    PostRelease,
+    PreEdit,
+    PostEdit,
+    PreDepend,
+    PostDepend,
+    PreDone,
+    PostDone,
+    PreWontFix,
+    PostWontFix,
+    /// Run for each issue that becomes ready when one of its blockers is closed.
+    PostUnblock,
+}
+
+impl HookEvent {
+    /// The hook executable name for this event (e.g. `pre-claim`).
+    pub fn name(&self) -> &'static str {
+        match self {
+            HookEvent::PreCreate => "pre-create",
+            HookEvent::PostCreate => "post-create",
+            HookEvent::PreClaim => "pre-claim",
+            HookEvent::PostClaim => "post-claim",
+            HookEvent::PreRelease => "pre-release",
+            HookEvent::PostRelease => "post-release",
+            HookEvent::PreEdit => "pre-edit",
+            HookEvent::PostEdit => "post-edit",
+            HookEvent::PreDepend => "pre-depend",
+            HookEvent::PostDepend => "post-depend",
+            HookEvent::PreDone => "pre-done",
+            HookEvent::PostDone => "post-done",
+            HookEvent::PreWontFix => "pre-wont-fix",
+            HookEvent::PostWontFix => "post-wont-fix",
+            HookEvent::PostUnblock => "post-unblock",
+        }
+    }
+
+    /// Whether this event runs before the change is committed (and may veto it).
+    pub fn is_pre(&self) -> bool {
+        matches!(
+            self,
+            HookEvent::PreCreate
+                | HookEvent::PreClaim
+                | HookEvent::PreRelease
+                | HookEvent::PreEdit
+                | HookEvent::PreDepend
+                | HookEvent::PreDone
+                | HookEvent::PreWontFix
+        )
+    }
+}
+
+impl fmt::Display for HookEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Get the path of a hook executable, if it is installed.
+pub fn hook_path(itack_dir: &Path, event: HookEvent) -> Option<PathBuf> {
+    let path = itack_dir.join("hooks").join(event.name());
+    path.is_file().then_some(path)
+}
+
+/// Run the hook for `event` with the issue as it is (post) or will be (pre).
+///
+/// Returns `HookRejected` if a pre-hook exits non-zero. Post-hook failures are
+/// printed as warnings and do not fail the command.
+pub fn run(
+    project: &Project,
+    event: HookEvent,
+    info: &IssueInfo,
+    extra_env: &[(&str, String)],
+) -> Result<()> {
+    let result = run_hook(project, event, info, extra_env);
+
+    if event.is_pre() {
+        match result? {
+            Some(status) if !status.success() => Err(ItackError::HookRejected(
+                event.to_string(),
+                status.to_string(),
+            )),
+            _ => Ok(()),
+        }
+    } else {
+        match result {
+            Ok(Some(status)) if !status.success() => {
+                eprintln!(
+                    "Warning: {} hook failed for issue #{}: {}",
+                    event, info.issue.id, status
+                );
+            }
+            Ok(_) => {}
+            Err(e) => eprintln!("Warning: {}", e),
+        }
+        Ok(())
+    }
+}
+
+/// Run a hook executable. Returns None if the hook is not installed.
+fn run_hook(
+    project: &Project,
+    event: HookEvent,
+    info: &IssueInfo,
+    extra_env: &[(&str, String)],
+) -> Result<Option<ExitStatus>> {
+    let Some(path) = hook_path(&project.itack_dir, event) else {
+        return Ok(None);
+    };
+
+    let actor = project.agent().map(|a| a.name).unwrap_or_default();
+    let payload = serde_json::to_string(&IssueDetail::new(&info.issue, &info.title, &info.body))?;
+
+    let mut command = Command::new(&path);
+    command
+        .current_dir(&project.repo_root)
+        .stdin(Stdio::piped())
+        .stdout(std::io::stderr())
+        .env("ITACK_EVENT", event.name())
+        .env("ITACK_ISSUE_ID", info.issue.id.to_string())
+        .env("ITACK_STATUS", info.issue.status.to_string())
+        .env("ITACK_TITLE", &info.title)
+        .env("ITACK_ACTOR", actor);
+    for (key, value) in extra_env {
+        command.env(key, value);
+    }
+
+    let mut child = command
+        .spawn()
+        .map_err(|e| ItackError::Other(format!("Failed to run hook {}: {}", path.display(), e)))?;
+
+    let written = match child.stdin.take() {
+        Some(mut stdin) => stdin.write_all(payload.as_bytes()),
+        None => Ok(()),
+    };
+    let status = child.wait()?;
+
+    // Hooks are free to ignore stdin, so a broken pipe is not an error
+    if let Err(e) = written
+        && e.kind() != std::io::ErrorKind::BrokenPipe
+    {
+        return Err(e.into());
+    }
+
+    Ok(Some(status))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hook_event_names() {
+        assert_eq!(HookEvent::PreClaim.name(), "pre-claim");
+        assert_eq!(HookEvent::PostWontFix.name(), "post-wont-fix");
+        assert!(HookEvent::PreDone.is_pre());
+        assert!(!HookEvent::PostDone.is_pre());
+        assert!(!HookEvent::PostUnblock.is_pre());
+    }
+}
@@ -3,12 +3,12 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-use crate::core::Status;
+use crate::core::{Priority, Status};
 
 /// An issue in the tracker.
 /// Fields are ordered alphabetically for consistent YAML output.
 /// Note: The title is stored in the markdown body as an H1 heading, not in YAML.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Issue {
     /// Assignee (who has claimed the issue).
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -18,6 +18,10 @@ pub struct Issue {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub branch: Option<String>,
 
+    /// SHAs of code commits that implemented or referenced this issue.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub commits: Vec<String>,
+
     /// Creation timestamp.
     pub created: DateTime<Utc>,
 
@@ -32,6 +36,22 @@ pub struct Issue {
     /// Unique issue ID.
     pub id: u32,
 
+    /// Where the issue was imported from, as `<source>:<external id>` (e.g. `github:owner/repo#12`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub imported_from: Option<String>,
+
+    /// Free-form labels.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub labels: Vec<String>,
+
+    /// Where the issue was moved by `itack move`, as `<repo path>#<id>`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub moved_to: Option<String>,
+
+    /// How urgent the issue is.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<Priority>,
+
     /// Session ID (e.g., Claude Code session) working on this issue.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub session: Option<String>,
@@ -46,10 +66,15 @@ impl Issue {
         Issue {
             assignee: None,
             branch: None,
+            commits: Vec::new(),
             created: Utc::now(),
             depends_on: Vec::new(),
             epic: None,
             id,
+            imported_from: None,
+            labels: Vec::new(),
+            moved_to: None,
+            priority: None,
             session: None,
             status: Status::default(),
         }
@@ -1,15 +1,22 @@
 //! Core types and functionality.
 
+pub mod agent;
+pub mod commit_refs;
 pub mod config;
+pub mod deps;
+pub mod duration;
+pub mod events;
 pub mod git;
+pub mod hooks;
 pub mod issue;
+pub mod priority;
 pub mod project;
 pub mod status;
+pub mod template;
 
 pub use config::Config;
-pub use git::{
-    commit_to_branch, find_issue_in_branch, read_file_from_branch, remove_file_from_branch,
-};
+pub use git::{TreeEdit, commit_to_branch, find_issue_in_branch, read_file_from_branch};
 pub use issue::Issue;
+pub use priority::Priority;
 pub use project::Project;
 pub use status::Status;
@@ -4,7 +4,9 @@ use git2::Repository;
 use std::path::{Path, PathBuf};
 
 use crate::core::Config;
+use crate::core::agent::{AgentIdentity, resolve_agent};
 use crate::error::{ItackError, Result};
+use crate::storage::db::ClaimPolicy;
 use crate::storage::{Database, Metadata};
 
 /// Project context for itack operations.
@@ -12,7 +14,6 @@ pub struct Project {
     /// Root directory of the git repository.
     pub repo_root: PathBuf,
     /// Path to .itack directory.
-    #[allow(dead_code)]
     pub itack_dir: PathBuf,
     /// Path to the SQLite database (in global config dir).
     pub db_path: PathBuf,
@@ -25,7 +26,12 @@ pub struct Project {
 impl Project {
     /// Find and load the project context from the current directory.
     pub fn discover() -> Result<Self> {
-        let repo_root = Self::find_repo_root()?;
+        Self::discover_from(&std::env::current_dir()?)
+    }
+
+    /// Find and load the project context of the repository containing `path`.
+    pub fn discover_from(path: &Path) -> Result<Self> {
+        let repo_root = Self::find_repo_root_from(path)?;
         let itack_dir = repo_root.join(".itack");
 
         if !itack_dir.exists() {
@@ -54,8 +60,12 @@ impl Project {
 
     /// Find the git repository root.
     pub fn find_repo_root() -> Result<PathBuf> {
-        let current = std::env::current_dir()?;
-        let repo = Repository::discover(&current).map_err(|_| ItackError::NotInGitRepo)?;
+        Self::find_repo_root_from(&std::env::current_dir()?)
+    }
+
+    /// Find the root of the git repository containing `path`.
+    fn find_repo_root_from(path: &Path) -> Result<PathBuf> {
+        let repo = Repository::discover(path).map_err(|_| ItackError::NotInGitRepo)?;
 
         repo.workdir()
             .map(|p| p.to_path_buf())
@@ -77,10 +87,16 @@ impl Project {
         itack_dir.exists() && metadata_path.exists()
     }
 
-    /// Open the database for this project.
+    /// Open the database for this project, applying the configured claim policy.
     pub fn open_db(&self) -> Result<Database> {
         let data_branch = self.config.data_branch.as_deref();
-        Database::open(&self.db_path, Some(&self.repo_root), data_branch)
+        let mut db = Database::open(&self.db_path, Some(&self.repo_root), data_branch)?;
+        db.set_claim_policy(ClaimPolicy {
+            ttl: self.config.claim_ttl()?,
+            max_claims: self.config.max_claims,
+            epic_limits: self.config.epic_claim_limits.clone(),
+        });
+        Ok(db)
     }
 
     /// Get the relative path to an issue file (e.g. `.itack/2026-01-25-issue-001.md`).
@@ -90,6 +106,19 @@ impl Project {
         PathBuf::from(".itack").join(format!("{}-issue-{:03}.md", date_str, id))
     }
 
+    /// Resolve the identity of the agent running this command.
+    pub fn agent(&self) -> Result<AgentIdentity> {
+        resolve_agent(&self.config, &self.repo_root).ok_or(ItackError::NoAgentIdentity)
+    }
+
+    /// Use the given assignee, or fall back to the current agent identity.
+    pub fn resolve_assignee(&self, assignee: Option<String>) -> Result<String> {
+        match assignee {
+            Some(assignee) => Ok(assignee),
+            None => Ok(self.agent()?.name),
+        }
+    }
+
     /// Get the current git branch name.
     /// Returns None if in a detached HEAD state or if there's no HEAD (unborn branch).
     pub fn current_branch(&self) -> Option<String> {
@@ -1,11 +1,14 @@
 //! Issue status enum with sort priority.
 
 use clap::ValueEnum;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
 /// Issue status with defined sort priority.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default, ValueEnum)]
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Default, ValueEnum,
+)]
 #[serde(rename_all = "kebab-case")]
 #[clap(rename_all = "kebab-case")]
 pub enum Status {
@@ -27,6 +30,20 @@ impl Status {
             Status::WontFix => 3,
         }
     }
+
+    /// Whether the issue is finished (done or wont-fix).
+    pub fn is_closed(&self) -> bool {
+        matches!(self, Status::Done | Status::WontFix)
+    }
+
+    /// Whether editing an issue file may change its status from this one to
+    /// `to`. Starting, stopping and closing work go through `claim`, `release`,
+    /// `done` and `wont-fix`, which keep the claim lock in step and run the
+    /// hooks, so the only status change an edit can make is reopening a closed
+    /// issue.
+    pub fn can_edit_to(&self, to: Status) -> bool {
+        *self == to || self.is_closed() && to == Status::Open
+    }
 }
 
 impl fmt::Display for Status {
@@ -52,6 +69,27 @@ mod tests {
         assert_eq!(Status::WontFix.sort_priority(), 3);
     }
 
+    #[test]
+    fn test_status_is_closed() {
+        assert!(!Status::Open.is_closed());
+        assert!(!Status::InProgress.is_closed());
+        assert!(Status::Done.is_closed());
+        assert!(Status::WontFix.is_closed());
+    }
+
+    #[test]
+    fn test_status_can_edit_to() {
+        assert!(Status::Done.can_edit_to(Status::Open));
+        assert!(Status::WontFix.can_edit_to(Status::Open));
+        assert!(Status::InProgress.can_edit_to(Status::InProgress));
+        assert!(!Status::Open.can_edit_to(Status::Done));
+        assert!(!Status::InProgress.can_edit_to(Status::WontFix));
+        assert!(!Status::Done.can_edit_to(Status::WontFix));
+        assert!(!Status::Open.can_edit_to(Status::InProgress));
+        assert!(!Status::Done.can_edit_to(Status::InProgress));
+        assert!(!Status::InProgress.can_edit_to(Status::Open));
+    }
+
     #[test]
     fn test_status_display() {
         assert_eq!(Status::Open.to_string(), "open");
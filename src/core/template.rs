@@ -0,0 +1,126 @@
+//! Issue templates: `.itack/templates/<name>.md` files used by `create --template`.
+//!
+//! A template is a markdown body skeleton, optionally preceded by YAML front
+//! matter with defaults for `epic`, `labels` and `priority`. Placeholder prompts
+//! are best written as HTML comments, which don't show when the issue is rendered.
+
+use std::fs;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::core::Priority;
+use crate::error::{ItackError, Result};
+use crate::storage::markdown::split_front_matter;
+
+/// Front matter defaults of a template.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TemplateDefaults {
+    pub epic: Option<String>,
+    #[serde(default)]
+    pub labels: Vec<String>,
+    pub priority: Option<Priority>,
+}
+
+/// A parsed issue template.
+#[derive(Debug)]
+pub struct Template {
+    pub defaults: TemplateDefaults,
+    pub body: String,
+}
+
+impl Template {
+    /// Parse a template file's content. Front matter is optional.
+    pub fn parse(content: &str) -> Result<Self> {
+        if !content.trim_start().starts_with("---") {
+            return Ok(Template {
+                defaults: TemplateDefaults::default(),
+                body: content.to_string(),
+            });
+        }
+
+        let (yaml, body) = split_front_matter(content)?;
+        let defaults = if yaml.trim().is_empty() {
+            TemplateDefaults::default()
+        } else {
+            serde_yaml::from_str(yaml)?
+        };
+        Ok(Template {
+            defaults,
+            body: body.to_string(),
+        })
+    }
+
+    /// Load the template `name` from `<itack_dir>/templates/<name>.md`.
+    pub fn load(itack_dir: &Path, name: &str) -> Result<Self> {
+        let path = itack_dir.join("templates").join(format!("{}.md", name));
+        match fs::read_to_string(&path) {
+            Ok(content) => Self::parse(&content).map_err(|e| {
+                ItackError::Other(format!("Invalid template {}: {}", path.display(), e))
+            }),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                let available = Self::names(itack_dir);
+                Err(ItackError::Other(if available.is_empty() {
+                    format!("Template '{}' not found: .itack/templates/ is empty", name)
+                } else {
+                    format!(
+                        "Template '{}' not found (available: {})",
+                        name,
+                        available.join(", ")
+                    )
+                }))
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Names of the templates in `<itack_dir>/templates`, sorted.
+    pub fn names(itack_dir: &Path) -> Vec<String> {
+        let Ok(entries) = fs::read_dir(itack_dir.join("templates")) else {
+            return Vec::new();
+        };
+
+        let mut names: Vec<String> = entries
+            .filter_map(|entry| {
+                let path = entry.ok()?.path();
+                if path.extension()? != "md" {
+                    return None;
+                }
+                Some(path.file_stem()?.to_string_lossy().into_owned())
+            })
+            .collect();
+        names.sort();
+        names
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_template_with_defaults() {
+        let template = Template::parse(
+            "---\nepic: Bugs\nlabels: [bug, triage]\npriority: high\n---\n## Steps to reproduce\n\n<!-- What did you do? -->\n",
+        )
+        .unwrap();
+        assert_eq!(template.defaults.epic.as_deref(), Some("Bugs"));
+        assert_eq!(template.defaults.labels, vec!["bug", "triage"]);
+        assert_eq!(template.defaults.priority, Some(Priority::High));
+        assert!(template.body.starts_with("## Steps to reproduce"));
+    }
+
+    #[test]
+    fn test_parse_template_without_front_matter() {
+        let template = Template::parse("## Goal\n").unwrap();
+        assert!(template.defaults.epic.is_none());
+        assert_eq!(template.body, "## Goal\n");
+    }
+
+    #[test]
+    fn test_parse_template_rejects_unknown_defaults() {
+        assert!(Template::parse("---\nstatus: done\n---\nBody\n").is_err());
+        assert!(Template::parse("---\npriority: urgent\n---\nBody\n").is_err());
+    }
+}
@@ -11,6 +11,12 @@ pub mod exit_codes {
     pub const ERROR: u8 = 1;
     /// Conflict error (e.g., claim already held).
     pub const CONFLICT: u8 = 2;
+    /// Timed out waiting (e.g., `claim --wait`).
+    pub const TIMEOUT: u8 = 3;
+    /// Claim limit exceeded for the assignee; back off and retry later.
+    pub const LIMIT_EXCEEDED: u8 = 4;
+    /// Issue is blocked by unfinished dependencies.
+    pub const BLOCKED: u8 = 5;
 }
 
 /// Main error type for itack operations.
@@ -28,9 +34,30 @@ pub enum ItackError {
     #[error("Issue {0} is already claimed by {1}")]
     AlreadyClaimed(u32, String),
 
+    #[error(
+        "{assignee} already holds the maximum of {limit} claim(s){}",
+        in_epic(epic)
+    )]
+    ClaimLimitExceeded {
+        assignee: String,
+        limit: u32,
+        epic: Option<String>,
+    },
+
+    #[error(
+        "Issue {id} is blocked by unfinished dependencies: {}",
+        issue_refs(blockers)
+    )]
+    Blocked { id: u32, blockers: Vec<u32> },
+
     #[error("Issue {0} is not claimed")]
     NotClaimed(u32),
 
+    #[error(
+        "Could not determine agent identity. Pass an assignee, set ITACK_AGENT, or set default_assignee in ~/.itack/config.toml."
+    )]
+    NoAgentIdentity,
+
     #[error("Issue {0} is already done")]
     AlreadyDone(u32),
 
@@ -64,9 +91,18 @@ pub enum ItackError {
     #[error("JSON error: {0}")]
     Json(#[from] serde_json::Error),
 
+    #[error("CSV error: {0}")]
+    Csv(#[from] csv::Error),
+
     #[error("Git error: {0}")]
     Git(#[from] git2::Error),
 
+    #[error("Timed out waiting for {0}")]
+    Timeout(String),
+
+    #[error("{0} hook rejected the change ({1})")]
+    HookRejected(String, String),
+
     #[error("Editor failed: {0}")]
     EditorFailed(String),
 
@@ -82,9 +118,45 @@ impl ItackError {
     pub fn exit_code(&self) -> ExitCode {
         match self {
             ItackError::AlreadyClaimed(_, _) => ExitCode::from(exit_codes::CONFLICT),
+            ItackError::Timeout(_) => ExitCode::from(exit_codes::TIMEOUT),
+            ItackError::ClaimLimitExceeded { .. } => ExitCode::from(exit_codes::LIMIT_EXCEEDED),
+            ItackError::Blocked { .. } => ExitCode::from(exit_codes::BLOCKED),
             _ => ExitCode::from(exit_codes::ERROR),
         }
     }
+
+    /// Get the HTTP status code for this error (used by `itack serve`).
+    pub fn http_status(&self) -> u16 {
+        match self {
+            ItackError::IssueNotFound(_) => 404,
+            ItackError::AlreadyClaimed(_, _)
+            | ItackError::Blocked { .. }
+            | ItackError::NotClaimed(_)
+            | ItackError::AlreadyDone(_)
+            | ItackError::AlreadyWontFix(_) => 409,
+            ItackError::ClaimLimitExceeded { .. } => 429,
+            ItackError::HookRejected(_, _) => 403,
+            ItackError::NoAgentIdentity | ItackError::Json(_) | ItackError::InvalidMarkdown(_) => {
+                400
+            }
+            _ => 500,
+        }
+    }
+}
+
+/// Format an optional epic as a " in epic X" suffix for error messages.
+fn in_epic(epic: &Option<String>) -> String {
+    epic.as_ref()
+        .map(|e| format!(" in epic {}", e))
+        .unwrap_or_default()
+}
+
+/// Format issue IDs as "#1, #2" for error messages.
+fn issue_refs(ids: &[u32]) -> String {
+    ids.iter()
+        .map(|id| format!("#{}", id))
+        .collect::<Vec<_>>()
+        .join(", ")
 }
 
 /// Result type alias for itack operations.
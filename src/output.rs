@@ -4,13 +4,14 @@ use comfy_table::{Cell, ContentArrangement, Table, presets::UTF8_FULL_CONDENSED}
 use serde::Serialize;
 
 use crate::commands::board::BoardSummary;
-use crate::core::Issue;
+use crate::core::{Issue, Status};
 use crate::error::Result;
-use crate::storage::db::IssueInfo;
+use crate::storage::db::{AgentRecord, IssueInfo};
 
 /// Output format options.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum OutputFormat {
+    #[default]
     Table,
     Json,
 }
@@ -64,6 +65,12 @@ pub fn print_issues_table(issues: &[IssueInfo]) {
 
 /// Print a list of issues as JSON.
 pub fn print_issues_json(issues: &[IssueInfo]) -> Result<()> {
+    println!("{}", issues_json(issues)?);
+    Ok(())
+}
+
+/// Format a list of issues as JSON.
+pub fn issues_json(issues: &[IssueInfo]) -> Result<String> {
     #[derive(Serialize)]
     struct IssueOutput<'a> {
         id: u32,
@@ -90,8 +97,7 @@ pub fn print_issues_json(issues: &[IssueInfo]) -> Result<()> {
         })
         .collect();
 
-    println!("{}", serde_json::to_string_pretty(&output)?);
-    Ok(())
+    Ok(serde_json::to_string_pretty(&output)?)
 }
 
 /// Print issue detail as a table.
@@ -125,6 +131,21 @@ pub fn print_issue_detail(issue: &Issue, title: &str, body: &str) {
             .join(", ")
     };
     table.add_row(vec![Cell::new("Depends On"), Cell::new(depends_on)]);
+    if !issue.labels.is_empty() {
+        table.add_row(vec![
+            Cell::new("Labels"),
+            Cell::new(issue.labels.join(", ")),
+        ]);
+    }
+    if let Some(priority) = issue.priority {
+        table.add_row(vec![Cell::new("Priority"), Cell::new(priority.to_string())]);
+    }
+    if let Some(imported_from) = &issue.imported_from {
+        table.add_row(vec![Cell::new("Imported From"), Cell::new(imported_from)]);
+    }
+    if let Some(moved_to) = &issue.moved_to {
+        table.add_row(vec![Cell::new("Moved To"), Cell::new(moved_to)]);
+    }
     table.add_row(vec![
         Cell::new("Session"),
         Cell::new(issue.session.as_deref().unwrap_or("-")),
@@ -142,37 +163,222 @@ pub fn print_issue_detail(issue: &Issue, title: &str, body: &str) {
     }
 }
 
+/// Print the commits linked to an issue: short SHA and subject, or a note when
+/// the commit can't be found in this repository.
+pub fn print_linked_commits(commits: &[(String, Option<String>)]) {
+    if commits.is_empty() {
+        return;
+    }
+
+    println!("\nCommits:");
+    for (sha, subject) in commits {
+        println!(
+            "  {} {}",
+            short_sha(sha),
+            subject.as_deref().unwrap_or("(not found)")
+        );
+    }
+}
+
+/// Abbreviate a commit SHA for display.
+pub fn short_sha(sha: &str) -> &str {
+    &sha[..sha.len().min(7)]
+}
+
+/// Issue detail in its JSON representation (as printed by `show --json`).
+#[derive(Serialize)]
+pub struct IssueDetail<'a> {
+    pub id: u32,
+    pub title: &'a str,
+    pub status: String,
+    pub epic: Option<&'a str>,
+    pub assignee: Option<&'a str>,
+    pub depends_on: &'a [u32],
+    #[serde(skip_serializing_if = "<[String]>::is_empty")]
+    pub labels: &'a [String],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub imported_from: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub moved_to: Option<&'a str>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub commits: Vec<LinkedCommit<'a>>,
+    pub session: Option<&'a str>,
+    pub created: String,
+    pub body: &'a str,
+}
+
+/// A commit linked to an issue, in the JSON representation of the issue.
+#[derive(Serialize)]
+pub struct LinkedCommit<'a> {
+    pub sha: &'a str,
+    /// Subject line, when looked up and found in this repository.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subject: Option<String>,
+}
+
+impl<'a> IssueDetail<'a> {
+    /// Build the JSON representation of an issue.
+    pub fn new(issue: &'a Issue, title: &'a str, body: &'a str) -> Self {
+        IssueDetail {
+            id: issue.id,
+            title,
+            status: issue.status.to_string(),
+            epic: issue.epic.as_deref(),
+            assignee: issue.assignee.as_deref(),
+            depends_on: &issue.depends_on,
+            labels: &issue.labels,
+            priority: issue.priority.map(|p| p.to_string()),
+            imported_from: issue.imported_from.as_deref(),
+            moved_to: issue.moved_to.as_deref(),
+            commits: issue
+                .commits
+                .iter()
+                .map(|sha| LinkedCommit { sha, subject: None })
+                .collect(),
+            session: issue.session.as_deref(),
+            created: issue.created.to_rfc3339(),
+            body,
+        }
+    }
+}
+
+/// Print issue detail as JSON, with the subjects of its linked commits.
+pub fn print_issue_json_with_commits(
+    issue: &Issue,
+    title: &str,
+    body: &str,
+    commits: &[(String, Option<String>)],
+) -> Result<()> {
+    let mut detail = IssueDetail::new(issue, title, body);
+    detail.commits = commits
+        .iter()
+        .map(|(sha, subject)| LinkedCommit {
+            sha,
+            subject: subject.clone(),
+        })
+        .collect();
+    println!("{}", serde_json::to_string_pretty(&detail)?);
+    Ok(())
+}
+
 /// Print issue detail as JSON.
 pub fn print_issue_json(issue: &Issue, title: &str, body: &str) -> Result<()> {
+    println!("{}", issue_json(issue, title, body)?);
+    Ok(())
+}
+
+/// Format issue detail as JSON.
+pub fn issue_json(issue: &Issue, title: &str, body: &str) -> Result<String> {
+    Ok(serde_json::to_string_pretty(&IssueDetail::new(
+        issue, title, body,
+    ))?)
+}
+
+/// Print the issues that became ready after a status change.
+pub fn print_unblocked(unblocked: &[IssueInfo]) {
+    if unblocked.is_empty() {
+        return;
+    }
+
+    println!("Unblocked issues:");
+    for info in unblocked {
+        println!("  #{}: {}", info.issue.id, info.title);
+    }
+}
+
+/// Print the result of a batch as JSON: the ID each `$N` placeholder got,
+/// and the issues the batch changed.
+pub fn print_batch_json(created: &[IssueInfo], changed: &[IssueInfo]) -> Result<()> {
+    #[derive(Serialize)]
+    struct BatchOutput<'a> {
+        created: serde_json::Map<String, serde_json::Value>,
+        changed: Vec<ChangedIssue<'a>>,
+    }
+
     #[derive(Serialize)]
-    struct IssueDetail<'a> {
+    struct ChangedIssue<'a> {
         id: u32,
         title: &'a str,
         status: String,
-        epic: Option<&'a str>,
         assignee: Option<&'a str>,
-        depends_on: &'a [u32],
-        session: Option<&'a str>,
-        created: String,
-        body: &'a str,
     }
 
-    let output = IssueDetail {
-        id: issue.id,
-        title,
-        status: issue.status.to_string(),
-        epic: issue.epic.as_deref(),
-        assignee: issue.assignee.as_deref(),
-        depends_on: &issue.depends_on,
-        session: issue.session.as_deref(),
-        created: issue.created.to_rfc3339(),
-        body,
+    let output = BatchOutput {
+        created: created
+            .iter()
+            .enumerate()
+            .map(|(i, info)| (format!("${}", i + 1), info.issue.id.into()))
+            .collect(),
+        changed: changed
+            .iter()
+            .map(|info| ChangedIssue {
+                id: info.issue.id,
+                title: &info.title,
+                status: info.issue.status.to_string(),
+                assignee: info.issue.assignee.as_deref(),
+            })
+            .collect(),
     };
 
     println!("{}", serde_json::to_string_pretty(&output)?);
     Ok(())
 }
 
+/// Print a status change and the issues it unblocked as JSON.
+pub fn print_status_change_json(
+    id: u32,
+    old_status: Status,
+    new_status: Status,
+    unblocked: &[IssueInfo],
+) -> Result<()> {
+    println!(
+        "{}",
+        status_change_json(id, old_status, new_status, unblocked)?
+    );
+    Ok(())
+}
+
+/// Format a status change and the issues it unblocked as JSON.
+pub fn status_change_json(
+    id: u32,
+    old_status: Status,
+    new_status: Status,
+    unblocked: &[IssueInfo],
+) -> Result<String> {
+    #[derive(Serialize)]
+    struct StatusChange<'a> {
+        id: u32,
+        old_status: String,
+        status: String,
+        unblocked: Vec<UnblockedIssue<'a>>,
+    }
+
+    #[derive(Serialize)]
+    struct UnblockedIssue<'a> {
+        id: u32,
+        title: &'a str,
+        epic: Option<&'a str>,
+    }
+
+    let output = StatusChange {
+        id,
+        old_status: old_status.to_string(),
+        status: new_status.to_string(),
+        unblocked: unblocked
+            .iter()
+            .map(|info| UnblockedIssue {
+                id: info.issue.id,
+                title: &info.title,
+                epic: info.issue.epic.as_deref(),
+            })
+            .collect(),
+    };
+
+    Ok(serde_json::to_string_pretty(&output)?)
+}
+
 /// Print board overview as a table.
 pub fn print_board(summary: &BoardSummary, issues: &[IssueInfo]) {
     println!("Project: {}", summary.project_id);
@@ -204,6 +410,12 @@ pub fn print_board(summary: &BoardSummary, issues: &[IssueInfo]) {
 
 /// Print board overview as JSON.
 pub fn print_board_json(summary: &BoardSummary, issues: &[IssueInfo]) -> Result<()> {
+    println!("{}", board_json(summary, issues)?);
+    Ok(())
+}
+
+/// Format board overview as JSON.
+pub fn board_json(summary: &BoardSummary, issues: &[IssueInfo]) -> Result<String> {
     #[derive(Serialize)]
     struct BoardOutput<'a> {
         project_id: &'a str,
@@ -252,6 +464,79 @@ pub fn print_board_json(summary: &BoardSummary, issues: &[IssueInfo]) -> Result<
             .collect(),
     };
 
+    Ok(serde_json::to_string_pretty(&output)?)
+}
+
+/// Format a list of issue IDs as "#1, #2" (or "-" when empty).
+pub fn format_issue_refs(ids: &[u32]) -> String {
+    if ids.is_empty() {
+        "-".to_string()
+    } else {
+        ids.iter()
+            .map(|id| format!("#{}", id))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// Print a list of agents as a table.
+pub fn print_agents_table(agents: &[AgentRecord]) {
+    if agents.is_empty() {
+        println!("No agents found.");
+        return;
+    }
+
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL_CONDENSED);
+    table.set_content_arrangement(ContentArrangement::Dynamic);
+
+    table.set_header(vec![
+        "Agent",
+        "State",
+        "Claims",
+        "Session",
+        "Branch",
+        "Last Seen",
+    ]);
+
+    for agent in agents {
+        table.add_row(vec![
+            Cell::new(&agent.name),
+            Cell::new(if agent.is_active() { "active" } else { "idle" }),
+            Cell::new(format_issue_refs(&agent.claims)),
+            Cell::new(agent.session.as_deref().unwrap_or("-")),
+            Cell::new(agent.branch.as_deref().unwrap_or("-")),
+            Cell::new(agent.last_seen.format("%Y-%m-%d %H:%M:%S UTC").to_string()),
+        ]);
+    }
+
+    println!("{}", table);
+}
+
+/// Print a list of agents as JSON.
+pub fn print_agents_json(agents: &[AgentRecord]) -> Result<()> {
+    #[derive(Serialize)]
+    struct AgentOutput<'a> {
+        name: &'a str,
+        active: bool,
+        claims: &'a [u32],
+        session: Option<&'a str>,
+        branch: Option<&'a str>,
+        last_seen: String,
+    }
+
+    let output: Vec<AgentOutput> = agents
+        .iter()
+        .map(|agent| AgentOutput {
+            name: &agent.name,
+            active: agent.is_active(),
+            claims: &agent.claims,
+            session: agent.session.as_deref(),
+            branch: agent.branch.as_deref(),
+            last_seen: agent.last_seen.to_rfc3339(),
+        })
+        .collect();
+
     println!("{}", serde_json::to_string_pretty(&output)?);
     Ok(())
 }
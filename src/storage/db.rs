@@ -2,6 +2,7 @@
 
 use chrono::{DateTime, Utc};
 use rusqlite::{Connection, OptionalExtension, TransactionBehavior, params};
+use std::collections::BTreeMap;
 use std::path::Path;
 
 use crate::core::Issue;
@@ -9,13 +10,45 @@ use crate::error::{ItackError, Result};
 use crate::storage::markdown;
 
 /// Current schema version.
-const SCHEMA_VERSION: i32 = 1;
+const SCHEMA_VERSION: i32 = 4;
+
+/// Rules applied when claiming issues.
+#[derive(Debug, Clone, Default)]
+pub struct ClaimPolicy {
+    /// Claims older than this are considered expired and may be taken over.
+    pub ttl: Option<std::time::Duration>,
+    /// Maximum number of issues one assignee may hold at once.
+    pub max_claims: Option<u32>,
+    /// Maximum number of issues one assignee may hold at once within an epic.
+    pub epic_limits: BTreeMap<String, u32>,
+}
+
+impl ClaimPolicy {
+    /// Check whether a claim made at `claimed_at` (RFC 3339) has expired.
+    fn is_expired(&self, claimed_at: &str) -> bool {
+        let Some(ttl) = self.ttl else {
+            return false;
+        };
+        let Ok(claimed_at) = DateTime::parse_from_rfc3339(claimed_at) else {
+            return false;
+        };
+        let Ok(ttl) = chrono::Duration::from_std(ttl) else {
+            return false;
+        };
+        // A TTL too long to represent never expires
+        claimed_at
+            .with_timezone(&Utc)
+            .checked_add_signed(ttl)
+            .is_some_and(|expires| expires <= Utc::now())
+    }
+}
 
 /// SQLite database handle for itack.
 pub struct Database {
     conn: Connection,
     repo_root: Option<std::path::PathBuf>,
     data_branch: Option<String>,
+    claim_policy: ClaimPolicy,
 }
 
 impl Database {
@@ -44,6 +77,7 @@ impl Database {
             conn,
             repo_root: repo_root.map(|p| p.to_path_buf()),
             data_branch: data_branch.map(|s| s.to_string()),
+            claim_policy: ClaimPolicy::default(),
         };
 
         db.ensure_schema()?;
@@ -70,12 +104,18 @@ impl Database {
             conn,
             repo_root: repo_root.map(|p| p.to_path_buf()),
             data_branch: data_branch.map(|s| s.to_string()),
+            claim_policy: ClaimPolicy::default(),
         };
 
         db.ensure_schema()?;
         Ok(db)
     }
 
+    /// Set the rules applied by `claim`.
+    pub fn set_claim_policy(&mut self, policy: ClaimPolicy) {
+        self.claim_policy = policy;
+    }
+
     /// Ensure the schema is up to date, rebuilding if necessary.
     fn ensure_schema(&mut self) -> Result<()> {
         // Check if schema_version table exists
@@ -109,7 +149,13 @@ impl Database {
     /// Returns an empty vec if repo_root/data_branch are not set or the branch doesn't exist.
     fn load_data_branch_issues(&self) -> Vec<IssueInfo> {
         if let (Some(repo_root), Some(data_branch)) = (&self.repo_root, &self.data_branch) {
-            load_all_issues_from_data_branch(repo_root, data_branch).unwrap_or_default()
+            // Archived issues still hold their IDs and import mappings
+            let mut issues =
+                load_all_issues_from_data_branch(repo_root, data_branch).unwrap_or_default();
+            issues.extend(
+                load_archived_issues_from_data_branch(repo_root, data_branch).unwrap_or_default(),
+            );
+            issues
         } else {
             Vec::new()
         }
@@ -140,6 +186,8 @@ impl Database {
         tx.execute_batch(
             r#"
             DROP TABLE IF EXISTS claims;
+            DROP TABLE IF EXISTS agents;
+            DROP TABLE IF EXISTS imports;
             DROP TABLE IF EXISTS state;
             DROP TABLE IF EXISTS schema_version;
 
@@ -155,33 +203,32 @@ impl Database {
             CREATE TABLE claims (
                 issue_id INTEGER PRIMARY KEY,
                 assignee TEXT NOT NULL,
-                claimed_at TEXT NOT NULL
+                claimed_at TEXT NOT NULL,
+                epic TEXT
             );
 
-            INSERT INTO schema_version (version) VALUES (1);
+            CREATE TABLE agents (
+                name TEXT PRIMARY KEY,
+                last_seen TEXT NOT NULL,
+                session TEXT,
+                branch TEXT
+            );
+
+            CREATE TABLE imports (
+                source TEXT NOT NULL,
+                external_id TEXT NOT NULL,
+                issue_id INTEGER NOT NULL,
+                PRIMARY KEY (source, external_id)
+            );
             "#,
         )?;
-
-        // Scan data branch for max ID and claims
-        let mut max_id: u32 = 0;
-
-        for info in &data_branch_issues {
-            max_id = max_id.max(info.issue.id);
-
-            if let Some(assignee) = &info.issue.assignee {
-                tx.execute(
-                    "INSERT OR REPLACE INTO claims (issue_id, assignee, claimed_at) VALUES (?1, ?2, ?3)",
-                    params![info.issue.id, assignee, info.issue.created.to_rfc3339()],
-                )?;
-            }
-        }
-
-        // Set next_issue_id to max + 1
         tx.execute(
-            "INSERT INTO state (id, next_issue_id) VALUES (1, ?1)",
-            params![max_id + 1],
+            "INSERT INTO schema_version (version) VALUES (?1)",
+            params![SCHEMA_VERSION],
         )?;
 
+        seed_state(&tx, &data_branch_issues)?;
+
         tx.commit()?;
         Ok(())
     }
@@ -198,27 +245,10 @@ impl Database {
 
         // Clear and rebuild state tables
         tx.execute("DELETE FROM claims", [])?;
+        tx.execute("DELETE FROM imports", [])?;
         tx.execute("DELETE FROM state", [])?;
 
-        // Scan data branch for max ID and claims
-        let mut max_id: u32 = 0;
-
-        for info in &data_branch_issues {
-            max_id = max_id.max(info.issue.id);
-
-            if let Some(assignee) = &info.issue.assignee {
-                tx.execute(
-                    "INSERT OR REPLACE INTO claims (issue_id, assignee, claimed_at) VALUES (?1, ?2, ?3)",
-                    params![info.issue.id, assignee, info.issue.created.to_rfc3339()],
-                )?;
-            }
-        }
-
-        // Set next_issue_id to max + 1
-        tx.execute(
-            "INSERT INTO state (id, next_issue_id) VALUES (1, ?1)",
-            params![max_id + 1],
-        )?;
+        seed_state(&tx, &data_branch_issues)?;
 
         tx.commit()?;
         Ok(())
@@ -234,6 +264,36 @@ impl Database {
         Ok(id)
     }
 
+    /// Make sure IDs up to and including `max_id` are never handed out again.
+    pub fn reserve_issue_ids(&self, max_id: u32) -> Result<()> {
+        self.conn.execute(
+            "UPDATE state SET next_issue_id = MAX(next_issue_id, ?1) WHERE id = 1",
+            params![max_id + 1],
+        )?;
+        Ok(())
+    }
+
+    /// Atomically reserve `count` consecutive issue IDs, returning the first.
+    pub fn allocate_issue_ids(&self, count: u32) -> Result<u32> {
+        let id: u32 = self.conn.query_row(
+            "UPDATE state SET next_issue_id = next_issue_id + ?1 WHERE id = 1 RETURNING next_issue_id - ?1",
+            params![count],
+            |row| row.get(0),
+        )?;
+        Ok(id)
+    }
+
+    /// Give back IDs reserved by [`Database::allocate_issue_ids`] that ended up
+    /// unused. Only takes effect if no IDs were allocated since, so concurrent
+    /// allocations are never handed out twice.
+    pub fn unallocate_issue_ids(&self, first: u32, count: u32) -> Result<()> {
+        self.conn.execute(
+            "UPDATE state SET next_issue_id = ?1 WHERE id = 1 AND next_issue_id = ?1 + ?2",
+            params![first, count],
+        )?;
+        Ok(())
+    }
+
     /// Get the current next_issue_id without incrementing.
     pub fn peek_next_issue_id(&self) -> Result<u32> {
         let id: u32 =
@@ -245,30 +305,68 @@ impl Database {
     }
 
     /// Attempt to claim an issue. Returns error if already claimed.
-    pub fn claim(&mut self, issue_id: u32, assignee: &str) -> Result<()> {
+    /// An existing claim older than the policy's TTL is taken over, and the
+    /// policy's per-assignee limits are enforced within the same transaction.
+    pub fn claim(&mut self, issue_id: u32, assignee: &str, epic: Option<&str>) -> Result<()> {
         // Use IMMEDIATE transaction for write intent
         let tx = self
             .conn
             .transaction_with_behavior(TransactionBehavior::Immediate)?;
 
         // Check if already claimed
-        let existing: Option<String> = tx
+        let existing: Option<(String, String)> = tx
             .query_row(
-                "SELECT assignee FROM claims WHERE issue_id = ?1",
+                "SELECT assignee, claimed_at FROM claims WHERE issue_id = ?1",
                 params![issue_id],
-                |row| row.get(0),
+                |row| Ok((row.get(0)?, row.get(1)?)),
             )
             .optional()?;
 
-        if let Some(existing_assignee) = existing {
-            return Err(ItackError::AlreadyClaimed(issue_id, existing_assignee));
+        if let Some((existing_assignee, claimed_at)) = existing {
+            if !self.claim_policy.is_expired(&claimed_at) {
+                return Err(ItackError::AlreadyClaimed(issue_id, existing_assignee));
+            }
+            tx.execute("DELETE FROM claims WHERE issue_id = ?1", params![issue_id])?;
+        }
+
+        // Enforce concurrency limits
+        if let Some(limit) = self.claim_policy.max_claims {
+            let held: u32 = tx.query_row(
+                "SELECT COUNT(*) FROM claims WHERE assignee = ?1",
+                params![assignee],
+                |row| row.get(0),
+            )?;
+            if held >= limit {
+                return Err(ItackError::ClaimLimitExceeded {
+                    assignee: assignee.to_string(),
+                    limit,
+                    epic: None,
+                });
+            }
+        }
+
+        if let Some(epic) = epic
+            && let Some(&limit) = self.claim_policy.epic_limits.get(epic)
+        {
+            let held: u32 = tx.query_row(
+                "SELECT COUNT(*) FROM claims WHERE assignee = ?1 AND epic = ?2",
+                params![assignee, epic],
+                |row| row.get(0),
+            )?;
+            if held >= limit {
+                return Err(ItackError::ClaimLimitExceeded {
+                    assignee: assignee.to_string(),
+                    limit,
+                    epic: Some(epic.to_string()),
+                });
+            }
         }
 
         // Insert claim
         let now = Utc::now().to_rfc3339();
         tx.execute(
-            "INSERT INTO claims (issue_id, assignee, claimed_at) VALUES (?1, ?2, ?3)",
-            params![issue_id, assignee, now],
+            "INSERT INTO claims (issue_id, assignee, claimed_at, epic) VALUES (?1, ?2, ?3, ?4)",
+            params![issue_id, assignee, now, epic],
         )?;
 
         tx.commit()?;
@@ -288,6 +386,104 @@ impl Database {
         Ok(())
     }
 
+    /// Record an existing assignment as a claim, bypassing the claim policy.
+    /// Used when importing issues that were already assigned elsewhere.
+    pub fn record_claim(&self, issue_id: u32, assignee: &str, epic: Option<&str>) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO claims (issue_id, assignee, claimed_at, epic) VALUES (?1, ?2, ?3, ?4)",
+            params![issue_id, assignee, Utc::now().to_rfc3339(), epic],
+        )?;
+        Ok(())
+    }
+
+    /// Look up the issue an external issue was imported as.
+    pub fn find_import(&self, source: &str, external_id: &str) -> Result<Option<u32>> {
+        let issue_id = self
+            .conn
+            .query_row(
+                "SELECT issue_id FROM imports WHERE source = ?1 AND external_id = ?2",
+                params![source, external_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(issue_id)
+    }
+
+    /// Remember that an external issue was imported as `issue_id`.
+    pub fn record_import(&self, source: &str, external_id: &str, issue_id: u32) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO imports (source, external_id, issue_id) VALUES (?1, ?2, ?3)",
+            params![source, external_id, issue_id],
+        )?;
+        Ok(())
+    }
+
+    /// Forget the external issues imported as `issue_id`, so importing them
+    /// again creates a new issue.
+    pub fn forget_imports(&self, issue_id: u32) -> Result<()> {
+        self.conn
+            .execute("DELETE FROM imports WHERE issue_id = ?1", params![issue_id])?;
+        Ok(())
+    }
+
+    /// Record that an agent was seen, along with its current session and branch.
+    pub fn touch_agent(
+        &self,
+        name: &str,
+        session: Option<&str>,
+        branch: Option<&str>,
+    ) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO agents (name, last_seen, session, branch) VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(name) DO UPDATE SET
+                 last_seen = excluded.last_seen,
+                 session = COALESCE(excluded.session, agents.session),
+                 branch = COALESCE(excluded.branch, agents.branch)",
+            params![name, Utc::now().to_rfc3339(), session, branch],
+        )?;
+        Ok(())
+    }
+
+    /// Get all known agents with their current claims, most recently seen first.
+    pub fn list_agents(&self) -> Result<Vec<AgentRecord>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT name, last_seen, session, branch FROM agents ORDER BY last_seen DESC",
+        )?;
+
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, Option<String>>(2)?,
+                row.get::<_, Option<String>>(3)?,
+            ))
+        })?;
+
+        let claims = self.list_claims()?;
+
+        let mut agents = Vec::new();
+        for row in rows {
+            let (name, last_seen_str, session, branch) = row?;
+            let last_seen = DateTime::parse_from_rfc3339(&last_seen_str)
+                .map_err(|e| ItackError::Other(format!("Invalid last_seen timestamp: {}", e)))?;
+            let mut claimed: Vec<u32> = claims
+                .iter()
+                .filter(|(_, assignee, _)| *assignee == name)
+                .map(|(id, _, _)| *id)
+                .collect();
+            claimed.sort();
+            agents.push(AgentRecord {
+                name,
+                last_seen: last_seen.with_timezone(&Utc),
+                session,
+                branch,
+                claims: claimed,
+            });
+        }
+
+        Ok(agents)
+    }
+
     /// Check if an issue is claimed and by whom.
     #[allow(dead_code)]
     pub fn get_claim(&self, issue_id: u32) -> Result<Option<(String, DateTime<Utc>)>> {
@@ -345,6 +541,24 @@ impl Database {
     }
 }
 
+/// An agent known to this project.
+#[derive(Debug, Clone)]
+pub struct AgentRecord {
+    pub name: String,
+    pub last_seen: DateTime<Utc>,
+    pub session: Option<String>,
+    pub branch: Option<String>,
+    /// IDs of the issues currently claimed by this agent.
+    pub claims: Vec<u32>,
+}
+
+impl AgentRecord {
+    /// Whether the agent currently holds any claims.
+    pub fn is_active(&self) -> bool {
+        !self.claims.is_empty()
+    }
+}
+
 /// Information about a loaded issue.
 #[derive(Clone)]
 pub struct IssueInfo {
@@ -355,16 +569,68 @@ pub struct IssueInfo {
     pub relative_path: std::path::PathBuf,
 }
 
+/// Seed claims, agents, imports and next_issue_id from the data branch issues.
+fn seed_state(tx: &rusqlite::Transaction, issues: &[IssueInfo]) -> Result<()> {
+    let mut max_id: u32 = 0;
+
+    for info in issues {
+        max_id = max_id.max(info.issue.id);
+
+        if let Some(assignee) = &info.issue.assignee {
+            // Closed issues keep their assignee as a record, but not a claim.
+            // The claim time isn't stored on the data branch, so restart the
+            // TTL from the rebuild rather than expiring old issues' claims.
+            if !info.issue.status.is_closed() {
+                tx.execute(
+                    "INSERT OR REPLACE INTO claims (issue_id, assignee, claimed_at, epic) VALUES (?1, ?2, ?3, ?4)",
+                    params![
+                        info.issue.id,
+                        assignee,
+                        Utc::now().to_rfc3339(),
+                        info.issue.epic
+                    ],
+                )?;
+            }
+            tx.execute(
+                "INSERT OR IGNORE INTO agents (name, last_seen, session, branch) VALUES (?1, ?2, ?3, ?4)",
+                params![
+                    assignee,
+                    info.issue.created.to_rfc3339(),
+                    info.issue.session,
+                    info.issue.branch
+                ],
+            )?;
+        }
+
+        if let Some((source, external_id)) = info
+            .issue
+            .imported_from
+            .as_deref()
+            .and_then(|from| from.split_once(':'))
+        {
+            tx.execute(
+                "INSERT OR REPLACE INTO imports (source, external_id, issue_id) VALUES (?1, ?2, ?3)",
+                params![source, external_id, info.issue.id],
+            )?;
+        }
+    }
+
+    // Set next_issue_id to max + 1
+    tx.execute(
+        "INSERT INTO state (id, next_issue_id) VALUES (1, ?1)",
+        params![max_id + 1],
+    )?;
+
+    Ok(())
+}
+
 /// Load all issues from the data branch.
 pub fn load_all_issues_from_data_branch(
     repo_root: &Path,
     data_branch: &str,
 ) -> Result<Vec<IssueInfo>> {
-    use crate::core::read_file_from_branch;
     use git2::Repository;
 
-    let mut issues = Vec::new();
-
     let repo = Repository::discover(repo_root).map_err(|_| ItackError::NotInGitRepo)?;
 
     // Find the branch
@@ -383,40 +649,121 @@ pub fn load_all_issues_from_data_branch(
 
     let itack_tree = repo.find_tree(itack_entry.id())?;
 
+    let mut issues = load_issues_from_tree(&repo, &itack_tree, Path::new(".itack"), true);
+    sort_issues(&mut issues);
+
+    Ok(issues)
+}
+
+/// Directory on the data branch that `itack archive` moves closed issues into.
+pub const ARCHIVE_DIR: &str = ".itack/archive";
+
+/// Load the archived issues from the data branch.
+/// Returns an empty list if nothing has been archived.
+pub fn load_archived_issues_from_data_branch(
+    repo_root: &Path,
+    data_branch: &str,
+) -> Result<Vec<IssueInfo>> {
+    use git2::Repository;
+
+    let repo = Repository::discover(repo_root).map_err(|_| ItackError::NotInGitRepo)?;
+
+    let branch_ref = format!("refs/heads/{}", data_branch);
+    let reference = repo
+        .find_reference(&branch_ref)
+        .map_err(|_| ItackError::DataBranchNotFound(data_branch.to_string()))?;
+    let tree = reference.peel_to_commit()?.tree()?;
+
+    let archive_tree = match tree.get_path(Path::new(ARCHIVE_DIR)) {
+        Ok(entry) => repo.find_tree(entry.id())?,
+        Err(e) if e.code() == git2::ErrorCode::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut issues = load_issues_from_tree(&repo, &archive_tree, Path::new(ARCHIVE_DIR), true);
+    sort_issues(&mut issues);
+
+    Ok(issues)
+}
+
+/// Load all issues as they were at a specific commit of the data branch,
+/// archived ones included, so archiving an issue doesn't look like deleting it.
+/// Returns an empty list if the commit has no `.itack` directory.
+/// Files that fail to parse are skipped without warnings, since history is read-only.
+pub fn load_all_issues_at_commit(repo_root: &Path, commit_id: git2::Oid) -> Result<Vec<IssueInfo>> {
+    use git2::Repository;
+
+    let repo = Repository::discover(repo_root).map_err(|_| ItackError::NotInGitRepo)?;
+    let tree = repo.find_commit(commit_id)?.tree()?;
+
+    let Some(itack_entry) = tree.get_name(".itack") else {
+        return Ok(Vec::new());
+    };
+    let itack_tree = repo.find_tree(itack_entry.id())?;
+
+    let mut issues = load_issues_from_tree(&repo, &itack_tree, Path::new(".itack"), false);
+    if let Ok(entry) = tree.get_path(Path::new(ARCHIVE_DIR)) {
+        let archive_tree = repo.find_tree(entry.id())?;
+        issues.extend(load_issues_from_tree(
+            &repo,
+            &archive_tree,
+            Path::new(ARCHIVE_DIR),
+            false,
+        ));
+    }
+    sort_issues(&mut issues);
+
+    Ok(issues)
+}
+
+/// Parse every `.md` issue file directly inside `tree`, which is `dir` on the branch.
+fn load_issues_from_tree(
+    repo: &git2::Repository,
+    itack_tree: &git2::Tree,
+    dir: &Path,
+    warn: bool,
+) -> Vec<IssueInfo> {
+    let mut issues = Vec::new();
+
     // Iterate over all .md files in .itack/
     for entry in itack_tree.iter() {
         if let Some(name) = entry.name()
             && name.ends_with(".md")
             && !name.starts_with('.')
         {
-            let relative_path = std::path::PathBuf::from(".itack").join(name);
-            match read_file_from_branch(repo_root, data_branch, &relative_path)? {
-                Some(content) => match String::from_utf8(content) {
-                    Ok(content_str) => match markdown::parse_issue(&content_str) {
-                        Ok((issue, title, body)) => {
-                            issues.push(IssueInfo {
-                                issue,
-                                title,
-                                body,
-                                relative_path: relative_path.clone(),
-                            });
-                        }
-                        Err(e) => {
-                            eprintln!("Warning: failed to parse {}: {}", name, e);
-                        }
-                    },
-                    Err(e) => {
-                        eprintln!("Warning: invalid UTF-8 in {}: {}", name, e);
-                    }
+            let relative_path = dir.join(name);
+            let parsed = match repo.find_blob(entry.id()) {
+                Ok(blob) => match std::str::from_utf8(blob.content()) {
+                    Ok(content_str) => markdown::parse_issue(content_str)
+                        .map_err(|e| format!("failed to parse {}: {}", name, e)),
+                    Err(e) => Err(format!("invalid UTF-8 in {}: {}", name, e)),
                 },
-                None => {
-                    eprintln!("Warning: could not read {} from data branch", name);
+                Err(_) => Err(format!("could not read {} from data branch", name)),
+            };
+
+            match parsed {
+                Ok((issue, title, body)) => {
+                    issues.push(IssueInfo {
+                        issue,
+                        title,
+                        body,
+                        relative_path,
+                    });
+                }
+                Err(message) => {
+                    if warn {
+                        eprintln!("Warning: {}", message);
+                    }
                 }
             }
         }
     }
 
-    // Sort by status priority, then by ID
+    issues
+}
+
+/// Sort by status priority, then by ID.
+pub fn sort_issues(issues: &mut [IssueInfo]) {
     issues.sort_by(|a, b| {
         let status_cmp = a
             .issue
@@ -429,8 +776,6 @@ pub fn load_all_issues_from_data_branch(
             status_cmp
         }
     });
-
-    Ok(issues)
 }
 
 /// Load a single issue by ID from the data branch.
@@ -490,14 +835,14 @@ mod tests {
         let (_dir, mut db) = setup_test_db();
 
         // Claim should succeed
-        db.claim(1, "agent-1").unwrap();
+        db.claim(1, "agent-1", None).unwrap();
         assert_eq!(
             db.get_claim(1).unwrap().map(|(a, _)| a),
             Some("agent-1".to_string())
         );
 
         // Second claim should fail
-        let err = db.claim(1, "agent-2").unwrap_err();
+        let err = db.claim(1, "agent-2", None).unwrap_err();
         assert!(matches!(err, ItackError::AlreadyClaimed(1, _)));
 
         // Release should succeed
@@ -505,13 +850,145 @@ mod tests {
         assert!(db.get_claim(1).unwrap().is_none());
 
         // Now agent-2 can claim
-        db.claim(1, "agent-2").unwrap();
+        db.claim(1, "agent-2", None).unwrap();
+        assert_eq!(
+            db.get_claim(1).unwrap().map(|(a, _)| a),
+            Some("agent-2".to_string())
+        );
+    }
+
+    #[test]
+    fn test_expired_claim_is_taken_over() {
+        let (_dir, mut db) = setup_test_db();
+
+        db.claim(1, "agent-1", None).unwrap();
+        assert!(matches!(
+            db.claim(1, "agent-2", None).unwrap_err(),
+            ItackError::AlreadyClaimed(1, _)
+        ));
+
+        db.set_claim_policy(ClaimPolicy {
+            ttl: Some(std::time::Duration::ZERO),
+            ..Default::default()
+        });
+        db.claim(1, "agent-2", None).unwrap();
         assert_eq!(
             db.get_claim(1).unwrap().map(|(a, _)| a),
             Some("agent-2".to_string())
         );
     }
 
+    #[test]
+    fn test_claim_limits() {
+        let (_dir, mut db) = setup_test_db();
+        db.set_claim_policy(ClaimPolicy {
+            max_claims: Some(2),
+            epic_limits: BTreeMap::from([("MVP".to_string(), 1)]),
+            ..Default::default()
+        });
+
+        db.claim(1, "agent-1", Some("MVP")).unwrap();
+
+        // Epic limit reached
+        let err = db.claim(2, "agent-1", Some("MVP")).unwrap_err();
+        assert!(matches!(
+            err,
+            ItackError::ClaimLimitExceeded {
+                limit: 1,
+                epic: Some(_),
+                ..
+            }
+        ));
+
+        // Other agents and other epics are unaffected
+        db.claim(2, "agent-2", Some("MVP")).unwrap();
+        db.claim(3, "agent-1", None).unwrap();
+
+        // Overall limit reached
+        let err = db.claim(4, "agent-1", None).unwrap_err();
+        assert!(matches!(
+            err,
+            ItackError::ClaimLimitExceeded {
+                limit: 2,
+                epic: None,
+                ..
+            }
+        ));
+
+        // Releasing frees up a slot
+        db.release(3).unwrap();
+        db.claim(4, "agent-1", None).unwrap();
+    }
+
+    #[test]
+    fn test_agents() {
+        let (_dir, mut db) = setup_test_db();
+
+        db.touch_agent("agent-1", Some("s1"), Some("issue-1"))
+            .unwrap();
+        db.touch_agent("agent-2", None, None).unwrap();
+        db.claim(1, "agent-1", None).unwrap();
+
+        // Touching without a session keeps the previous one
+        db.touch_agent("agent-1", None, None).unwrap();
+
+        let agents = db.list_agents().unwrap();
+        assert_eq!(agents.len(), 2);
+
+        let agent_1 = agents.iter().find(|a| a.name == "agent-1").unwrap();
+        assert!(agent_1.is_active());
+        assert_eq!(agent_1.claims, vec![1]);
+        assert_eq!(agent_1.session.as_deref(), Some("s1"));
+        assert_eq!(agent_1.branch.as_deref(), Some("issue-1"));
+
+        let agent_2 = agents.iter().find(|a| a.name == "agent-2").unwrap();
+        assert!(!agent_2.is_active());
+    }
+
+    #[test]
+    fn test_allocate_issue_ids() {
+        let (_dir, db) = setup_test_db();
+
+        assert_eq!(db.allocate_issue_ids(3).unwrap(), 1);
+        assert_eq!(db.next_issue_id().unwrap(), 4);
+    }
+
+    #[test]
+    fn test_unallocate_issue_ids() {
+        let (_dir, db) = setup_test_db();
+
+        let first = db.allocate_issue_ids(3).unwrap();
+        db.unallocate_issue_ids(first, 3).unwrap();
+        assert_eq!(db.peek_next_issue_id().unwrap(), 1);
+
+        // IDs allocated in between are never handed out again
+        let first = db.allocate_issue_ids(2).unwrap();
+        assert_eq!(db.next_issue_id().unwrap(), 3);
+        db.unallocate_issue_ids(first, 2).unwrap();
+        assert_eq!(db.peek_next_issue_id().unwrap(), 4);
+    }
+
+    #[test]
+    fn test_reserve_issue_ids() {
+        let (_dir, db) = setup_test_db();
+
+        db.reserve_issue_ids(5).unwrap();
+        assert_eq!(db.next_issue_id().unwrap(), 6);
+        // Never moves backwards
+        db.reserve_issue_ids(2).unwrap();
+        assert_eq!(db.next_issue_id().unwrap(), 7);
+    }
+
+    #[test]
+    fn test_imports() {
+        let (_dir, db) = setup_test_db();
+
+        assert_eq!(db.find_import("github", "owner/repo#12").unwrap(), None);
+        db.record_import("github", "owner/repo#12", 3).unwrap();
+        assert_eq!(db.find_import("github", "owner/repo#12").unwrap(), Some(3));
+        assert_eq!(db.find_import("github", "owner/repo#13").unwrap(), None);
+    }
+
     #[test]
     fn test_release_unclaimed() {
         let (_dir, mut db) = setup_test_db();
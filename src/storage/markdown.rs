@@ -1,13 +1,28 @@
 //! Markdown file I/O with YAML front matter.
 
+use chrono::{DateTime, Utc};
+
 use crate::core::Issue;
 use crate::error::{ItackError, Result};
 
 const FRONT_MATTER_DELIMITER: &str = "---";
+const COMMENTS_HEADING: &str = "## Comments";
 
 /// Parse an issue from a markdown file with YAML front matter.
 /// Returns the issue, title (from H1 heading), and body (without title heading).
 pub fn parse_issue(content: &str) -> Result<(Issue, String, String)> {
+    let (yaml_content, body) = split_front_matter(content)?;
+
+    let issue: Issue = serde_yaml::from_str(yaml_content)?;
+
+    // Extract the title from the H1 heading
+    let (title, body) = extract_title_heading(body)?;
+
+    Ok((issue, title, body))
+}
+
+/// Split markdown into its YAML front matter and the body after it.
+pub fn split_front_matter(content: &str) -> Result<(&str, &str)> {
     let content = content.trim_start();
 
     if !content.starts_with(FRONT_MATTER_DELIMITER) {
@@ -25,14 +40,7 @@ pub fn parse_issue(content: &str) -> Result<(Issue, String, String)> {
 
     let yaml_content = &after_first[..end_pos];
     let body_start = FRONT_MATTER_DELIMITER.len() + end_pos + FRONT_MATTER_DELIMITER.len();
-    let body = content[body_start..].trim_start_matches('\n').to_string();
-
-    let issue: Issue = serde_yaml::from_str(yaml_content)?;
-
-    // Extract the title from H1 heading
-    let (title, body) = extract_title_heading(&body)?;
-
-    Ok((issue, title, body))
+    Ok((yaml_content, content[body_start..].trim_start_matches('\n')))
 }
 
 /// Extract the title from the H1 heading and return (title, remaining body).
@@ -75,6 +83,27 @@ pub fn format_issue(issue: &Issue, title: &str, body: &str) -> Result<String> {
     Ok(result)
 }
 
+/// Append a comment to an issue body, under a `## Comments` section at the end.
+pub fn append_comment(body: &str, author: &str, at: DateTime<Utc>, text: &str) -> String {
+    let mut result = body.trim_end().to_string();
+    if !result
+        .lines()
+        .any(|line| line.trim_end() == COMMENTS_HEADING)
+    {
+        if !result.is_empty() {
+            result.push_str("\n\n");
+        }
+        result.push_str(COMMENTS_HEADING);
+    }
+    result.push_str(&format!(
+        "\n\n### {} ({})\n\n{}\n",
+        author,
+        at.format("%Y-%m-%d %H:%M UTC"),
+        text.trim()
+    ));
+    result
+}
+
 /// Read an issue from a markdown file.
 /// Returns the issue, title, and body.
 #[cfg(test)]
@@ -188,4 +217,25 @@ No H1 heading here.
 "#;
         assert!(parse_issue(content).is_err());
     }
+
+    #[test]
+    fn test_append_comment() {
+        let at = DateTime::parse_from_rfc3339("2024-01-15T10:30:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        let body = append_comment("Description here.\n", "agent-1", at, "First");
+        assert_eq!(
+            body,
+            "Description here.\n\n## Comments\n\n### agent-1 (2024-01-15 10:30 UTC)\n\nFirst\n"
+        );
+
+        // Later comments go under the existing section
+        let body = append_comment(&body, "agent-2", at, "Second\n");
+        assert_eq!(body.matches(COMMENTS_HEADING).count(), 1);
+        assert!(body.ends_with("First\n\n### agent-2 (2024-01-15 10:30 UTC)\n\nSecond\n"));
+
+        let body = append_comment("", "agent-1", at, "Only");
+        assert!(body.starts_with(COMMENTS_HEADING));
+    }
 }
@@ -1,6 +1,7 @@
 //! End-to-end CLI tests.
 
 use assert_cmd::Command;
+use assert_cmd::cargo::cargo_bin_cmd;
 use predicates::prelude::*;
 use std::fs;
 use std::path::Path;
@@ -41,6 +42,31 @@ fn read_issue_from_data_branch(repo_path: &Path, id: u32) -> Option<String> {
     }
 }
 
+/// Count the commits reachable from a branch.
+fn git_count_commits(repo_path: &Path, branch: &str) -> usize {
+    let output = std::process::Command::new("git")
+        .args(["rev-list", "--count", branch])
+        .current_dir(repo_path)
+        .output()
+        .unwrap();
+    String::from_utf8_lossy(&output.stdout)
+        .trim()
+        .parse()
+        .unwrap()
+}
+
+/// Install an executable hook script in `.itack/hooks/`.
+#[cfg(unix)]
+fn install_hook(repo_path: &Path, name: &str, script: &str) {
+    use std::os::unix::fs::PermissionsExt;
+
+    let hooks_dir = repo_path.join(".itack/hooks");
+    fs::create_dir_all(&hooks_dir).unwrap();
+    let path = hooks_dir.join(name);
+    fs::write(&path, script).unwrap();
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+}
+
 /// Test environment with isolated git repo and database directory.
 struct TestEnv {
     /// Temporary git repository.
@@ -60,13 +86,18 @@ impl TestEnv {
 }
 
 fn itack(env: &TestEnv) -> Command {
-    let mut cmd = Command::cargo_bin("itack").unwrap();
+    let mut cmd = cargo_bin_cmd!("itack");
     cmd.env("ITACK_HOME", env.itack_home_str());
+    cmd.env_remove("ITACK_AGENT");
     cmd
 }
 
 fn setup_git_repo() -> TestEnv {
-    let repo = TempDir::new().unwrap();
+    init_git_repo(TempDir::new().unwrap())
+}
+
+/// Initialize a git repository in `repo`, for tests that need it somewhere specific.
+fn init_git_repo(repo: TempDir) -> TestEnv {
     let itack_home = TempDir::new().unwrap();
 
     // Initialize git repo
@@ -112,8 +143,7 @@ fn test_init_fails_without_git() {
     let dir = TempDir::new().unwrap();
     let itack_home = TempDir::new().unwrap();
 
-    Command::cargo_bin("itack")
-        .unwrap()
+    cargo_bin_cmd!("itack")
         .env("ITACK_HOME", itack_home.path())
         .arg("init")
         .current_dir(dir.path())
@@ -599,3 +629,2633 @@ fn test_markdown_file_format() {
     // Title should be in markdown body as H1 heading
     assert!(content.contains("# Test issue"));
 }
+
+#[test]
+fn test_claim_wait_times_out() {
+    let env = setup_git_repo();
+
+    itack(&env)
+        .arg("init")
+        .current_dir(env.path())
+        .assert()
+        .success();
+
+    itack(&env)
+        .args(["create", "Test issue"])
+        .current_dir(env.path())
+        .assert()
+        .success();
+
+    itack(&env)
+        .args(["claim", "1", "agent-1"])
+        .current_dir(env.path())
+        .assert()
+        .success();
+
+    // Waiting on a claim that is never released times out with exit code 3
+    itack(&env)
+        .args(["claim", "1", "agent-2", "--wait", "--timeout", "1s"])
+        .current_dir(env.path())
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains("released by agent-1"));
+}
+
+#[test]
+fn test_claim_wait_takes_over_expired_claim() {
+    let env = setup_git_repo();
+
+    fs::write(
+        env.itack_home.path().join("config.toml"),
+        "claim_ttl = \"1s\"\n",
+    )
+    .unwrap();
+
+    itack(&env)
+        .arg("init")
+        .current_dir(env.path())
+        .assert()
+        .success();
+
+    itack(&env)
+        .args(["create", "Test issue"])
+        .current_dir(env.path())
+        .assert()
+        .success();
+
+    itack(&env)
+        .args(["claim", "1", "agent-1"])
+        .current_dir(env.path())
+        .assert()
+        .success();
+
+    // The first claim expires after a second, so the waiting claim succeeds
+    itack(&env)
+        .args(["claim", "1", "agent-2", "--wait", "--timeout", "10s"])
+        .current_dir(env.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Claimed issue #1 for agent-2"));
+
+    itack(&env)
+        .args(["show", "1", "--json"])
+        .current_dir(env.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"assignee\": \"agent-2\""));
+}
+
+#[test]
+fn test_claim_wait_deps() {
+    let env = setup_git_repo();
+
+    itack(&env)
+        .arg("init")
+        .current_dir(env.path())
+        .assert()
+        .success();
+
+    itack(&env)
+        .args(["create", "Blocker"])
+        .current_dir(env.path())
+        .assert()
+        .success();
+
+    itack(&env)
+        .args(["create", "Dependent", "--depends-on", "1"])
+        .current_dir(env.path())
+        .assert()
+        .success();
+
+    // Blocker is still open, so waiting times out
+    itack(&env)
+        .args(["claim", "2", "agent-1", "--wait-deps", "--timeout", "1s"])
+        .current_dir(env.path())
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains("#1"));
+
+    itack(&env)
+        .args(["done", "1"])
+        .current_dir(env.path())
+        .assert()
+        .success();
+
+    itack(&env)
+        .args(["claim", "2", "agent-1", "--wait-deps", "--timeout", "1s"])
+        .current_dir(env.path())
+        .assert()
+        .success();
+}
+
+#[test]
+fn test_whoami_resolution_order() {
+    let env = setup_git_repo();
+
+    itack(&env)
+        .arg("init")
+        .current_dir(env.path())
+        .assert()
+        .success();
+
+    // Falls back to the git user name
+    itack(&env)
+        .arg("whoami")
+        .current_dir(env.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Test User (from git user.name)"));
+
+    // Config default_assignee beats git
+    fs::write(
+        env.itack_home.path().join("config.toml"),
+        "default_assignee = \"config-agent\"\n",
+    )
+    .unwrap();
+    itack(&env)
+        .arg("whoami")
+        .current_dir(env.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("config-agent"));
+
+    // ITACK_AGENT beats everything
+    itack(&env)
+        .arg("whoami")
+        .env("ITACK_AGENT", "env-agent")
+        .current_dir(env.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("env-agent (from ITACK_AGENT)"));
+}
+
+#[test]
+fn test_claim_defaults_to_agent_and_lists_agents() {
+    let env = setup_git_repo();
+
+    itack(&env)
+        .arg("init")
+        .current_dir(env.path())
+        .assert()
+        .success();
+
+    itack(&env)
+        .args(["create", "First"])
+        .current_dir(env.path())
+        .assert()
+        .success();
+
+    itack(&env)
+        .args(["create", "Second"])
+        .current_dir(env.path())
+        .assert()
+        .success();
+
+    // Claim without an explicit assignee
+    itack(&env)
+        .args(["claim", "1", "--session", "sess-1"])
+        .env("ITACK_AGENT", "env-agent")
+        .current_dir(env.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Claimed issue #1 for env-agent"));
+
+    itack(&env)
+        .args(["claim", "2", "other-agent"])
+        .current_dir(env.path())
+        .assert()
+        .success();
+
+    itack(&env)
+        .args(["release", "2"])
+        .current_dir(env.path())
+        .assert()
+        .success();
+
+    itack(&env)
+        .args(["agents", "--json"])
+        .current_dir(env.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"name\": \"env-agent\""))
+        .stdout(predicate::str::contains("\"session\": \"sess-1\""))
+        .stdout(predicate::str::contains("\"name\": \"other-agent\""))
+        .stdout(predicate::str::contains("\"active\": false"));
+
+    itack(&env)
+        .arg("agents")
+        .current_dir(env.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("active"))
+        .stdout(predicate::str::contains("idle"));
+}
+
+#[test]
+fn test_claim_limit_returns_exit_code_4() {
+    let env = setup_git_repo();
+
+    fs::write(
+        env.itack_home.path().join("config.toml"),
+        "max_claims = 1\n\n[epic_claim_limits]\nMVP = 1\n",
+    )
+    .unwrap();
+
+    itack(&env)
+        .arg("init")
+        .current_dir(env.path())
+        .assert()
+        .success();
+
+    for title in ["First", "Second"] {
+        itack(&env)
+            .args(["create", title])
+            .current_dir(env.path())
+            .assert()
+            .success();
+    }
+
+    itack(&env)
+        .args(["claim", "1", "agent-1"])
+        .current_dir(env.path())
+        .assert()
+        .success();
+
+    itack(&env)
+        .args(["claim", "2", "agent-1"])
+        .current_dir(env.path())
+        .assert()
+        .code(4)
+        .stderr(predicate::str::contains("maximum of 1 claim(s)"));
+
+    // Other agents are not affected
+    itack(&env)
+        .args(["claim", "2", "agent-2"])
+        .current_dir(env.path())
+        .assert()
+        .success();
+}
+
+#[test]
+fn test_closing_an_issue_frees_its_claim() {
+    let env = setup_git_repo();
+
+    fs::write(
+        env.itack_home.path().join("config.toml"),
+        "max_claims = 1\n",
+    )
+    .unwrap();
+
+    itack(&env)
+        .arg("init")
+        .current_dir(env.path())
+        .assert()
+        .success();
+    for title in ["First", "Second", "Third"] {
+        itack(&env)
+            .args(["create", title])
+            .current_dir(env.path())
+            .assert()
+            .success();
+    }
+
+    for (id, close) in [("1", "done"), ("2", "wont-fix")] {
+        itack(&env)
+            .args(["claim", id, "bob"])
+            .current_dir(env.path())
+            .assert()
+            .success();
+        itack(&env)
+            .args([close, id])
+            .current_dir(env.path())
+            .assert()
+            .success();
+    }
+
+    // Closed issues keep their assignee but don't count towards the limit,
+    // also when the database is rebuilt from the data branch
+    for entry in fs::read_dir(env.itack_home.path()).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().is_some_and(|ext| ext == "db") {
+            fs::remove_file(path).unwrap();
+        }
+    }
+    itack(&env)
+        .args(["claim", "3", "bob"])
+        .current_dir(env.path())
+        .assert()
+        .success();
+    let issue = read_issue_from_data_branch(env.path(), 1).unwrap();
+    assert!(issue.contains("assignee: bob"));
+}
+
+#[test]
+fn test_rebuilt_claims_restart_their_ttl() {
+    let env = setup_git_repo();
+
+    fs::write(
+        env.itack_home.path().join("config.toml"),
+        "claim_ttl = \"1h\"\n",
+    )
+    .unwrap();
+
+    itack(&env)
+        .arg("init")
+        .current_dir(env.path())
+        .assert()
+        .success();
+
+    // An issue created long before it was claimed
+    fs::write(
+        env.path().join("old.jsonl"),
+        r#"{"project":"elsewhere","front_matter":{"assignee":"bob","created":"2020-01-01T00:00:00Z","id":1,"status":"in-progress"},"title":"Old","body":""}"#,
+    )
+    .unwrap();
+    itack(&env)
+        .args(["import", "--format", "jsonl", "old.jsonl"])
+        .current_dir(env.path())
+        .assert()
+        .success();
+
+    for entry in fs::read_dir(env.itack_home.path()).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().is_some_and(|ext| ext == "db") {
+            fs::remove_file(path).unwrap();
+        }
+    }
+
+    // The claim rebuilt from the data branch is still live
+    itack(&env)
+        .args(["claim", "1", "alice"])
+        .current_dir(env.path())
+        .assert()
+        .code(2);
+}
+
+#[test]
+fn test_claim_blocked_issue() {
+    let env = setup_git_repo();
+
+    itack(&env)
+        .arg("init")
+        .current_dir(env.path())
+        .assert()
+        .success();
+
+    itack(&env)
+        .args(["create", "Blocker"])
+        .current_dir(env.path())
+        .assert()
+        .success();
+
+    itack(&env)
+        .args(["create", "Dependent", "--depends-on", "1"])
+        .current_dir(env.path())
+        .assert()
+        .success();
+
+    // Claiming a blocked issue fails with exit code 5
+    itack(&env)
+        .args(["claim", "2", "agent-1"])
+        .current_dir(env.path())
+        .assert()
+        .code(5)
+        .stderr(predicate::str::contains(
+            "blocked by unfinished dependencies: #1",
+        ));
+
+    // list --ready / --blocked use the same logic
+    itack(&env)
+        .args(["list", "--ready"])
+        .current_dir(env.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Blocker"))
+        .stdout(predicate::str::contains("Dependent").not());
+
+    itack(&env)
+        .args(["list", "--blocked"])
+        .current_dir(env.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Dependent"))
+        .stdout(predicate::str::contains("Blocker").not());
+
+    // The override claims anyway
+    itack(&env)
+        .args(["claim", "2", "agent-1", "--ignore-deps"])
+        .current_dir(env.path())
+        .assert()
+        .success();
+}
+
+#[cfg(unix)]
+#[test]
+fn test_done_reports_unblocked_issues() {
+    let env = setup_git_repo();
+
+    itack(&env)
+        .arg("init")
+        .current_dir(env.path())
+        .assert()
+        .success();
+
+    itack(&env)
+        .args(["create", "Blocker"])
+        .current_dir(env.path())
+        .assert()
+        .success();
+
+    itack(&env)
+        .args(["create", "Other blocker"])
+        .current_dir(env.path())
+        .assert()
+        .success();
+
+    itack(&env)
+        .args(["create", "Ready after 1", "--depends-on", "1"])
+        .current_dir(env.path())
+        .assert()
+        .success();
+
+    itack(&env)
+        .args(["create", "Still blocked", "--depends-on", "1,2"])
+        .current_dir(env.path())
+        .assert()
+        .success();
+
+    install_hook(
+        env.path(),
+        "post-unblock",
+        "#!/bin/sh\necho \"$ITACK_ISSUE_ID by $ITACK_UNBLOCKED_BY\" >> .itack/unblocked.log\n",
+    );
+
+    itack(&env)
+        .args(["done", "1", "--json"])
+        .current_dir(env.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"status\": \"done\""))
+        .stdout(predicate::str::contains("\"id\": 3"))
+        .stdout(predicate::str::contains("\"id\": 4").not());
+
+    let log = fs::read_to_string(env.path().join(".itack/unblocked.log")).unwrap();
+    assert_eq!(log.trim(), "3 by 1");
+
+    // Closing the other blocker via wont-fix unblocks the last issue
+    itack(&env)
+        .args(["wont-fix", "2"])
+        .current_dir(env.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Unblocked issues:"))
+        .stdout(predicate::str::contains("#4: Still blocked"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_pre_hook_aborts_and_post_hook_receives_issue() {
+    let env = setup_git_repo();
+
+    itack(&env)
+        .arg("init")
+        .current_dir(env.path())
+        .assert()
+        .success();
+
+    install_hook(
+        env.path(),
+        "post-create",
+        "#!/bin/sh\ncat > .itack/created.json\necho \"$ITACK_EVENT $ITACK_ISSUE_ID $ITACK_STATUS $ITACK_ACTOR\" > .itack/created.env\n",
+    );
+
+    itack(&env)
+        .args(["create", "Hooked issue"])
+        .env("ITACK_AGENT", "agent-1")
+        .current_dir(env.path())
+        .assert()
+        .success();
+
+    let payload = fs::read_to_string(env.path().join(".itack/created.json")).unwrap();
+    assert!(payload.contains("\"title\":\"Hooked issue\""));
+    let hook_env = fs::read_to_string(env.path().join(".itack/created.env")).unwrap();
+    assert_eq!(hook_env.trim(), "post-create 1 open agent-1");
+
+    // A failing pre-claim hook aborts the claim before anything is committed
+    install_hook(env.path(), "pre-claim", "#!/bin/sh\nexit 1\n");
+
+    itack(&env)
+        .args(["claim", "1", "agent-1"])
+        .current_dir(env.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "pre-claim hook rejected the change",
+        ));
+
+    let content = read_issue_from_data_branch(env.path(), 1).unwrap();
+    assert!(content.contains("status: open"));
+    assert!(!content.contains("assignee"));
+
+    // Once the hook allows it, the claim is not blocked by a stale lock
+    install_hook(env.path(), "pre-claim", "#!/bin/sh\nexit 0\n");
+
+    itack(&env)
+        .args(["claim", "1", "agent-1"])
+        .current_dir(env.path())
+        .assert()
+        .success();
+}
+
+#[test]
+fn test_watch_replays_events_since_commit() {
+    let env = setup_git_repo();
+
+    itack(&env)
+        .arg("init")
+        .current_dir(env.path())
+        .assert()
+        .success();
+
+    itack(&env)
+        .args(["create", "First issue"])
+        .current_dir(env.path())
+        .assert()
+        .success();
+
+    let since = std::process::Command::new("git")
+        .args(["rev-parse", "data/itack"])
+        .current_dir(env.path())
+        .output()
+        .unwrap();
+    let since = String::from_utf8(since.stdout).unwrap().trim().to_string();
+
+    itack(&env)
+        .args(["create", "Second issue"])
+        .current_dir(env.path())
+        .assert()
+        .success();
+
+    itack(&env)
+        .args(["claim", "1", "agent-1"])
+        .current_dir(env.path())
+        .assert()
+        .success();
+
+    itack(&env)
+        .args(["done", "1"])
+        .current_dir(env.path())
+        .assert()
+        .success();
+
+    let output = itack(&env)
+        .args(["watch", "--since", &since, "--once"])
+        .current_dir(env.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let events: Vec<serde_json::Value> = String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    let summary: Vec<(&str, u64)> = events
+        .iter()
+        .map(|e| {
+            (
+                e["event"].as_str().unwrap(),
+                e["issue_id"].as_u64().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("created", 2),
+            ("claimed", 1),
+            ("status_changed", 1),
+            ("status_changed", 1),
+        ]
+    );
+    assert_eq!(events[1]["to"], "agent-1");
+    assert_eq!(events[3]["to"], "done");
+
+    // Resuming from the last event's commit yields nothing new
+    let last = events[3]["commit"].as_str().unwrap();
+    itack(&env)
+        .args(["watch", "--since", last, "--once"])
+        .current_dir(env.path())
+        .assert()
+        .success()
+        .stdout("");
+}
+
+#[test]
+fn test_comment_and_next() {
+    let env = setup_git_repo();
+
+    itack(&env)
+        .arg("init")
+        .current_dir(env.path())
+        .assert()
+        .success();
+
+    itack(&env)
+        .args(["create", "Blocker"])
+        .current_dir(env.path())
+        .assert()
+        .success();
+
+    itack(&env)
+        .args(["create", "Blocked", "--depends-on", "1"])
+        .current_dir(env.path())
+        .assert()
+        .success();
+
+    itack(&env)
+        .args(["comment", "1", "Looks tricky", "--author", "agent-2"])
+        .current_dir(env.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Added comment to issue #1"));
+
+    let content = read_issue_from_data_branch(env.path(), 1).unwrap();
+    assert!(content.contains("## Comments"));
+    assert!(content.contains("### agent-2 ("));
+    assert!(content.contains("Looks tricky"));
+
+    itack(&env)
+        .args(["next", "--claim", "--assignee", "agent-1"])
+        .current_dir(env.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Claimed issue #1 for agent-1"));
+
+    // Issue 2 is still blocked and issue 1 is taken
+    itack(&env)
+        .args(["next", "--json"])
+        .current_dir(env.path())
+        .assert()
+        .success()
+        .stdout("null\n");
+}
+
+#[test]
+fn test_mcp_server_tools() {
+    let env = setup_git_repo();
+
+    itack(&env)
+        .arg("init")
+        .current_dir(env.path())
+        .assert()
+        .success();
+
+    let requests = [
+        r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2025-06-18","capabilities":{},"clientInfo":{"name":"test","version":"0"}}}"#,
+        r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#,
+        r#"{"jsonrpc":"2.0","id":2,"method":"tools/list"}"#,
+        r#"{"jsonrpc":"2.0","id":3,"method":"tools/call","params":{"name":"create","arguments":{"title":"From MCP","epic":"mcp"}}}"#,
+        r#"{"jsonrpc":"2.0","id":4,"method":"tools/call","params":{"name":"claim","arguments":{"id":1,"assignee":"agent-1"}}}"#,
+        r#"{"jsonrpc":"2.0","id":5,"method":"tools/call","params":{"name":"claim","arguments":{"id":1,"assignee":"agent-2"}}}"#,
+        r#"{"jsonrpc":"2.0","id":6,"method":"tools/call","params":{"name":"list","arguments":{}}}"#,
+    ];
+
+    let output = itack(&env)
+        .arg("mcp")
+        .current_dir(env.path())
+        .write_stdin(requests.join("\n") + "\n")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let responses: Vec<serde_json::Value> = String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    // The notification gets no response
+    assert_eq!(responses.len(), 6);
+
+    assert_eq!(responses[0]["result"]["serverInfo"]["name"], "itack");
+
+    let tools: Vec<&str> = responses[1]["result"]["tools"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|tool| tool["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        tools,
+        vec![
+            "list", "show", "create", "claim", "release", "set", "done", "comment", "next"
+        ]
+    );
+
+    let tool_json = |response: &serde_json::Value| -> serde_json::Value {
+        let text = response["result"]["content"][0]["text"].as_str().unwrap();
+        serde_json::from_str(text).unwrap_or(serde_json::Value::Null)
+    };
+
+    assert_eq!(tool_json(&responses[2])["title"], "From MCP");
+    assert_eq!(tool_json(&responses[3])["assignee"], "agent-1");
+
+    // Command errors come back as tool errors, not protocol errors
+    assert_eq!(responses[4]["result"]["isError"], true);
+    assert!(
+        responses[4]["result"]["content"][0]["text"]
+            .as_str()
+            .unwrap()
+            .contains("already claimed")
+    );
+
+    assert_eq!(tool_json(&responses[5])[0]["status"], "in-progress");
+}
+
+/// A running `itack serve` process, stopped when dropped.
+struct Server {
+    child: std::process::Child,
+    addr: String,
+}
+
+impl Server {
+    fn start(env: &TestEnv) -> Self {
+        use std::io::{BufRead, BufReader};
+
+        let mut child = std::process::Command::new(assert_cmd::cargo::cargo_bin!("itack"))
+            .args(["serve", "--bind", "127.0.0.1:0"])
+            .env("ITACK_HOME", env.itack_home_str())
+            .env_remove("ITACK_AGENT")
+            .current_dir(env.path())
+            .stdout(std::process::Stdio::piped())
+            .spawn()
+            .unwrap();
+
+        let mut line = String::new();
+        BufReader::new(child.stdout.take().unwrap())
+            .read_line(&mut line)
+            .unwrap();
+        let addr = line
+            .trim()
+            .trim_start_matches("Listening on http://")
+            .to_string();
+        Server { child, addr }
+    }
+
+    /// Send a request and return the status, raw headers and body.
+    fn request(
+        &self,
+        method: &str,
+        path: &str,
+        headers: &[(&str, &str)],
+        body: &str,
+    ) -> (u16, String, String) {
+        use std::io::{Read, Write};
+
+        let mut stream = std::net::TcpStream::connect(&self.addr).unwrap();
+        let mut request = format!(
+            "{} {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\nContent-Length: {}\r\n",
+            method,
+            path,
+            self.addr,
+            body.len()
+        );
+        for (name, value) in headers {
+            request.push_str(&format!("{}: {}\r\n", name, value));
+        }
+        request.push_str("\r\n");
+        request.push_str(body);
+        stream.write_all(request.as_bytes()).unwrap();
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        let status = head.split(' ').nth(1).unwrap().parse().unwrap();
+        (status, head.to_string(), body.to_string())
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Get a header value from a raw response head.
+fn response_header(head: &str, name: &str) -> Option<String> {
+    head.lines().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        key.eq_ignore_ascii_case(name)
+            .then(|| value.trim().to_string())
+    })
+}
+
+#[test]
+fn test_serve_rest_api() {
+    let env = setup_git_repo();
+
+    itack(&env)
+        .arg("init")
+        .current_dir(env.path())
+        .assert()
+        .success();
+
+    let server = Server::start(&env);
+
+    let (status, head, body) = server.request(
+        "POST",
+        "/issues",
+        &[],
+        r#"{"title":"From HTTP","epic":"api"}"#,
+    );
+    assert_eq!(status, 201);
+    assert_eq!(response_header(&head, "Location").unwrap(), "/issues/1");
+    let issue: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(issue["title"], "From HTTP");
+
+    // Conditional GET with the blob ETag
+    let (status, head, _) = server.request("GET", "/issues/1", &[], "");
+    assert_eq!(status, 200);
+    let etag = response_header(&head, "ETag").unwrap();
+    let (status, _, _) = server.request("GET", "/issues/1", &[("If-None-Match", &etag)], "");
+    assert_eq!(status, 304);
+
+    // PATCH with a matching ETag succeeds and changes it; a stale one is refused
+    let (status, head, body) = server.request(
+        "PATCH",
+        "/issues/1",
+        &[("If-Match", &etag)],
+        r#"{"body":"Updated over HTTP"}"#,
+    );
+    assert_eq!(status, 200);
+    assert!(body.contains("Updated over HTTP"));
+    assert_ne!(response_header(&head, "ETag").unwrap(), etag);
+
+    let (status, _, _) = server.request(
+        "PATCH",
+        "/issues/1",
+        &[("If-Match", &etag)],
+        r#"{"title":"Lost update"}"#,
+    );
+    assert_eq!(status, 412);
+
+    let (status, _, body) =
+        server.request("POST", "/issues/1/claim", &[], r#"{"assignee":"agent-1"}"#);
+    assert_eq!(status, 200);
+    assert!(body.contains("\"assignee\": \"agent-1\""));
+
+    let (status, _, _) =
+        server.request("POST", "/issues/1/claim", &[], r#"{"assignee":"agent-2"}"#);
+    assert_eq!(status, 409);
+
+    let (status, _, body) = server.request("GET", "/claims", &[], "");
+    assert_eq!(status, 200);
+    let claims: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(claims[0]["issue_id"], 1);
+    assert_eq!(claims[0]["assignee"], "agent-1");
+
+    // Claims aren't changed behind the claim endpoints' back
+    let (status, _, _) = server.request("PATCH", "/issues/1", &[], r#"{"status":"open"}"#);
+    assert_eq!(status, 422);
+
+    let (status, _, body) = server.request("GET", "/issues?status=in-progress&epic=api", &[], "");
+    assert_eq!(status, 200);
+    let issues: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(issues.as_array().unwrap().len(), 1);
+
+    let (status, _, body) = server.request("PATCH", "/issues/1", &[], r#"{"status":"done"}"#);
+    assert_eq!(status, 200);
+    assert!(body.contains("\"status\": \"done\""));
+
+    let (status, _, body) = server.request("GET", "/board", &[], "");
+    assert_eq!(status, 200);
+    let board: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(board["counts"]["done"], 1);
+
+    // Reopening is a plain edit, but starting work needs a claim
+    let (status, _, body) = server.request("PATCH", "/issues/1", &[], r#"{"status":"open"}"#);
+    assert_eq!(status, 200);
+    assert!(body.contains("\"status\": \"open\""));
+    let (status, _, _) = server.request("PATCH", "/issues/1", &[], r#"{"status":"in-progress"}"#);
+    assert_eq!(status, 422);
+
+    let (status, _, _) = server.request("GET", "/issues/99", &[], "");
+    assert_eq!(status, 404);
+}
+
+#[test]
+fn test_serve_event_stream() {
+    use std::io::{Read, Write};
+
+    let env = setup_git_repo();
+
+    itack(&env)
+        .arg("init")
+        .current_dir(env.path())
+        .assert()
+        .success();
+
+    let server = Server::start(&env);
+
+    let mut stream = std::net::TcpStream::connect(&server.addr).unwrap();
+    stream
+        .set_read_timeout(Some(std::time::Duration::from_secs(20)))
+        .unwrap();
+    write!(
+        stream,
+        "GET /events HTTP/1.1\r\nHost: {}\r\nAccept: text/event-stream\r\n\r\n",
+        server.addr
+    )
+    .unwrap();
+
+    // Wait for the response head so the feed starts before the change
+    let mut received = String::new();
+    let mut buf = [0u8; 4096];
+    while !received.contains("\r\n\r\n") {
+        let n = stream.read(&mut buf).unwrap();
+        received.push_str(&String::from_utf8_lossy(&buf[..n]));
+    }
+    assert!(received.contains("text/event-stream"));
+
+    itack(&env)
+        .args(["create", "Streamed issue"])
+        .current_dir(env.path())
+        .assert()
+        .success();
+
+    while !received.contains("event: created") {
+        let n = stream.read(&mut buf).unwrap();
+        assert!(n > 0, "event stream closed early");
+        received.push_str(&String::from_utf8_lossy(&buf[..n]));
+    }
+    assert!(received.contains("\"title\":\"Streamed issue\""));
+}
+
+#[test]
+fn test_export_html_site() {
+    let env = setup_git_repo();
+
+    itack(&env)
+        .arg("init")
+        .current_dir(env.path())
+        .assert()
+        .success();
+
+    itack(&env)
+        .args([
+            "create",
+            "Design API",
+            "--epic",
+            "Phase 2",
+            "--body",
+            "Use **REST**. <script>alert(1)</script>",
+        ])
+        .current_dir(env.path())
+        .assert()
+        .success();
+
+    itack(&env)
+        .args([
+            "create",
+            "Build API",
+            "--epic",
+            "Phase 2",
+            "--depends-on",
+            "1",
+        ])
+        .current_dir(env.path())
+        .assert()
+        .success();
+
+    itack(&env)
+        .args(["claim", "1", "agent-1"])
+        .current_dir(env.path())
+        .assert()
+        .success();
+
+    let site = env.path().join("site");
+    itack(&env)
+        .args(["export-html", site.to_str().unwrap()])
+        .current_dir(env.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Exported 2 issues"));
+
+    let board = fs::read_to_string(site.join("index.html")).unwrap();
+    assert!(board.contains("in-progress (1)"));
+    assert!(board.contains("href=\"issues/2.html\""));
+
+    let epic = fs::read_to_string(site.join("epics/phase-2.html")).unwrap();
+    assert!(epic.contains("Design API"));
+    assert!(epic.contains("Build API"));
+
+    let issue = fs::read_to_string(site.join("issues/1.html")).unwrap();
+    assert!(issue.contains("<strong>REST</strong>"));
+    assert!(issue.contains("&lt;script&gt;"));
+    assert!(!issue.contains("<script>alert"));
+    // Issue 2 depends on issue 1, so issue 1 blocks it
+    assert!(issue.contains("href=\"2.html\""));
+    assert!(issue.contains("Claim issue #1 for agent-1"));
+    assert!(issue.contains("Create issue #1: Design API"));
+
+    let index: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(site.join("search-index.json")).unwrap()).unwrap();
+    assert_eq!(index.as_array().unwrap().len(), 2);
+    assert_eq!(index[1]["url"], "issues/2.html");
+}
+
+#[test]
+fn test_import_github_export() {
+    let env = setup_git_repo();
+
+    itack(&env)
+        .arg("init")
+        .current_dir(env.path())
+        .assert()
+        .success();
+
+    itack(&env)
+        .args(["create", "Existing issue"])
+        .current_dir(env.path())
+        .assert()
+        .success();
+
+    let export = env.path().join("issues.json");
+    fs::write(
+        &export,
+        r#"[
+          {"number": 12, "title": "Crash on start", "state": "OPEN",
+           "body": "Blocks #9, see also #3.",
+           "labels": [{"name": "bug"}], "assignees": [{"login": "octocat"}],
+           "milestone": {"title": "v1"},
+           "comments": [{"author": {"login": "hubot"}, "body": "Same as #12?",
+                         "createdAt": "2024-01-02T03:04:05Z"}],
+           "createdAt": "2024-01-01T00:00:00Z",
+           "url": "https://github.com/owner/repo/issues/12"},
+          {"number": 9, "title": "Old idea", "state": "CLOSED", "stateReason": "NOT_PLANNED",
+           "body": "", "labels": [], "assignees": [], "milestone": null, "comments": [],
+           "createdAt": "2023-06-01T00:00:00Z",
+           "url": "https://github.com/owner/repo/issues/9"}
+        ]"#,
+    )
+    .unwrap();
+
+    let commits_before = git_count_commits(env.path(), "data/itack");
+
+    itack(&env)
+        .args(["import", "github", "issues.json"])
+        .current_dir(env.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Imported 2 issues from GitHub"));
+
+    // Everything lands in a single data branch commit
+    assert_eq!(
+        git_count_commits(env.path(), "data/itack"),
+        commits_before + 1
+    );
+
+    // IDs are allocated in GitHub number order after existing issues
+    let old_idea = read_issue_from_data_branch(env.path(), 2).unwrap();
+    assert!(old_idea.contains("# Old idea"));
+    assert!(old_idea.contains("status: wont-fix"));
+    assert!(old_idea.contains("imported_from: github:owner/repo#9"));
+
+    let crash = read_issue_from_data_branch(env.path(), 3).unwrap();
+    assert!(crash.contains("status: in-progress"));
+    assert!(crash.contains("assignee: octocat"));
+    assert!(crash.contains("epic: v1"));
+    assert!(crash.contains("- bug"));
+    assert!(crash.contains("Blocks #2, see also owner/repo#3."));
+    assert!(crash.contains("### hubot (2024-01-02 03:04 UTC)"));
+    assert!(crash.contains("Same as #3?"));
+
+    itack(&env)
+        .args(["list", "--assignee", "octocat"])
+        .current_dir(env.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Crash on start"));
+
+    // Re-importing the same export is a no-op
+    itack(&env)
+        .args(["import", "github", "issues.json"])
+        .current_dir(env.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Imported 0 issues from GitHub (2 already imported)",
+        ));
+    assert_eq!(
+        git_count_commits(env.path(), "data/itack"),
+        commits_before + 1
+    );
+}
+
+#[test]
+fn test_export_import_jsonl() {
+    let source = setup_git_repo();
+
+    itack(&source)
+        .arg("init")
+        .current_dir(source.path())
+        .assert()
+        .success();
+
+    itack(&source)
+        .args(["create", "Base", "--body", "Start here"])
+        .current_dir(source.path())
+        .assert()
+        .success();
+
+    itack(&source)
+        .args([
+            "create",
+            "Follow-up",
+            "--depends-on",
+            "1",
+            "--body",
+            "After #1",
+        ])
+        .current_dir(source.path())
+        .assert()
+        .success();
+
+    itack(&source)
+        .args(["export", "--history", "--output", "backup.jsonl"])
+        .current_dir(source.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Exported 2 issues to backup.jsonl",
+        ));
+
+    let export = fs::read_to_string(source.path().join("backup.jsonl")).unwrap();
+    let records: Vec<serde_json::Value> = export
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[1]["front_matter"]["id"], 2);
+    assert_eq!(
+        records[1]["front_matter"]["depends_on"],
+        serde_json::json!([1])
+    );
+    assert_eq!(records[1]["title"], "Follow-up");
+    assert_eq!(
+        records[1]["history"][0]["summary"],
+        "Create issue #2: Follow-up"
+    );
+
+    // Restoring into an empty tracker keeps IDs, and is idempotent
+    let restore = setup_git_repo();
+    fs::write(restore.path().join("backup.jsonl"), &export).unwrap();
+
+    itack(&restore)
+        .arg("init")
+        .current_dir(restore.path())
+        .assert()
+        .success();
+
+    itack(&restore)
+        .args(["import", "--format", "jsonl", "backup.jsonl"])
+        .current_dir(restore.path())
+        .assert()
+        .success()
+        .stdout("Imported 2 issues\n");
+
+    itack(&restore)
+        .args(["import", "--format", "jsonl", "backup.jsonl"])
+        .current_dir(restore.path())
+        .assert()
+        .success()
+        .stdout("Imported 0 issues (2 already imported)\n");
+
+    let follow_up = read_issue_from_data_branch(restore.path(), 2).unwrap();
+    assert!(follow_up.contains("# Follow-up"));
+    assert!(follow_up.contains("- 1"));
+
+    itack(&restore)
+        .args(["create", "Next"])
+        .current_dir(restore.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Created issue #3"));
+
+    // Merging into a tracker with its own issues needs new IDs
+    let merge = setup_git_repo();
+    fs::write(merge.path().join("backup.jsonl"), &export).unwrap();
+
+    itack(&merge)
+        .arg("init")
+        .current_dir(merge.path())
+        .assert()
+        .success();
+
+    itack(&merge)
+        .args(["create", "Local issue"])
+        .current_dir(merge.path())
+        .assert()
+        .success();
+
+    itack(&merge)
+        .args(["import", "--format", "jsonl", "backup.jsonl"])
+        .current_dir(merge.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Issue #1 already exists"));
+
+    itack(&merge)
+        .args(["import", "--format", "jsonl", "--renumber", "backup.jsonl"])
+        .current_dir(merge.path())
+        .assert()
+        .success()
+        .stdout("Imported 2 issues\n");
+
+    let follow_up = read_issue_from_data_branch(merge.path(), 3).unwrap();
+    assert!(follow_up.contains("# Follow-up"));
+    assert!(follow_up.contains("- 2"));
+    assert!(follow_up.contains("After #2"));
+    assert!(follow_up.contains("imported_from: itack:"));
+
+    itack(&merge)
+        .args(["import", "--format", "jsonl", "--renumber", "backup.jsonl"])
+        .current_dir(merge.path())
+        .assert()
+        .success()
+        .stdout("Imported 0 issues (2 already imported)\n");
+}
+
+#[test]
+fn test_csv_export_and_import() {
+    let env = setup_git_repo();
+
+    itack(&env)
+        .arg("init")
+        .current_dir(env.path())
+        .assert()
+        .success();
+
+    itack(&env)
+        .args(["create", "Parser", "--epic", "v1"])
+        .current_dir(env.path())
+        .assert()
+        .success();
+
+    itack(&env)
+        .args(["create", "Docs, part one"])
+        .current_dir(env.path())
+        .assert()
+        .success();
+
+    itack(&env)
+        .args([
+            "export",
+            "--format",
+            "csv",
+            "--columns",
+            "id,title,status,epic",
+        ])
+        .current_dir(env.path())
+        .assert()
+        .success()
+        .stdout("id,title,status,epic\n1,Parser,open,v1\n2,\"Docs, part one\",open,\n");
+
+    itack(&env)
+        .args(["export", "--format", "csv", "--history"])
+        .current_dir(env.path())
+        .assert()
+        .failure();
+
+    fs::write(
+        env.path().join("plan.csv"),
+        "id,title,status,epic,assignee,depends_on\n\
+         1,,in-progress,v1,alice,\n\
+         2,,,,,1\n\
+         ,Release notes,,v1,,\"1, 2\"\n",
+    )
+    .unwrap();
+
+    let commits_before = git_count_commits(env.path(), "data/itack");
+
+    itack(&env)
+        .args(["import", "--format", "csv", "--dry-run", "plan.csv"])
+        .current_dir(env.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("+ #3 Release notes"))
+        .stdout(predicate::str::contains("    status: open -> in-progress"))
+        .stdout(predicate::str::contains("    assignee: - -> alice"))
+        .stdout(predicate::str::contains("    depends_on: - -> #1"))
+        .stdout(predicate::str::contains(
+            "Dry run, nothing committed: 1 created, 2 updated, 0 unchanged",
+        ));
+    assert_eq!(git_count_commits(env.path(), "data/itack"), commits_before);
+
+    itack(&env)
+        .args(["import", "--format", "csv", "plan.csv"])
+        .current_dir(env.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Imported CSV: 1 created, 2 updated, 0 unchanged",
+        ));
+    assert_eq!(
+        git_count_commits(env.path(), "data/itack"),
+        commits_before + 1
+    );
+
+    let parser = read_issue_from_data_branch(env.path(), 1).unwrap();
+    assert!(parser.contains("status: in-progress"));
+    assert!(parser.contains("assignee: alice"));
+
+    let notes = read_issue_from_data_branch(env.path(), 3).unwrap();
+    assert!(notes.contains("# Release notes"));
+    assert!(notes.contains("epic: v1"));
+
+    // The assignment is a real claim
+    itack(&env)
+        .args(["claim", "1", "bob"])
+        .current_dir(env.path())
+        .assert()
+        .code(2);
+
+    // Unknown dependencies are rejected before anything is committed
+    fs::write(env.path().join("bad.csv"), "id,depends_on\n2,9\n").unwrap();
+    itack(&env)
+        .args(["import", "--format", "csv", "bad.csv"])
+        .current_dir(env.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Row 2: unknown dependency #9"));
+
+    // Closing an issue frees its claim and unblocks its dependents, and
+    // claims for closed rows aren't taken
+    fs::write(
+        env.itack_home.path().join("config.toml"),
+        "max_claims = 1\n",
+    )
+    .unwrap();
+    fs::write(
+        env.path().join("close.csv"),
+        "id,title,status,assignee\n1,,done,alice\n,Shipped,done,carol\n",
+    )
+    .unwrap();
+    itack(&env)
+        .args(["import", "--format", "csv", "close.csv"])
+        .current_dir(env.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Unblocked issues:\n  #2: Docs, part one",
+        ));
+    itack(&env)
+        .args(["claim", "2", "alice"])
+        .current_dir(env.path())
+        .assert()
+        .success();
+    itack(&env)
+        .args(["claim", "3", "carol", "--ignore-deps"])
+        .current_dir(env.path())
+        .assert()
+        .success();
+}
+
+#[test]
+fn test_batch_applies_operations_in_one_commit() {
+    let env = setup_git_repo();
+
+    itack(&env)
+        .arg("init")
+        .current_dir(env.path())
+        .assert()
+        .success();
+
+    itack(&env)
+        .args(["create", "Existing"])
+        .current_dir(env.path())
+        .assert()
+        .success();
+
+    let commits_before = git_count_commits(env.path(), "data/itack");
+
+    let operations = r#"{"op": "create", "title": "Parser", "epic": "v1"}
+{"op": "create", "title": "Docs", "depends_on": ["$1"]}
+{"op": "claim", "id": "$1", "assignee": "agent-1"}
+{"op": "comment", "id": "$1", "text": "Starting", "author": "agent-1"}
+
+{"op": "done", "id": 1}
+"#;
+
+    itack(&env)
+        .arg("batch")
+        .write_stdin(operations)
+        .current_dir(env.path())
+        .assert()
+        .success()
+        .stdout(
+            "$1 -> #2: Parser\n$2 -> #3: Docs\nApplied 5 operations to 3 issues in one commit\n",
+        );
+
+    assert_eq!(
+        git_count_commits(env.path(), "data/itack"),
+        commits_before + 1
+    );
+
+    let parser = read_issue_from_data_branch(env.path(), 2).unwrap();
+    assert!(parser.contains("assignee: agent-1"));
+    assert!(parser.contains("status: in-progress"));
+    assert!(parser.contains("Starting"));
+    let docs = read_issue_from_data_branch(env.path(), 3).unwrap();
+    assert!(docs.contains("- 2"));
+    let existing = read_issue_from_data_branch(env.path(), 1).unwrap();
+    assert!(existing.contains("status: done"));
+
+    // The claim went through the database
+    itack(&env)
+        .args(["claim", "2", "bob"])
+        .current_dir(env.path())
+        .assert()
+        .code(2);
+
+    // A failing operation leaves everything untouched
+    itack(&env)
+        .arg("batch")
+        .write_stdin("{\"op\": \"create\", \"title\": \"Lost\"}\n{\"op\": \"done\", \"id\": 99}\n")
+        .current_dir(env.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Line 2: Issue 99 not found"));
+
+    // So does a claim the database refuses, and neither uses up an ID
+    itack(&env)
+        .arg("batch")
+        .write_stdin("{\"op\": \"create\", \"title\": \"Lost\"}\n{\"op\": \"claim\", \"id\": 2, \"assignee\": \"bob\"}\n")
+        .current_dir(env.path())
+        .assert()
+        .failure();
+
+    assert_eq!(
+        git_count_commits(env.path(), "data/itack"),
+        commits_before + 1
+    );
+
+    itack(&env)
+        .arg("batch")
+        .arg("--json")
+        .write_stdin(
+            "{\"op\": \"create\", \"title\": \"Tests\"}\n{\"op\": \"release\", \"id\": 2}\n",
+        )
+        .current_dir(env.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"$1\": 4"));
+
+    itack(&env)
+        .args(["claim", "2", "bob"])
+        .current_dir(env.path())
+        .assert()
+        .success();
+}
+
+#[cfg(unix)]
+#[test]
+fn test_commit_messages_close_and_link_issues() {
+    let env = setup_git_repo();
+
+    itack(&env)
+        .arg("init")
+        .current_dir(env.path())
+        .assert()
+        .success();
+
+    for title in ["Parser bug", "Lexer cleanup", "Depends on parser"] {
+        itack(&env)
+            .args(["create", title])
+            .current_dir(env.path())
+            .assert()
+            .success();
+    }
+    itack(&env)
+        .args(["depend", "3", "1"])
+        .current_dir(env.path())
+        .assert()
+        .success();
+
+    itack(&env)
+        .args(["hook", "install", "--post-merge"])
+        .current_dir(env.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("post-commit"))
+        .stdout(predicate::str::contains("post-merge"));
+
+    // The installed hook runs itack from PATH
+    let bin_dir = assert_cmd::cargo::cargo_bin!("itack")
+        .parent()
+        .unwrap()
+        .to_path_buf();
+    let path = format!(
+        "{}:{}",
+        bin_dir.display(),
+        std::env::var("PATH").unwrap_or_default()
+    );
+    fs::write(env.path().join("parser.rs"), "fn parse() {}\n").unwrap();
+    let git = |args: &[&str]| {
+        let output = std::process::Command::new("git")
+            .args(args)
+            .env("PATH", &path)
+            .env("ITACK_HOME", env.itack_home_str())
+            .current_dir(env.path())
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    };
+    git(&["add", "parser.rs"]);
+    git(&["commit", "-m", "Fix parser, closes #1\n\nrefs #2, #9"]);
+    let sha = git(&["rev-parse", "HEAD"]);
+
+    let parser = read_issue_from_data_branch(env.path(), 1).unwrap();
+    assert!(parser.contains("status: done"));
+    assert!(parser.contains(&format!("- {}", sha)));
+    let lexer = read_issue_from_data_branch(env.path(), 2).unwrap();
+    assert!(lexer.contains("status: open"));
+    assert!(lexer.contains(&format!("- {}", sha)));
+
+    // The work tree is untouched and scanning again changes nothing
+    assert_eq!(git(&["status", "--porcelain"]), "?? .itack/");
+    assert!(
+        fs::read_dir(env.path().join(".itack"))
+            .unwrap()
+            .all(|entry| entry
+                .unwrap()
+                .path()
+                .extension()
+                .is_none_or(|ext| ext != "md"))
+    );
+    let commits_before = git_count_commits(env.path(), "data/itack");
+    itack(&env)
+        .args(["scan-commits", "HEAD"])
+        .current_dir(env.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty())
+        .stderr(predicate::str::contains("unknown issue #9"));
+    assert_eq!(git_count_commits(env.path(), "data/itack"), commits_before);
+
+    // An existing hook is only replaced with --force
+    fs::write(env.path().join(".git/hooks/post-commit"), "#!/bin/sh\n").unwrap();
+    itack(&env)
+        .args(["hook", "install"])
+        .current_dir(env.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--force"));
+}
+
+#[test]
+fn test_scan_commits_range_reports_unblocked() {
+    let env = setup_git_repo();
+
+    itack(&env)
+        .arg("init")
+        .current_dir(env.path())
+        .assert()
+        .success();
+
+    itack(&env)
+        .args(["create", "Blocker"])
+        .current_dir(env.path())
+        .assert()
+        .success();
+    itack(&env)
+        .args(["create", "Follow-up", "--depends-on", "1"])
+        .current_dir(env.path())
+        .assert()
+        .success();
+
+    for message in ["Start work\n\nrefs #1", "Finish work, fixes #1"] {
+        std::process::Command::new("git")
+            .args(["commit", "--allow-empty", "-m", message])
+            .current_dir(env.path())
+            .output()
+            .unwrap();
+    }
+
+    itack(&env)
+        .args(["scan-commits", "..HEAD"])
+        .current_dir(env.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Closed issue #1 by"))
+        .stdout(predicate::str::contains("#2: Follow-up"));
+
+    let blocker = read_issue_from_data_branch(env.path(), 1).unwrap();
+    assert_eq!(blocker.matches("\n- ").count(), 2);
+}
+
+#[test]
+fn test_link_commit_from_issue_branch() {
+    let env = setup_git_repo();
+    let git = |args: &[&str]| {
+        let output = std::process::Command::new("git")
+            .args(args)
+            .current_dir(env.path())
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+    };
+
+    // Commits from before the issue existed aren't about it
+    let output = std::process::Command::new("git")
+        .args(["commit", "--allow-empty", "-m", "Old work on #1"])
+        .env("GIT_COMMITTER_DATE", "2020-01-01T00:00:00Z")
+        .current_dir(env.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    itack(&env)
+        .arg("init")
+        .current_dir(env.path())
+        .assert()
+        .success();
+    itack(&env)
+        .args(["create", "Parser"])
+        .current_dir(env.path())
+        .assert()
+        .success();
+
+    // Nor are commits already on the mainline
+    git(&["branch", "-M", "main"]);
+    git(&["commit", "--allow-empty", "-m", "Mainline note on #1"]);
+
+    git(&["checkout", "-b", "issue-1-parser"]);
+    itack(&env)
+        .args(["claim", "1", "agent-1"])
+        .current_dir(env.path())
+        .assert()
+        .success();
+    git(&["commit", "--allow-empty", "-m", "Start parser (#1)"]);
+    git(&["commit", "--allow-empty", "-m", "Unrelated tidy-up"]);
+    git(&[
+        "commit",
+        "--allow-empty",
+        "-m",
+        "Parser errors\n\nPart of #1",
+    ]);
+
+    // Without a commit, the issue's branch is scanned for mentions of #1
+    itack(&env)
+        .args(["link-commit", "1"])
+        .current_dir(env.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Linked").count(2));
+
+    itack(&env)
+        .args(["link-commit", "1"])
+        .current_dir(env.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No new commits"));
+
+    itack(&env)
+        .args(["link-commit", "1", "HEAD~1"])
+        .current_dir(env.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Linked"));
+
+    itack(&env)
+        .args(["show", "1"])
+        .current_dir(env.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Commits:"))
+        .stdout(predicate::str::contains("Start parser (#1)"))
+        .stdout(predicate::str::contains("Parser errors"))
+        .stdout(predicate::str::contains("Unrelated tidy-up"))
+        .stdout(predicate::str::contains("Old work").not())
+        .stdout(predicate::str::contains("Mainline note").not());
+
+    itack(&env)
+        .args(["show", "1", "--json"])
+        .current_dir(env.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "\"subject\": \"Start parser (#1)\"",
+        ));
+
+    itack(&env)
+        .args(["link-commit", "1", "no-such-commit"])
+        .current_dir(env.path())
+        .assert()
+        .failure();
+}
+
+#[test]
+fn test_start_and_finish_with_worktree() {
+    // Worktrees are created next to the repository, so keep both in one temp dir
+    let parent = TempDir::new().unwrap();
+    let env = init_git_repo(TempDir::new_in(parent.path()).unwrap());
+    let git = |dir: &Path, args: &[&str]| {
+        let output = std::process::Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "git {:?} failed", args);
+    };
+    git(
+        env.path(),
+        &["commit", "--allow-empty", "-m", "Initial commit"],
+    );
+    git(env.path(), &["branch", "-M", "main"]);
+
+    itack(&env)
+        .arg("init")
+        .current_dir(env.path())
+        .assert()
+        .success();
+    itack(&env)
+        .args(["create", "Parser bug!"])
+        .current_dir(env.path())
+        .assert()
+        .success();
+
+    itack(&env)
+        .args(["start", "1", "agent-1"])
+        .current_dir(env.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Claimed issue #1 for agent-1"))
+        .stdout(predicate::str::contains("on branch issue-1-parser-bug"));
+
+    let worktree = parent.path().join("issue-1-parser-bug");
+    assert!(worktree.is_dir());
+    let issue = read_issue_from_data_branch(env.path(), 1).unwrap();
+    assert!(issue.contains("branch: issue-1-parser-bug"));
+    assert!(issue.contains("status: in-progress"));
+
+    // itack works inside the new worktree
+    itack(&env)
+        .args(["show", "1"])
+        .current_dir(&worktree)
+        .assert()
+        .success();
+    git(&worktree, &["commit", "--allow-empty", "-m", "Fix parser"]);
+
+    // Not merged yet, so the worktree is kept
+    itack(&env)
+        .args(["finish", "1", "--remove-worktree"])
+        .current_dir(env.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("in-progress -> done"))
+        .stdout(predicate::str::contains("is not merged into main"));
+    assert!(worktree.is_dir());
+
+    git(env.path(), &["merge", "issue-1-parser-bug"]);
+    itack(&env)
+        .args(["finish", "1", "--remove-worktree"])
+        .current_dir(env.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("already done"))
+        .stdout(predicate::str::contains("Removed worktree"));
+    assert!(!worktree.exists());
+    let issue = read_issue_from_data_branch(env.path(), 1).unwrap();
+    assert!(issue.contains("status: done"));
+    assert!(issue.contains("branch: issue-1-parser-bug"));
+
+    // A failed claim leaves no worktree or branch behind
+    itack(&env)
+        .args(["create", "Lexer"])
+        .current_dir(env.path())
+        .assert()
+        .success();
+    itack(&env)
+        .args(["claim", "2", "bob"])
+        .current_dir(env.path())
+        .assert()
+        .success();
+    itack(&env)
+        .args(["start", "2", "alice"])
+        .current_dir(env.path())
+        .assert()
+        .code(2);
+    assert!(!parent.path().join("issue-2-lexer").exists());
+    let branches = std::process::Command::new("git")
+        .args(["branch", "--list", "issue-2-lexer"])
+        .current_dir(env.path())
+        .output()
+        .unwrap();
+    assert!(branches.stdout.is_empty());
+}
+
+#[test]
+fn test_reconcile_ignores_branches_without_commits() {
+    // Worktrees are created next to the repository, so keep both in one temp dir
+    let parent = TempDir::new().unwrap();
+    let env = init_git_repo(TempDir::new_in(parent.path()).unwrap());
+    let git = |args: &[&str]| {
+        let output = std::process::Command::new("git")
+            .args(args)
+            .current_dir(env.path())
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "git {:?} failed", args);
+    };
+    git(&["commit", "--allow-empty", "-m", "Initial commit"]);
+    git(&["branch", "-M", "main"]);
+
+    itack(&env)
+        .arg("init")
+        .current_dir(env.path())
+        .assert()
+        .success();
+    itack(&env)
+        .args(["create", "Parser"])
+        .current_dir(env.path())
+        .assert()
+        .success();
+    itack(&env)
+        .args(["start", "1", "agent-1"])
+        .current_dir(env.path())
+        .assert()
+        .success();
+
+    // The new branch is still where main was, and main moves on without it
+    itack(&env)
+        .arg("reconcile")
+        .current_dir(env.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No in-progress issues"));
+    git(&["commit", "--allow-empty", "-m", "Unrelated work"]);
+    itack(&env)
+        .arg("reconcile")
+        .current_dir(env.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No in-progress issues"));
+    itack(&env)
+        .arg("doctor")
+        .current_dir(env.path())
+        .assert()
+        .stdout(predicate::str::contains(
+            "No in-progress issues have branches merged",
+        ));
+}
+
+#[test]
+fn test_reconcile_merged_branches() {
+    let env = setup_git_repo();
+    let git = |args: &[&str]| {
+        let output = std::process::Command::new("git")
+            .args(args)
+            .current_dir(env.path())
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "git {:?} failed", args);
+    };
+    git(&["commit", "--allow-empty", "-m", "Initial commit"]);
+    git(&["branch", "-M", "main"]);
+
+    itack(&env)
+        .arg("init")
+        .current_dir(env.path())
+        .assert()
+        .success();
+    for title in ["Parser", "Docs", "After parser"] {
+        itack(&env)
+            .args(["create", title])
+            .current_dir(env.path())
+            .assert()
+            .success();
+    }
+    itack(&env)
+        .args(["depend", "3", "1"])
+        .current_dir(env.path())
+        .assert()
+        .success();
+
+    // #2 is claimed on main itself, so it never counts as merged
+    itack(&env)
+        .args(["claim", "2", "agent-2"])
+        .current_dir(env.path())
+        .assert()
+        .success();
+    git(&["checkout", "-b", "issue-1-parser"]);
+    itack(&env)
+        .args(["claim", "1", "agent-1"])
+        .current_dir(env.path())
+        .assert()
+        .success();
+    git(&["commit", "--allow-empty", "-m", "Parser work"]);
+    git(&["checkout", "main"]);
+
+    itack(&env)
+        .arg("reconcile")
+        .current_dir(env.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No in-progress issues"));
+
+    git(&["merge", "issue-1-parser"]);
+
+    itack(&env)
+        .arg("doctor")
+        .current_dir(env.path())
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("#1: Parser (issue-1-parser)"));
+
+    itack(&env)
+        .arg("reconcile")
+        .write_stdin("")
+        .current_dir(env.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("#1: Parser"))
+        .stdout(predicate::str::contains("#2").not())
+        .stdout(predicate::str::contains("--yes"));
+
+    itack(&env)
+        .args(["reconcile", "--yes"])
+        .current_dir(env.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Marked 1 issues done"))
+        .stdout(predicate::str::contains("#3: After parser"));
+
+    let parser = read_issue_from_data_branch(env.path(), 1).unwrap();
+    assert!(parser.contains("status: done"));
+    let docs = read_issue_from_data_branch(env.path(), 2).unwrap();
+    assert!(docs.contains("status: in-progress"));
+
+    itack(&env)
+        .arg("doctor")
+        .current_dir(env.path())
+        .assert()
+        .success();
+}
+
+#[cfg(unix)]
+#[test]
+fn test_create_from_template() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let env = setup_git_repo();
+
+    itack(&env)
+        .arg("init")
+        .current_dir(env.path())
+        .assert()
+        .success();
+
+    let templates = env.path().join(".itack/templates");
+    fs::create_dir_all(&templates).unwrap();
+    fs::write(
+        templates.join("bug.md"),
+        "---\nepic: Bugs\nlabels: [bug]\npriority: high\n---\n## What happened\n\n<!-- What happened? -->\n",
+    )
+    .unwrap();
+
+    // An explicit body and epic win over the template's
+    itack(&env)
+        .args(["create", "Crash", "--template", "bug", "--epic", "MVP"])
+        .args(["--body", "Crashes on start"])
+        .current_dir(env.path())
+        .assert()
+        .success();
+    let issue = read_issue_from_data_branch(env.path(), 1).unwrap();
+    assert!(issue.contains("epic: MVP"));
+    assert!(issue.contains("- bug"));
+    assert!(issue.contains("priority: high"));
+    assert!(issue.contains("Crashes on start"));
+    assert!(!issue.contains("What happened"));
+
+    // Without a body, the editor opens on the template's skeleton
+    let editor = env.itack_home.path().join("editor.sh");
+    fs::write(
+        &editor,
+        "#!/bin/sh\nsed -i 's/<!-- What happened? -->/It hung/' \"$1\"\n",
+    )
+    .unwrap();
+    fs::set_permissions(&editor, fs::Permissions::from_mode(0o755)).unwrap();
+    itack(&env)
+        .args(["create", "Hang", "--template", "bug"])
+        .env("EDITOR", &editor)
+        .current_dir(env.path())
+        .assert()
+        .success();
+    let issue = read_issue_from_data_branch(env.path(), 2).unwrap();
+    assert!(issue.contains("epic: Bugs"));
+    assert!(issue.contains("## What happened\n\nIt hung"));
+
+    itack(&env)
+        .args(["show", "2"])
+        .current_dir(env.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Priority"))
+        .stdout(predicate::str::contains("high"));
+
+    // Priority and labels given on the command line replace the template's
+    itack(&env)
+        .args(["create", "Typo", "--template", "bug", "--body", "Misspelt"])
+        .args(["--priority", "low", "--label", "docs", "--label", "ui"])
+        .current_dir(env.path())
+        .assert()
+        .success();
+    let issue = read_issue_from_data_branch(env.path(), 3).unwrap();
+    assert!(issue.contains("priority: low"));
+    assert!(issue.contains("- docs\n- ui"));
+    assert!(!issue.contains("- bug"));
+
+    itack(&env)
+        .args(["create", "Idea", "--template", "feature"])
+        .current_dir(env.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("available: bug"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_create_edit_reopens_until_valid() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let env = setup_git_repo();
+
+    itack(&env)
+        .arg("init")
+        .current_dir(env.path())
+        .assert()
+        .success();
+
+    // Save garbage first, then a valid issue once the error is shown
+    let home = env.itack_home.path();
+    let editor = home.join("editor.sh");
+    fs::write(
+        &editor,
+        format!(
+            r#"#!/bin/sh
+if [ ! -f {seen} ]; then
+  cp "$1" {skeleton}
+  touch {seen}
+  echo "not an issue" > "$1"
+  exit 0
+fi
+cp "$1" {retry}
+cat > "$1" <<'ISSUE'
+---
+created: 2020-01-01T00:00:00Z
+epic: Docs
+id: 99
+status: done
+---
+# Edited title
+
+Written in the editor
+ISSUE
+"#,
+            seen = home.join("seen").display(),
+            skeleton = home.join("skeleton.md").display(),
+            retry = home.join("retry.md").display(),
+        ),
+    )
+    .unwrap();
+    fs::set_permissions(&editor, fs::Permissions::from_mode(0o755)).unwrap();
+
+    itack(&env)
+        .args(["create", "Draft", "--edit", "--epic", "MVP"])
+        .env("EDITOR", &editor)
+        .current_dir(env.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Created issue #1: Edited title"));
+
+    let skeleton = fs::read_to_string(home.join("skeleton.md")).unwrap();
+    assert!(skeleton.contains("# Draft"));
+    assert!(skeleton.contains("epic: MVP"));
+    let retry = fs::read_to_string(home.join("retry.md")).unwrap();
+    assert!(retry.starts_with("<!-- itack: "));
+    assert!(retry.contains("front matter"));
+    assert!(retry.contains("not an issue"));
+
+    // ID, creation time and status come from itack, not the editor
+    let issue = read_issue_from_data_branch(env.path(), 1).unwrap();
+    assert!(issue.contains("id: 1"));
+    assert!(issue.contains("status: open"));
+    assert!(issue.contains("epic: Docs"));
+    assert!(issue.contains("Written in the editor"));
+    assert!(!issue.contains("itack:"));
+
+    // Saving an empty file aborts
+    fs::write(&editor, "#!/bin/sh\n: > \"$1\"\n").unwrap();
+    itack(&env)
+        .args(["create", "Nothing", "--edit"])
+        .env("EDITOR", &editor)
+        .current_dir(env.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Aborted"));
+    assert!(read_issue_from_data_branch(env.path(), 2).is_none());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_edit_validates_before_committing() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let env = setup_git_repo();
+
+    itack(&env)
+        .arg("init")
+        .current_dir(env.path())
+        .assert()
+        .success();
+    itack(&env)
+        .args(["create", "Original"])
+        .current_dir(env.path())
+        .assert()
+        .success();
+
+    let editor = env.itack_home.path().join("editor.sh");
+    let edit_with = |script: &str| {
+        fs::write(&editor, format!("#!/bin/sh\n{}\n", script)).unwrap();
+        fs::set_permissions(&editor, fs::Permissions::from_mode(0o755)).unwrap();
+        itack(&env)
+            .args(["edit", "1"])
+            .env("EDITOR", &editor)
+            .current_dir(env.path())
+            .assert()
+    };
+    let commits = git_count_commits(env.path(), "data/itack");
+
+    // Saving without changes commits nothing
+    edit_with("exit 0")
+        .success()
+        .stdout(predicate::str::contains("No changes to issue #1"));
+
+    // Rejected edits fail without a terminal to ask on
+    edit_with(r#"sed -i 's/^id: 1$/id: 7/' "$1""#)
+        .failure()
+        .stderr(predicate::str::contains("ID can't be changed"));
+    edit_with(r#"sed -i 's/^status: open$/status: in-progress/' "$1""#)
+        .failure()
+        .stderr(predicate::str::contains("from open to in-progress"));
+    edit_with(r#"sed -i 's/^status: open$/status: done/' "$1""#)
+        .failure()
+        .stderr(predicate::str::contains("'itack done'"));
+    edit_with(r#"echo "no front matter" > "$1""#).failure();
+    assert_eq!(git_count_commits(env.path(), "data/itack"), commits);
+
+    edit_with(r#"sed -i 's/^# Original$/# Renamed/' "$1""#).success();
+    assert_eq!(git_count_commits(env.path(), "data/itack"), commits + 1);
+    let issue = read_issue_from_data_branch(env.path(), 1).unwrap();
+    assert!(issue.contains("# Renamed"));
+}
+
+#[test]
+fn test_set_fields_in_one_commit() {
+    let env = setup_git_repo();
+
+    itack(&env)
+        .arg("init")
+        .current_dir(env.path())
+        .assert()
+        .success();
+    for title in ["First", "Second"] {
+        itack(&env)
+            .args(["create", title])
+            .current_dir(env.path())
+            .assert()
+            .success();
+    }
+    itack(&env)
+        .args(["depend", "2", "1"])
+        .current_dir(env.path())
+        .assert()
+        .success();
+    let commits = git_count_commits(env.path(), "data/itack");
+
+    itack(&env)
+        .args([
+            "set",
+            "1",
+            "title=Renamed first",
+            "epic=MVP",
+            "priority=high",
+            "labels=bug,ui",
+            "assignee=alice",
+        ])
+        .current_dir(env.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Updated issue #1: title, epic, status, assignee, priority, labels",
+        ));
+    assert_eq!(git_count_commits(env.path(), "data/itack"), commits + 1);
+    let log = std::process::Command::new("git")
+        .args(["log", "-1", "--format=%s", "data/itack"])
+        .current_dir(env.path())
+        .output()
+        .unwrap();
+    assert_eq!(
+        String::from_utf8_lossy(&log.stdout).trim(),
+        "Set title, epic, status, assignee, priority, labels on issue #1"
+    );
+    let issue = read_issue_from_data_branch(env.path(), 1).unwrap();
+    assert!(issue.contains("# Renamed first"));
+    assert!(issue.contains("epic: MVP"));
+    assert!(issue.contains("priority: high"));
+    assert!(issue.contains("assignee: alice"));
+    assert!(issue.contains("status: in-progress"));
+
+    // The claim lock moved with the assignee
+    itack(&env)
+        .args(["claim", "1", "bob"])
+        .current_dir(env.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("already claimed by alice"));
+
+    // Invalid values are rejected before anything is committed
+    itack(&env)
+        .args(["set", "1", "epic=Other", "status=finished"])
+        .current_dir(env.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "expected one of: open, in-progress, done, wont-fix",
+        ));
+    itack(&env)
+        .args(["set", "1", "id=5"])
+        .current_dir(env.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Unknown field 'id'"));
+    itack(&env)
+        .args(["set", "1", "epic=MVP"])
+        .current_dir(env.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No changes to issue #1"));
+    assert_eq!(git_count_commits(env.path(), "data/itack"), commits + 1);
+
+    // Closing through set unblocks dependents
+    itack(&env)
+        .args(["set", "1", "status=done"])
+        .current_dir(env.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Unblocked issues:"))
+        .stdout(predicate::str::contains("#2: Second"));
+
+    // Reopening unassigns, and closing already freed the claim
+    itack(&env)
+        .args(["set", "1", "status=open"])
+        .current_dir(env.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Updated issue #1: status, assignee",
+        ));
+    itack(&env)
+        .args(["claim", "1", "bob"])
+        .current_dir(env.path())
+        .assert()
+        .success();
+
+    // Starting work needs someone to do it, who then holds the claim
+    itack(&env)
+        .args(["set", "2", "status=in-progress"])
+        .current_dir(env.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("needs an assignee"));
+    // Issue #1 is open again, so starting #2 is blocked as a claim would be
+    itack(&env)
+        .args(["set", "2", "status=in-progress", "assignee=carol"])
+        .current_dir(env.path())
+        .assert()
+        .code(5);
+    itack(&env)
+        .args(["set", "2", "status=in-progress", "assignee=carol"])
+        .arg("--ignore-deps")
+        .current_dir(env.path())
+        .assert()
+        .success();
+    itack(&env)
+        .args(["claim", "2", "bob", "--ignore-deps"])
+        .current_dir(env.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("already claimed by carol"));
+}
+
+#[test]
+fn test_delete_issue_clears_claim_and_dependencies() {
+    let env = setup_git_repo();
+
+    itack(&env)
+        .arg("init")
+        .current_dir(env.path())
+        .assert()
+        .success();
+    for title in ["Junk", "Real", "Other"] {
+        itack(&env)
+            .args(["create", title])
+            .current_dir(env.path())
+            .assert()
+            .success();
+    }
+    itack(&env)
+        .args(["depend", "2", "1", "3"])
+        .current_dir(env.path())
+        .assert()
+        .success();
+    itack(&env)
+        .args(["claim", "1", "alice"])
+        .current_dir(env.path())
+        .assert()
+        .success();
+    let commits = git_count_commits(env.path(), "data/itack");
+
+    itack(&env)
+        .args(["delete", "1"])
+        .current_dir(env.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Deleted issue #1: Junk"))
+        .stdout(predicate::str::contains(
+            "Removed it from the dependencies of #2",
+        ));
+
+    // One commit removes the file and updates the dependent
+    assert_eq!(git_count_commits(env.path(), "data/itack"), commits + 1);
+    assert!(read_issue_from_data_branch(env.path(), 1).is_none());
+    let dependent = read_issue_from_data_branch(env.path(), 2).unwrap();
+    assert!(dependent.contains("depends_on:\n- 3\n"));
+
+    // The claim is gone and new issues don't reuse the ID
+    let output = itack(&env)
+        .args(["agents", "--json"])
+        .current_dir(env.path())
+        .output()
+        .unwrap();
+    let agents: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let alice = agents
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|agent| agent["name"] == "alice")
+        .unwrap();
+    assert_eq!(alice["claims"], serde_json::json!([]));
+    itack(&env)
+        .args(["create", "Next"])
+        .current_dir(env.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Created issue #4"));
+
+    itack(&env)
+        .args(["delete", "1"])
+        .current_dir(env.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Issue 1 not found"));
+}
+
+#[test]
+fn test_archive_closed_issues() {
+    let env = setup_git_repo();
+
+    itack(&env)
+        .arg("init")
+        .current_dir(env.path())
+        .assert()
+        .success();
+    for title in ["Finished", "Ongoing"] {
+        itack(&env)
+            .args(["create", title])
+            .current_dir(env.path())
+            .assert()
+            .success();
+    }
+    itack(&env)
+        .args(["done", "1"])
+        .current_dir(env.path())
+        .assert()
+        .success();
+
+    // Nothing was closed long enough ago yet
+    itack(&env)
+        .arg("archive")
+        .current_dir(env.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "No issues closed more than 30 days ago",
+        ));
+
+    itack(&env)
+        .args(["archive", "--days", "0"])
+        .current_dir(env.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Archived issue #1: Finished"))
+        .stdout(predicate::str::contains("Ongoing").not());
+
+    let output = std::process::Command::new("git")
+        .args([
+            "ls-tree",
+            "-r",
+            "--name-only",
+            "data/itack",
+            ".itack/archive/",
+        ])
+        .current_dir(env.path())
+        .output()
+        .unwrap();
+    assert!(String::from_utf8_lossy(&output.stdout).contains("-issue-001.md"));
+
+    // Archived issues are left out of list unless asked for, but can still be shown
+    itack(&env)
+        .arg("list")
+        .current_dir(env.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Finished").not());
+    itack(&env)
+        .args(["list", "--include-archived"])
+        .current_dir(env.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Finished"))
+        .stdout(predicate::str::contains("Ongoing"));
+    itack(&env)
+        .args(["search", "finished", "--include-archived"])
+        .current_dir(env.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Finished"));
+    itack(&env)
+        .args(["show", "1"])
+        .current_dir(env.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Finished"));
+
+    // Archiving isn't deleting: exports keep the issue and its history
+    itack(&env)
+        .args(["export", "--history"])
+        .current_dir(env.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"title\":\"Finished\""))
+        .stdout(predicate::str::contains("Mark issue #1 as done"));
+    itack(&env)
+        .args(["watch", "--once"])
+        .current_dir(env.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("deleted").not());
+
+    // Reopening brings the issue back out of the archive
+    itack(&env)
+        .args(["set", "1", "status=open"])
+        .current_dir(env.path())
+        .assert()
+        .success();
+    itack(&env)
+        .arg("list")
+        .current_dir(env.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Finished"));
+    let output = std::process::Command::new("git")
+        .args([
+            "ls-tree",
+            "-r",
+            "--name-only",
+            "data/itack",
+            ".itack/archive/",
+        ])
+        .current_dir(env.path())
+        .output()
+        .unwrap();
+    assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn test_move_issue_to_another_project() {
+    let source = setup_git_repo();
+    let target = setup_git_repo();
+
+    // Both projects share one ITACK_HOME, as they would for a real user
+    let in_target = |args: &[&str]| {
+        let mut cmd = itack(&source);
+        cmd.args(args).current_dir(target.path());
+        cmd
+    };
+    for path in [source.path(), target.path()] {
+        itack(&source)
+            .arg("init")
+            .current_dir(path)
+            .assert()
+            .success();
+    }
+    in_target(&["create", "Already here"]).assert().success();
+
+    for title in ["Parser bug", "Lexer bug", "Unrelated", "Release"] {
+        itack(&source)
+            .args(["create", title])
+            .current_dir(source.path())
+            .assert()
+            .success();
+    }
+    itack(&source)
+        .args(["depend", "2", "1", "3"])
+        .current_dir(source.path())
+        .assert()
+        .success();
+    itack(&source)
+        .args(["depend", "4", "2"])
+        .current_dir(source.path())
+        .assert()
+        .success();
+    itack(&source)
+        .args([
+            "edit",
+            "2",
+            "--body",
+            "Split from #1 and #2, see #3 (not #99)",
+        ])
+        .current_dir(source.path())
+        .assert()
+        .success();
+
+    let target_path = target.path().to_str().unwrap();
+    itack(&source)
+        .args(["move", "1", "--to", target_path])
+        .current_dir(source.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Moved issue #1 to "))
+        .stdout(predicate::str::contains(" as #2"));
+
+    // The dependency on #1 moved along with it; #3 stayed behind
+    itack(&source)
+        .args(["move", "2", "--to", target_path])
+        .current_dir(source.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(" as #3"))
+        .stdout(predicate::str::contains("are not in"))
+        .stdout(predicate::str::contains(": #3"))
+        .stdout(predicate::str::contains("Still depending on it here: #4"))
+        .stdout(predicate::str::contains("Unblocked").not());
+
+    // References follow the moved issues; the rest point back at the source
+    let moved = read_issue_from_data_branch(target.path(), 3).unwrap();
+    assert!(moved.contains("# Lexer bug"));
+    let source_location = source.path().canonicalize().unwrap();
+    assert!(moved.contains(&format!(
+        "Split from #2 and #3, see {}#3 (not #99)",
+        source_location.display()
+    )));
+    assert!(moved.contains("depends_on:\n- 2\n"));
+    assert!(moved.contains("imported_from: itack:"));
+
+    let tombstone = read_issue_from_data_branch(source.path(), 2).unwrap();
+    assert!(tombstone.contains("status: wont-fix"));
+    assert!(tombstone.contains("moved_to: "));
+    assert!(tombstone.contains("#3"));
+
+    itack(&source)
+        .args(["move", "2", "--to", target_path])
+        .current_dir(source.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("already moved"));
+    itack(&source)
+        .args(["move", "3", "--to", source.path().to_str().unwrap()])
+        .current_dir(source.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("already in"));
+
+    // The target allocates IDs after the moved issues
+    in_target(&["create", "Fresh"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Created issue #4"));
+}